@@ -0,0 +1,30 @@
+extern crate r_path_finder;
+use r_path_finder::algorithm::a_star::AStar;
+use r_path_finder::{solve, Grid, Terrain, TitleCoords};
+
+fn main() {
+    println!("====== Example ======");
+    println!("..::Headless A* Algorithm:...");
+
+    let start = TitleCoords { x: 2, y: 2 };
+    let goal = TitleCoords { x: 17, y: 2 };
+
+    let mut builder = Grid::builder(20, 20).obstacle(TitleCoords { x: 10, y: 15 });
+    for y in 0..15 {
+        builder = builder.terrain(TitleCoords { x: 10, y }, Terrain::Water);
+    }
+    let grid = builder.build().expect("Titles are inside the grid");
+
+    match solve(&grid, start, goal, AStar::default()) {
+        Ok(result) if result.is_goal_reachable() => {
+            println!("Path: {:?}", result.path);
+            println!("Cost: {}", result.cost);
+            println!("Steps: {}", result.steps);
+            println!("Visited nodes: {}", result.visited_nodes);
+        }
+        Ok(_) => println!("Goal is unreachable !"),
+        Err(error) => println!("Invalid input: {:?}", error),
+    }
+
+    println!("======== DONE =======");
+}
@@ -1,46 +1,204 @@
-use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, SimulationCoordinator};
+use super::{
+    search_space::SearchSpace, Algorithm, AlgorithmError, Measurable, Pathfinder, Priority,
+    SimulationCoordinator,
+};
 use crate::{
-    algorithm::get_statistics,
-    map::{grid::Grid, TitleCoords},
+    algorithm::{
+        heuristic::{Heuristic, HeuristicSelection},
+        statistics::SearchStatistics,
+    },
+    map::grid::Grid,
 };
 use priority_queue::DoublePriorityQueue;
 use std::collections::HashMap;
 
+/// # TieBreaking
+/// Order of titles with equal priority in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreaking {
+    /// Left to the priority queue
+    #[default]
+    None,
+    /// Prefer titles further from the start (closer to the goal)
+    HigherG,
+    /// Prefer titles with lower heuristic estimation
+    LowerH,
+    /// Prefer the most recently discovered titles
+    Lifo,
+    /// Prefer the earliest discovered titles
+    Fifo,
+}
+
+impl TieBreaking {
+    pub const ALL: [TieBreaking; 5] = [
+        TieBreaking::None,
+        TieBreaking::HigherG,
+        TieBreaking::LowerH,
+        TieBreaking::Lifo,
+        TieBreaking::Fifo,
+    ];
+
+    /// # name
+    /// Tie-breaking strategy name
+    pub fn name(&self) -> &'static str {
+        match self {
+            TieBreaking::None => "None",
+            TieBreaking::HigherG => "Higher g",
+            TieBreaking::LowerH => "Lower h",
+            TieBreaking::Lifo => "LIFO",
+            TieBreaking::Fifo => "FIFO",
+        }
+    }
+
+    /// Secondary priority and insertion order key. Lower values are popped first
+    fn tie_keys(&self, cost: f64, estimation: f64, discovery_id: i64) -> (Priority, i64) {
+        match self {
+            TieBreaking::None => (Priority(0.0), 0),
+            TieBreaking::HigherG => (Priority(-cost), 0),
+            TieBreaking::LowerH => (Priority(estimation), 0),
+            TieBreaking::Lifo => (Priority(0.0), -discovery_id),
+            TieBreaking::Fifo => (Priority(0.0), discovery_id),
+        }
+    }
+}
+
+/// Priority of A*: f = g + w * h, followed by the tie-breaking keys
+type AStarPriority = (Priority, Priority, i64);
+
 /// # A* Algorithm
-/// Mix of Dijkstra and Greedy Best First Search
-#[derive(Default)]
-pub struct AStar {
-    priority_titles: DoublePriorityQueue<TitleCoords, i32>,
-    cost_so_far: HashMap<TitleCoords, i32>,
+/// Mix of Dijkstra and Greedy Best First Search.
+///
+/// Weighted A* inflates the heuristic by the weight (epsilon >= 1).
+/// It expands fewer titles and the found path costs at most epsilon times the optimal one.
+///
+/// Searches the grid by default, or any other search space created with `AStar::new()`
+pub struct AStar<S: SearchSpace = Grid> {
+    priority_titles: DoublePriorityQueue<S::Node, AStarPriority>,
+    cost_so_far: HashMap<S::Node, f64>,
+    start: Option<S::Node>,
     sim_coordinator: SimulationCoordinator,
-    path_finder: Pathfinder,
+    path_finder: Pathfinder<S::Node>,
+    heuristic: HeuristicSelection<S>,
+    heuristic_name: String,
+    weight: f64,
+    tie_breaking: TieBreaking,
+    discovered_titles: i64,
 }
 
-impl Measurable for AStar {
-    fn output_statistics(&self) -> String {
-        get_statistics(
-            self.name().as_str(),
-            self.path_finder.get_path().len(),
-            self.sim_coordinator.steps,
-            self.cost_so_far.len(),
+impl<S: SearchSpace> AStar<S> {
+    /// # new
+    /// A* in the search space guided by its estimation
+    pub fn new() -> Self {
+        Self {
+            priority_titles: DoublePriorityQueue::new(),
+            cost_so_far: HashMap::new(),
+            start: None,
+            sim_coordinator: SimulationCoordinator::default(),
+            path_finder: Pathfinder::default(),
+            heuristic: HeuristicSelection::default(),
+            heuristic_name: String::new(),
+            weight: 1.0,
+            tie_breaking: TieBreaking::default(),
+            discovered_titles: 0,
+        }
+    }
+
+    /// # weighted
+    /// Inflate the heuristic by the weight (epsilon). Weights below 1 are raised to 1
+    pub fn weighted(mut self, weight: f64) -> Self {
+        self.weight = weight.max(1.0);
+        self
+    }
+
+    /// # tie_breaking
+    /// Select the order of titles with equal priority
+    pub fn tie_breaking(mut self, tie_breaking: TieBreaking) -> Self {
+        self.tie_breaking = tie_breaking;
+        self
+    }
+
+    /// # suboptimality_bound
+    /// The found path costs at most this times the optimal one,
+    /// as long as the heuristic is admissible
+    pub fn suboptimality_bound(&self) -> f64 {
+        self.weight
+    }
+
+    fn priority(&self, cost: f64, estimation: f64) -> AStarPriority {
+        let (tie_key, order_key) =
+            self.tie_breaking
+                .tie_keys(cost, estimation, self.discovered_titles);
+        (
+            Priority(cost + self.weight * estimation),
+            tie_key,
+            order_key,
         )
     }
 }
 
-impl Algorithm for AStar {
+impl Default for AStar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AStar {
+    /// # with_heuristic
+    /// Create the algorithm with the heuristic. By default the grid distance metric is used
+    pub fn with_heuristic(heuristic: impl Heuristic + 'static) -> Self {
+        Self {
+            heuristic: HeuristicSelection::new(heuristic),
+            ..Default::default()
+        }
+    }
+}
+
+impl<S: SearchSpace> Measurable for AStar<S> {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(
+            self.name(),
+            &[
+                ("Heuristic", self.heuristic_name.clone()),
+                (
+                    "Weight",
+                    format!(
+                        "{:.1} (cost <= {:.1} x optimal)",
+                        self.weight,
+                        self.suboptimality_bound()
+                    ),
+                ),
+                ("Tie-breaking", self.tie_breaking.name().to_string()),
+            ],
+            &self.sim_coordinator,
+            &self.path_finder,
+        )
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.cost_so_far.len()
+    }
+}
+
+impl<S: SearchSpace> Algorithm<S> for AStar<S> {
     /// # start
     /// A* Algorithm starts.
     ///
     /// Init the algorithm values
-    fn start(&mut self, grid: &mut Grid) -> Result<(), super::AlgorithmError> {
-        if grid.start_title.is_none() || grid.goal_title.is_none() {
-            return Err(AlgorithmError::InvalidInputData);
-        }
-        let start = grid.start_title.unwrap();
+    fn start(&mut self, space: &mut S) -> Result<(), super::AlgorithmError> {
+        let start = space.start_node().ok_or(AlgorithmError::InvalidInputData)?;
 
-        self.priority_titles.push(start, 0);
-        self.cost_so_far.insert(start, 0);
+        self.start = Some(start);
+        let start_priority = self.priority(0.0, 0.0);
+        self.priority_titles.push(start, start_priority);
+        self.sim_coordinator
+            .record_generated(self.priority_titles.len());
+        self.cost_so_far.insert(start, 0.0);
         self.path_finder.add_to_path(start, None);
+        self.heuristic_name = self.heuristic.name(space);
         self.sim_coordinator.start_processing();
 
         Ok(())
@@ -48,7 +206,7 @@ impl Algorithm for AStar {
 
     /// # execute_step
     /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
-    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+    fn execute_step(&mut self, space: &mut S, delta_time: f64) {
         if !self.sim_coordinator.is_ready_to_execute(delta_time) {
             return;
         }
@@ -56,35 +214,39 @@ impl Algorithm for AStar {
         if let Some(current_title) = self.priority_titles.pop_min() {
             self.sim_coordinator.increase_step_count();
 
-            let start = grid.start_title.unwrap();
-            let goal = grid.goal_title.unwrap();
+            let start = self.start.unwrap();
             let current = current_title.0;
             let _priority = current_title.1;
 
             //Early exit
-            if self.sim_coordinator.process_goal_reached(current, goal) {
-                self.path_finder.reconstruct_path(start, goal);
-                for element in self.path_finder.get_path().iter() {
-                    grid.set_trace_back_path(*element);
-                }
+            if self
+                .sim_coordinator
+                .process_goal_test(space.is_goal(current))
+            {
+                self.path_finder.reconstruct_path(start, current);
+                self.path_finder.trace_back_path(space);
                 return;
             }
 
-            grid.mark_visited(current);
+            space.mark_visited(current);
+            self.sim_coordinator.record_expansion();
 
-            let neighbors = grid.get_neighbors(current);
+            let neighbors = space.successors(current);
 
-            for neighbor in neighbors {
-                let new_cost =
-                    *self.cost_so_far.get(&current).unwrap() + grid.cost(current, neighbor);
+            for (neighbor, cost) in neighbors {
+                let new_cost = *self.cost_so_far.get(&current).unwrap() + cost;
 
                 if !self.cost_so_far.contains_key(&neighbor)
                     || new_cost < *self.cost_so_far.get(&neighbor).unwrap()
                 {
-                    grid.mark_process(neighbor);
+                    space.mark_process(neighbor);
                     self.cost_so_far.insert(neighbor, new_cost);
-                    let priority = new_cost + grid.heuristic(current, neighbor);
+                    self.discovered_titles += 1;
+                    let estimation = self.heuristic.estimate(space, neighbor);
+                    let priority = self.priority(new_cost, estimation);
                     self.priority_titles.push(neighbor, priority);
+                    self.sim_coordinator
+                        .record_generated(self.priority_titles.len());
                     self.path_finder.add_to_path(neighbor, Some(current));
                 }
             }
@@ -99,10 +261,17 @@ impl Algorithm for AStar {
     }
 
     /// # reset
-    /// Reset the algorithm processing
-    fn reset(&mut self, grid: &mut Grid) {
-        *self = AStar::default();
-        grid.reset();
+    /// Reset the algorithm processing and clear its search overlay.
+    ///
+    /// The heuristic, weight and tie-breaking are kept
+    fn reset(&mut self, space: &mut S) {
+        *self = Self {
+            heuristic: std::mem::take(&mut self.heuristic),
+            weight: self.weight,
+            tie_breaking: self.tie_breaking,
+            ..Self::new()
+        };
+        space.clear_marks();
     }
 
     /// # has_completed
@@ -116,4 +285,67 @@ impl Algorithm for AStar {
     fn name(&self) -> String {
         "A*".to_string()
     }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the goal is reached
+    fn solution_path(&self) -> &[S::Node] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(S::Node, S::Node)> {
+        self.path_finder.take_parent_updates()
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::solver::solve,
+        map::{SearchState, TitleCoords},
+    };
+
+    #[test]
+    fn a_star_tie_breaking_priority() {
+        let fifo = AStar::default().tie_breaking(TieBreaking::Fifo);
+        let lifo = AStar::default().tie_breaking(TieBreaking::Lifo);
+        let higher_g = AStar::default().tie_breaking(TieBreaking::HigherG);
+
+        assert!(fifo.priority(2.0, 3.0) < fifo.priority(1.0, 5.0));
+        assert_eq!(fifo.priority(2.0, 3.0).0, fifo.priority(4.0, 1.0).0);
+        assert!(higher_g.priority(4.0, 1.0) < higher_g.priority(2.0, 3.0));
+
+        let mut lifo = lifo;
+        let first = lifo.priority(2.0, 3.0);
+        lifo.discovered_titles += 1;
+        assert!(lifo.priority(2.0, 3.0) < first);
+    }
+
+    #[test]
+    fn weighted_a_star() {
+        let mut grid = Grid::new(0, 0, 30, 30, 1);
+        for y in 5..25 {
+            grid.set_obstacle(TitleCoords { x: 15, y }).unwrap();
+        }
+        let start = TitleCoords { x: 2, y: 15 };
+        let goal = TitleCoords { x: 28, y: 15 };
+
+        let optimal = solve(&grid, start, goal, AStar::default()).unwrap();
+        let weighted_a_star = AStar::default()
+            .weighted(3.0)
+            .tie_breaking(TieBreaking::HigherG);
+        assert_eq!(3.0, weighted_a_star.suboptimality_bound());
+        let weighted = solve(&grid, start, goal, weighted_a_star).unwrap();
+
+        assert!(weighted.cost >= optimal.cost);
+        assert!(weighted.cost <= 3.0 * optimal.cost);
+        assert!(weighted.steps < optimal.steps);
+        assert!(
+            weighted.overlay.count(SearchState::Visited)
+                < optimal.overlay.count(SearchState::Visited)
+        );
+        assert_eq!(1.0, AStar::default().weighted(0.5).suboptimality_bound());
+    }
 }
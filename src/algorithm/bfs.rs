@@ -1,44 +1,69 @@
-use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, SimulationCoordinator};
-use crate::{
-    algorithm::get_statistics,
-    map::{grid::Grid, TitleCoords},
+use super::{
+    search_space::SearchSpace, Algorithm, AlgorithmError, Measurable, Pathfinder,
+    SimulationCoordinator,
 };
-use std::collections::VecDeque;
+use crate::{algorithm::statistics::SearchStatistics, map::grid::Grid};
+use std::collections::{HashSet, VecDeque};
 
 /// # Breadth-First Search Algorithm
 /// The algorithm explores titles equally in all directions.
-#[derive(Default)]
-pub struct Bfs {
-    title_processing_queue: VecDeque<TitleCoords>,
-    visited_titles: Vec<TitleCoords>,
+///
+/// Searches the grid by default, or any other search space created with `Bfs::new()`
+pub struct Bfs<S: SearchSpace = Grid> {
+    title_processing_queue: VecDeque<S::Node>,
+    visited_titles: HashSet<S::Node>,
+    start: Option<S::Node>,
     sim_coordinator: SimulationCoordinator,
-    path_finder: Pathfinder,
+    path_finder: Pathfinder<S::Node>,
 }
 
-impl Measurable for Bfs {
-    fn output_statistics(&self) -> String {
-        get_statistics(
-            self.name().as_str(),
-            self.path_finder.get_path().len(),
-            self.sim_coordinator.steps,
-            self.visited_titles.len(),
-        )
+impl<S: SearchSpace> Bfs<S> {
+    /// # new
+    /// Breadth-First Search in the search space
+    pub fn new() -> Self {
+        Self {
+            title_processing_queue: VecDeque::new(),
+            visited_titles: HashSet::new(),
+            start: None,
+            sim_coordinator: SimulationCoordinator::default(),
+            path_finder: Pathfinder::default(),
+        }
+    }
+}
+
+impl Default for Bfs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SearchSpace> Measurable for Bfs<S> {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(self.name(), &[], &self.sim_coordinator, &self.path_finder)
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.visited_titles.len()
     }
 }
 
-impl Algorithm for Bfs {
+impl<S: SearchSpace> Algorithm<S> for Bfs<S> {
     /// # start
     /// BFS Algorithm starts.
     ///
     /// Init the algorithm values
-    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
-        if grid.start_title.is_none() || grid.goal_title.is_none() {
-            return Err(AlgorithmError::InvalidInputData);
-        }
-        let start = grid.start_title.unwrap();
+    fn start(&mut self, space: &mut S) -> Result<(), AlgorithmError> {
+        let start = space.start_node().ok_or(AlgorithmError::InvalidInputData)?;
 
+        self.start = Some(start);
         self.title_processing_queue.push_back(start);
-        self.visited_titles.push(start);
+        self.sim_coordinator
+            .record_generated(self.title_processing_queue.len());
+        self.visited_titles.insert(start);
         self.path_finder.add_to_path(start, None);
         self.sim_coordinator.start_processing();
         Ok(())
@@ -46,31 +71,33 @@ impl Algorithm for Bfs {
 
     /// # execute_step
     /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
-    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+    fn execute_step(&mut self, space: &mut S, delta_time: f64) {
         if !self.sim_coordinator.is_ready_to_execute(delta_time) {
             return;
         }
         if let Some(current) = self.title_processing_queue.pop_front() {
             self.sim_coordinator.increase_step_count();
-            let goal = grid.goal_title.unwrap();
-            let start = grid.start_title.unwrap();
-
-            if self.sim_coordinator.process_goal_reached(current, goal) {
-                self.path_finder.reconstruct_path(start, goal);
-                for element in self.path_finder.get_path().iter() {
-                    grid.set_trace_back_path(*element);
-                }
+            let start = self.start.unwrap();
+
+            if self
+                .sim_coordinator
+                .process_goal_test(space.is_goal(current))
+            {
+                self.path_finder.reconstruct_path(start, current);
+                self.path_finder.trace_back_path(space);
                 return;
             }
-            grid.mark_visited(current);
-            let neighboring_titles = grid.get_neighbors(current);
+            space.mark_visited(current);
+            self.sim_coordinator.record_expansion();
+            let neighboring_titles = space.successors(current);
 
-            for neighbor_title in neighboring_titles {
-                if !self.visited_titles.contains(&neighbor_title) {
-                    grid.mark_process(neighbor_title);
-                    self.visited_titles.push(neighbor_title);
+            for (neighbor_title, _cost) in neighboring_titles {
+                if self.visited_titles.insert(neighbor_title) {
+                    space.mark_process(neighbor_title);
                     self.path_finder.add_to_path(neighbor_title, Some(current));
                     self.title_processing_queue.push_back(neighbor_title);
+                    self.sim_coordinator
+                        .record_generated(self.title_processing_queue.len());
                 }
             }
             // Check if goal is unreachable
@@ -84,10 +111,10 @@ impl Algorithm for Bfs {
     }
 
     /// # reset
-    /// Reset the algorithm processing
-    fn reset(&mut self, grid: &mut Grid) {
-        *self = Bfs::default();
-        grid.reset();
+    /// Reset the algorithm processing and clear its search overlay
+    fn reset(&mut self, space: &mut S) {
+        *self = Bfs::new();
+        space.clear_marks();
     }
 
     /// # has_completed
@@ -101,13 +128,25 @@ impl Algorithm for Bfs {
     fn name(&self) -> String {
         "Breadth First Search".to_string()
     }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the goal is reached
+    fn solution_path(&self) -> &[S::Node] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(S::Node, S::Node)> {
+        self.path_finder.take_parent_updates()
+    }
 }
 
 #[cfg(test)]
 mod unit_test {
 
     use super::*;
-    use crate::algorithm::ONE_ITERATION_TIME_SEC;
+    use crate::{algorithm::ONE_ITERATION_TIME_SEC, map::TitleCoords};
     #[test]
     fn bfs_start() {
         let mut bfs = Bfs::default();
@@ -123,7 +162,7 @@ mod unit_test {
         assert!(bfs.start(&mut grid).is_ok());
 
         assert_eq!(bfs.title_processing_queue[0], start);
-        assert_eq!(bfs.visited_titles[0], start);
+        assert!(bfs.visited_titles.contains(&start));
         assert_eq!(*bfs.path_finder.path.get(&start).unwrap(), None);
     }
 
@@ -161,8 +200,8 @@ mod unit_test {
         ];
 
         assert_eq!(bfs.visited_titles.len(), 5);
-        for (id, item) in expected_visited_tiles.iter().enumerate() {
-            assert_eq!(*item, bfs.visited_titles[id]);
+        for item in expected_visited_tiles.iter() {
+            assert!(bfs.visited_titles.contains(item));
         }
 
         assert_eq!(bfs.title_processing_queue.len(), 4);
@@ -0,0 +1,387 @@
+use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, Priority, SimulationCoordinator};
+use crate::{
+    algorithm::statistics::SearchStatistics,
+    map::{grid::Grid, TitleCoords},
+};
+use priority_queue::DoublePriorityQueue;
+use std::collections::HashMap;
+
+/// Order of the titles in both frontiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Strategy {
+    #[default]
+    Bfs,
+    Dijkstra,
+    AStar,
+}
+
+impl Strategy {
+    /// Cost of the move between neighbours. Bfs counts the moves only
+    fn step_cost(&self, grid: &Grid, from: TitleCoords, to: TitleCoords) -> f64 {
+        match self {
+            Strategy::Bfs => 1.0,
+            Strategy::Dijkstra | Strategy::AStar => grid.cost(from, to),
+        }
+    }
+
+    /// Priority of the title in the frontier searching towards the target
+    fn priority(&self, grid: &Grid, cost: f64, title: TitleCoords, target: TitleCoords) -> f64 {
+        match self {
+            Strategy::Bfs | Strategy::Dijkstra => cost,
+            Strategy::AStar => cost + grid.heuristic(title, target),
+        }
+    }
+}
+
+/// One of the two searches: from the start or from the goal
+#[derive(Default)]
+struct Frontier {
+    priority_titles: DoublePriorityQueue<TitleCoords, Priority>,
+    cost_so_far: HashMap<TitleCoords, f64>,
+    path_finder: Pathfinder,
+}
+
+impl Frontier {
+    fn open(&mut self, title: TitleCoords) {
+        self.priority_titles.push(title, Priority(0.0));
+        self.cost_so_far.insert(title, 0.0);
+        self.path_finder.add_to_path(title, None);
+    }
+
+    fn min_priority(&self) -> Option<f64> {
+        self.priority_titles
+            .peek_min()
+            .map(|(_, priority)| priority.0)
+    }
+}
+
+/// # Bidirectional Search
+/// Runs two searches at once, one from the start and one backwards from the goal,
+/// and joins their half-paths where the frontiers meet. The frontiers are expanded in turns.
+///
+/// The search stops once no shorter path can pass through the frontiers: for Bfs and Dijkstra when
+/// the sum of the lowest costs in both frontiers reaches the best path found, for A* when
+/// the lowest priority of either frontier does.
+///
+/// Bidirectional Bfs is created by default.
+#[derive(Default)]
+pub struct BidirectionalSearch {
+    strategy: Strategy,
+    forward: Frontier,
+    backward: Frontier,
+    /// Cost of the best path found so far and the title where its halves meet
+    best_meeting: Option<(f64, TitleCoords)>,
+    is_backward_turn: bool,
+    sim_coordinator: SimulationCoordinator,
+}
+
+impl BidirectionalSearch {
+    /// # bfs
+    /// Bidirectional Breadth First Search
+    pub fn bfs() -> Self {
+        Self::default()
+    }
+
+    /// # dijkstra
+    /// Bidirectional Dijkstra
+    pub fn dijkstra() -> Self {
+        Self {
+            strategy: Strategy::Dijkstra,
+            ..Default::default()
+        }
+    }
+
+    /// # a_star
+    /// Bidirectional A* with the grid heuristic towards the goal and back towards the start
+    pub fn a_star() -> Self {
+        Self {
+            strategy: Strategy::AStar,
+            ..Default::default()
+        }
+    }
+
+    /// Check the meet-in-the-middle termination condition
+    fn should_stop(&self) -> bool {
+        let (Some(forward), Some(backward)) =
+            (self.forward.min_priority(), self.backward.min_priority())
+        else {
+            return true;
+        };
+        match (self.best_meeting, self.strategy) {
+            (None, _) => false,
+            (Some((best_cost, _)), Strategy::AStar) => forward.max(backward) >= best_cost,
+            (Some((best_cost, _)), _) => forward + backward >= best_cost,
+        }
+    }
+
+    /// Expand the best title of the forward or the backward frontier
+    fn expand(&mut self, grid: &mut Grid, is_forward: bool) {
+        let (frontier, other, target) = if is_forward {
+            (&mut self.forward, &self.backward, grid.goal_title.unwrap())
+        } else {
+            (&mut self.backward, &self.forward, grid.start_title.unwrap())
+        };
+        let Some((current, _priority)) = frontier.priority_titles.pop_min() else {
+            return;
+        };
+
+        if is_forward {
+            grid.mark_visited(current);
+        } else {
+            grid.mark_backward_visited(current);
+        }
+        self.sim_coordinator.record_expansion();
+
+        let current_cost = *frontier.cost_so_far.get(&current).unwrap();
+        for neighbor in grid.get_neighbors(current) {
+            // The backward search walks the moves in the opposite direction
+            let step_cost = if is_forward {
+                self.strategy.step_cost(grid, current, neighbor)
+            } else {
+                self.strategy.step_cost(grid, neighbor, current)
+            };
+            let new_cost = current_cost + step_cost;
+
+            if !frontier.cost_so_far.contains_key(&neighbor)
+                || new_cost < *frontier.cost_so_far.get(&neighbor).unwrap()
+            {
+                if is_forward {
+                    grid.mark_process(neighbor);
+                } else {
+                    grid.mark_backward_process(neighbor);
+                }
+                frontier.cost_so_far.insert(neighbor, new_cost);
+                let priority = self.strategy.priority(grid, new_cost, neighbor, target);
+                frontier.priority_titles.push(neighbor, Priority(priority));
+                self.sim_coordinator
+                    .record_generated(frontier.priority_titles.len());
+                frontier.path_finder.add_to_path(neighbor, Some(current));
+
+                if let Some(other_cost) = other.cost_so_far.get(&neighbor) {
+                    let path_cost = new_cost + other_cost;
+                    if self
+                        .best_meeting
+                        .is_none_or(|(best_cost, _)| path_cost < best_cost)
+                    {
+                        self.best_meeting = Some((path_cost, neighbor));
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, grid: &mut Grid) {
+        self.sim_coordinator.has_completed = true;
+        self.sim_coordinator.stop_processing();
+
+        if let Some((_, meeting)) = self.best_meeting {
+            let start = grid.start_title.unwrap();
+            let goal = grid.goal_title.unwrap();
+            self.forward.path_finder.reconstruct_joined_path(
+                &self.backward.path_finder,
+                start,
+                meeting,
+                goal,
+            );
+            self.forward.path_finder.trace_back_path(grid);
+        }
+    }
+}
+
+impl Measurable for BidirectionalSearch {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(
+            self.name(),
+            &[],
+            &self.sim_coordinator,
+            &self.forward.path_finder,
+        )
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.forward.cost_so_far.len() + self.backward.cost_so_far.len()
+    }
+}
+
+impl Algorithm for BidirectionalSearch {
+    /// # start
+    /// Bidirectional search starts.
+    ///
+    /// Open the forward frontier in the start and the backward one in the goal
+    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
+        if grid.start_title.is_none() || grid.goal_title.is_none() {
+            return Err(AlgorithmError::InvalidInputData);
+        }
+        let start = grid.start_title.unwrap();
+        let goal = grid.goal_title.unwrap();
+
+        self.forward.open(start);
+        self.backward.open(goal);
+        self.sim_coordinator.record_generated(1);
+        self.sim_coordinator.record_generated(1);
+        if start == goal {
+            self.best_meeting = Some((0.0, start));
+        }
+        self.sim_coordinator.start_processing();
+
+        Ok(())
+    }
+
+    /// # execute_step
+    /// Algorithm processing update every ONE_ITERATION_TIME_SEC until the frontiers meet
+    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+        if !self.sim_coordinator.is_ready_to_execute(delta_time) {
+            return;
+        }
+
+        if self.should_stop() {
+            self.finish(grid);
+            return;
+        }
+        self.sim_coordinator.increase_step_count();
+
+        self.expand(grid, !self.is_backward_turn);
+        self.is_backward_turn = !self.is_backward_turn;
+    }
+
+    /// # reset
+    /// Reset the algorithm processing and clear its search overlay.
+    ///
+    /// The variant (Bfs, Dijkstra or A*) is kept
+    fn reset(&mut self, grid: &mut Grid) {
+        *self = Self {
+            strategy: self.strategy,
+            ..Default::default()
+        };
+        grid.clear_overlay();
+    }
+
+    /// # has_completed
+    /// Check if processing is done
+    fn has_completed(&self) -> bool {
+        self.sim_coordinator.has_completed
+    }
+
+    /// # name
+    /// Algorithm name
+    fn name(&self) -> String {
+        match self.strategy {
+            Strategy::Bfs => "Bidirectional Bfs".to_string(),
+            Strategy::Dijkstra => "Bidirectional Dijkstra".to_string(),
+            Strategy::AStar => "Bidirectional A*".to_string(),
+        }
+    }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the frontiers meet
+    fn solution_path(&self) -> &[TitleCoords] {
+        self.forward.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set by both frontiers since the last call.
+    /// The parents of the backward frontier point towards the goal
+    fn take_parent_updates(&mut self) -> Vec<(TitleCoords, TitleCoords)> {
+        let mut parent_updates = self.forward.path_finder.take_parent_updates();
+        parent_updates.extend(self.backward.path_finder.take_parent_updates());
+        parent_updates
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{bfs::Bfs, dijkstra::Dijkstra, solver::solve},
+        map::{Neighbourhood, SearchState, Terrain},
+    };
+
+    fn terrain_grid(neighbourhood: Neighbourhood) -> Grid {
+        let mut grid = Grid::new(0, 0, 25, 20, 1);
+        grid.set_neighbourhood(neighbourhood);
+        // Deterministic scattered obstacles and terrain
+        let mut seed: u32 = 11;
+        for x in 0..25 {
+            for y in 0..20 {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let title = TitleCoords { x, y };
+                match (seed >> 16) % 8 {
+                    0 | 1 => grid.set_obstacle(title).unwrap(),
+                    2 => grid.set_terrain(title, Terrain::Mud),
+                    3 => grid.set_terrain(title, Terrain::Water),
+                    _ => {}
+                }
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn bidirectional_search_is_optimal() {
+        let pairs = [
+            (TitleCoords { x: 0, y: 0 }, TitleCoords { x: 24, y: 19 }),
+            (TitleCoords { x: 3, y: 17 }, TitleCoords { x: 21, y: 2 }),
+            (TitleCoords { x: 12, y: 1 }, TitleCoords { x: 12, y: 18 }),
+        ];
+
+        let mut reachable_pairs = 0;
+        for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight] {
+            let mut grid = terrain_grid(neighbourhood);
+            for (start, goal) in pairs {
+                grid.clear_title(start).unwrap();
+                grid.clear_title(goal).unwrap();
+
+                let bfs = solve(&grid, start, goal, Bfs::default()).unwrap();
+                let dijkstra = solve(&grid, start, goal, Dijkstra::default()).unwrap();
+                let bidirectional_bfs =
+                    solve(&grid, start, goal, BidirectionalSearch::bfs()).unwrap();
+                let bidirectional_dijkstra =
+                    solve(&grid, start, goal, BidirectionalSearch::dijkstra()).unwrap();
+                let bidirectional_a_star =
+                    solve(&grid, start, goal, BidirectionalSearch::a_star()).unwrap();
+
+                assert_eq!(
+                    dijkstra.is_goal_reachable(),
+                    bidirectional_a_star.is_goal_reachable()
+                );
+                if !dijkstra.is_goal_reachable() {
+                    continue;
+                }
+                reachable_pairs += 1;
+                assert_eq!(bfs.path.len(), bidirectional_bfs.path.len());
+                assert!((dijkstra.cost - bidirectional_dijkstra.cost).abs() < 1e-9);
+                assert!((dijkstra.cost - bidirectional_a_star.cost).abs() < 1e-9);
+                for result in [&bidirectional_bfs, &bidirectional_a_star] {
+                    assert_eq!(start, result.path[0]);
+                    assert_eq!(goal, *result.path.last().unwrap());
+                }
+            }
+        }
+        assert!(reachable_pairs > 2);
+    }
+
+    #[test]
+    fn bidirectional_search_frontiers() {
+        let mut grid = Grid::new(0, 0, 10, 3, 1);
+        let start = TitleCoords { x: 0, y: 1 };
+        let goal = TitleCoords { x: 9, y: 1 };
+
+        let result = solve(&grid, start, goal, BidirectionalSearch::dijkstra()).unwrap();
+        assert_eq!(9.0, result.cost);
+        assert!(result.overlay.count(SearchState::Path) > 0);
+        assert!(result.overlay.count(SearchState::BackwardVisited) > 0);
+
+        for y in 0..3 {
+            grid.set_obstacle(TitleCoords { x: 5, y }).unwrap();
+        }
+        let result = solve(&grid, start, goal, BidirectionalSearch::a_star()).unwrap();
+        assert!(!result.is_goal_reachable());
+
+        let result = solve(&grid, start, start, BidirectionalSearch::bfs()).unwrap();
+        assert_eq!(vec![start], result.path);
+    }
+}
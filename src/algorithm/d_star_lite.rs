@@ -0,0 +1,480 @@
+use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, Priority, SimulationCoordinator};
+use crate::{
+    algorithm::statistics::SearchStatistics,
+    map::{grid::Grid, TitleCoords},
+};
+use priority_queue::DoublePriorityQueue;
+use std::collections::HashMap;
+
+/// Key of D* Lite: (min(g, rhs) + h + km, min(g, rhs))
+type DStarKey = (Priority, Priority);
+/// Keys closer than this are equal. The key modifier sums the heuristic of every start move,
+/// so the same cost reached along different moves differs in the last bits
+const KEY_TOLERANCE: f64 = 1e-9;
+
+/// # D* Lite Algorithm
+/// Incremental search which plans backwards from the goal and keeps its state
+/// when the map changes.
+///
+/// Titles blocked, freed or repainted while the search runs or after it has finished are
+/// picked up on the next step. Only the titles whose cost to the goal changed are expanded
+/// again, so the path is repaired instead of searched from scratch. Moving the start
+/// keeps the search state as well, moving the goal starts a new plan.
+#[derive(Default)]
+pub struct DStarLite {
+    priority_titles: DoublePriorityQueue<TitleCoords, DStarKey>,
+    /// Cost from the title to the goal
+    g: HashMap<TitleCoords, f64>,
+    /// One-step lookahead of the cost to the goal
+    rhs: HashMap<TitleCoords, f64>,
+    /// Key modifier - heuristic distance the start has moved since the first plan
+    key_modifier: f64,
+    last_start: Option<TitleCoords>,
+    goal: Option<TitleCoords>,
+    replans: u32,
+    initial_plan_expansions: u32,
+    plan_expansions: u32,
+    sim_coordinator: SimulationCoordinator,
+    path_finder: Pathfinder,
+}
+
+impl DStarLite {
+    /// # last_repair_expansions
+    /// Titles expanded to repair the path after the last map change.
+    /// Zero until the map changes
+    pub fn last_repair_expansions(&self) -> u32 {
+        if self.replans == 0 {
+            return 0;
+        }
+        self.plan_expansions
+    }
+
+    /// # initial_plan_expansions
+    /// Titles expanded by the first plan
+    pub fn initial_plan_expansions(&self) -> u32 {
+        self.initial_plan_expansions
+    }
+
+    fn g(&self, title: TitleCoords) -> f64 {
+        *self.g.get(&title).unwrap_or(&f64::INFINITY)
+    }
+
+    fn rhs(&self, title: TitleCoords) -> f64 {
+        *self.rhs.get(&title).unwrap_or(&f64::INFINITY)
+    }
+
+    fn key(&self, grid: &Grid, title: TitleCoords, start: TitleCoords) -> DStarKey {
+        let cost = self.g(title).min(self.rhs(title));
+        (
+            Priority(cost + grid.heuristic(start, title) + self.key_modifier),
+            Priority(cost),
+        )
+    }
+
+    /// Titles reachable in one move. Moves are reversible, so they are the predecessors too
+    fn successors(grid: &Grid, title: TitleCoords) -> Vec<TitleCoords> {
+        if grid.is_obstacle(title) {
+            return Vec::new();
+        }
+        grid.get_neighbors(title)
+    }
+
+    /// Clear the search state and plan from the goal again
+    fn initialize(&mut self, grid: &mut Grid, start: TitleCoords, goal: TitleCoords) {
+        self.priority_titles.clear();
+        self.g.clear();
+        self.rhs.clear();
+        self.key_modifier = 0.0;
+        self.last_start = Some(start);
+        self.goal = Some(goal);
+
+        self.rhs.insert(goal, 0.0);
+        let key = self.key(grid, goal, start);
+        self.priority_titles.push(goal, key);
+        self.sim_coordinator
+            .record_generated(self.priority_titles.len());
+    }
+
+    fn update_vertex(&mut self, grid: &mut Grid, title: TitleCoords, start: TitleCoords) {
+        if Some(title) != self.goal {
+            let rhs = Self::successors(grid, title)
+                .into_iter()
+                .map(|successor| grid.cost(title, successor) + self.g(successor))
+                .fold(f64::INFINITY, f64::min);
+            self.rhs.insert(title, rhs);
+        }
+        self.priority_titles.remove(&title);
+        if self.g(title) != self.rhs(title) {
+            let key = self.key(grid, title, start);
+            self.priority_titles.push(title, key);
+            self.sim_coordinator
+                .record_generated(self.priority_titles.len());
+            grid.mark_process(title);
+        }
+    }
+
+    /// Pick up the map edits and the moved start or goal since the last step.
+    ///
+    /// Returns true if the search state was repaired
+    fn apply_changes(&mut self, grid: &mut Grid, start: TitleCoords, goal: TitleCoords) -> bool {
+        let changed_titles = grid.take_changed_titles();
+        if self.goal != Some(goal) {
+            self.initialize(grid, start, goal);
+            return true;
+        }
+        let last_start = self.last_start.unwrap_or(start);
+        if changed_titles.is_empty() && last_start == start {
+            return false;
+        }
+
+        self.key_modifier += grid.heuristic(last_start, start);
+        self.last_start = Some(start);
+        // A changed title affects the moves into it and, without corner cutting,
+        // the diagonal moves around it
+        for title in changed_titles {
+            self.update_vertex(grid, title, start);
+            for surrounding in surrounding_titles(grid, title) {
+                self.update_vertex(grid, surrounding, start);
+            }
+        }
+        true
+    }
+
+    /// Planning is done when no queued title can lower the cost of the start.
+    /// The cost of the start itself is known from its lookahead (rhs).
+    ///
+    /// Titles whose key ties with the start are expanded too, a stale cost among them
+    /// would be followed by the path otherwise
+    fn is_plan_consistent(&self, grid: &Grid, start: TitleCoords) -> bool {
+        let start_key = self.key(grid, start, start);
+        match self.priority_titles.peek_min() {
+            Some((_, top_key)) if top_key.0 .0 <= start_key.0 .0 + KEY_TOLERANCE => false,
+            _ => self.rhs(start) <= self.g(start),
+        }
+    }
+
+    /// Follow the cheapest moves from the start to the goal
+    fn finish_plan(&mut self, grid: &mut Grid, start: TitleCoords, goal: TitleCoords) {
+        self.sim_coordinator.has_completed = true;
+        self.sim_coordinator.stop_processing();
+        if self.replans == 0 {
+            self.initial_plan_expansions = self.plan_expansions;
+        }
+
+        self.path_finder = Pathfinder::default();
+        if self.rhs(start).is_infinite() {
+            return;
+        }
+        let mut path_finder = Pathfinder::default();
+        path_finder.add_to_path(start, None);
+        let mut current = start;
+        let max_path_length = (grid.columns * grid.rows) as usize;
+        for _ in 0..max_path_length {
+            if current == goal {
+                break;
+            }
+            let next = Self::successors(grid, current)
+                .into_iter()
+                .map(|successor| (successor, grid.cost(current, successor) + self.g(successor)))
+                .filter(|(_, cost)| cost.is_finite())
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            let Some((next, _)) = next else {
+                return;
+            };
+            path_finder.add_to_path(next, Some(current));
+            current = next;
+        }
+        if current != goal {
+            return;
+        }
+        path_finder.reconstruct_path(start, goal);
+        path_finder.trace_back_path(grid);
+        self.path_finder = path_finder;
+    }
+}
+
+/// Titles around the title inside the grid, including the obstacles
+fn surrounding_titles(grid: &Grid, title: TitleCoords) -> Vec<TitleCoords> {
+    let mut titles = Vec::with_capacity(8);
+    for dx in -1..=1_isize {
+        for dy in -1..=1_isize {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let (Some(x), Some(y)) = (
+                title.x.checked_add_signed(dx),
+                title.y.checked_add_signed(dy),
+            ) else {
+                continue;
+            };
+            let surrounding = TitleCoords { x, y };
+            if grid.is_within_bounds(surrounding) {
+                titles.push(surrounding);
+            }
+        }
+    }
+    titles
+}
+
+impl Measurable for DStarLite {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(
+            self.name(),
+            &[
+                ("Replans", self.replans.to_string()),
+                (
+                    "First plan expansions",
+                    self.initial_plan_expansions.to_string(),
+                ),
+                (
+                    "Last repair expansions",
+                    self.last_repair_expansions().to_string(),
+                ),
+            ],
+            &self.sim_coordinator,
+            &self.path_finder,
+        )
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.g.len()
+    }
+}
+
+impl Algorithm for DStarLite {
+    /// # start
+    /// D* Lite Algorithm starts.
+    ///
+    /// Init the algorithm values. The search starts from the goal
+    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
+        if grid.start_title.is_none() || grid.goal_title.is_none() {
+            return Err(AlgorithmError::InvalidInputData);
+        }
+        // Edits made before the start are part of the first plan
+        grid.take_changed_titles();
+        self.initialize(grid, grid.start_title.unwrap(), grid.goal_title.unwrap());
+        self.sim_coordinator.start_processing();
+
+        Ok(())
+    }
+
+    /// # execute_step
+    /// Algorithm processing update every ONE_ITERATION_TIME_SEC until the plan is consistent.
+    ///
+    /// Map changes are checked on every call, also after the plan has finished
+    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+        let (Some(start), Some(goal)) = (grid.start_title, grid.goal_title) else {
+            return;
+        };
+        if self.goal.is_none() {
+            return;
+        }
+        if self.apply_changes(grid, start, goal) {
+            grid.clear_overlay();
+            self.replans += 1;
+            self.plan_expansions = 0;
+            self.sim_coordinator.has_completed = false;
+            self.sim_coordinator.start_processing();
+        }
+
+        if !self.sim_coordinator.is_ready_to_execute(delta_time) {
+            return;
+        }
+        self.sim_coordinator.increase_step_count();
+
+        if self.is_plan_consistent(grid, start) {
+            self.finish_plan(grid, start, goal);
+            return;
+        }
+
+        let (current, old_key) = self.priority_titles.pop_min().unwrap();
+        let new_key = self.key(grid, current, start);
+        if old_key < new_key {
+            self.priority_titles.push(current, new_key);
+            return;
+        }
+
+        grid.mark_visited(current);
+        self.sim_coordinator.record_expansion();
+        self.plan_expansions += 1;
+
+        if self.g(current) > self.rhs(current) {
+            self.g.insert(current, self.rhs(current));
+        } else {
+            self.g.insert(current, f64::INFINITY);
+            self.update_vertex(grid, current, start);
+        }
+        for predecessor in Self::successors(grid, current) {
+            self.update_vertex(grid, predecessor, start);
+        }
+    }
+
+    /// # reset
+    /// Reset the algorithm processing and clear its search overlay
+    fn reset(&mut self, grid: &mut Grid) {
+        *self = Self::default();
+        grid.clear_overlay();
+    }
+
+    /// # has_completed
+    /// Check if processing is done
+    fn has_completed(&self) -> bool {
+        self.sim_coordinator.has_completed
+    }
+
+    /// # name
+    /// Algorithm name
+    fn name(&self) -> String {
+        "D* Lite".to_string()
+    }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the plan is finished
+    fn solution_path(&self) -> &[TitleCoords] {
+        self.path_finder.get_path()
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{a_star::AStar, dijkstra::Dijkstra, solver::solve, ONE_ITERATION_TIME_SEC},
+        map::{Neighbourhood, Terrain},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn run_until_completed(d_star_lite: &mut DStarLite, grid: &mut Grid) {
+        d_star_lite.execute_step(grid, ONE_ITERATION_TIME_SEC);
+        while !d_star_lite.has_completed() {
+            d_star_lite.execute_step(grid, ONE_ITERATION_TIME_SEC);
+        }
+    }
+
+    #[test]
+    fn d_star_lite_repairs_path() {
+        let mut grid = Grid::new(0, 0, 30, 30, 1);
+        for y in 5..25 {
+            grid.set_obstacle(TitleCoords { x: 15, y }).unwrap();
+        }
+        let start = TitleCoords { x: 2, y: 15 };
+        let goal = TitleCoords { x: 28, y: 15 };
+        grid.set_start(start).unwrap();
+        grid.set_goal(goal).unwrap();
+
+        let mut d_star_lite = DStarLite::default();
+        d_star_lite.start(&mut grid).unwrap();
+        run_until_completed(&mut d_star_lite, &mut grid);
+        let optimal = solve(&grid, start, goal, AStar::default()).unwrap();
+        assert!((optimal.cost - d_star_lite.statistics().path_cost).abs() < 1e-9);
+        assert_eq!(0, d_star_lite.last_repair_expansions());
+
+        // Block the current path close to the goal
+        let blocked = d_star_lite.solution_path()[3];
+        grid.set_obstacle(blocked).unwrap();
+        run_until_completed(&mut d_star_lite, &mut grid);
+        assert!(!d_star_lite.solution_path().contains(&blocked));
+
+        let optimal = solve(&grid, start, goal, AStar::default()).unwrap();
+        let statistics = d_star_lite.statistics();
+        assert_eq!(1, d_star_lite.replans);
+        assert!((optimal.cost - statistics.path_cost).abs() < 1e-9);
+        assert!(d_star_lite.last_repair_expansions() < optimal.statistics.expanded_nodes);
+        assert!(d_star_lite.last_repair_expansions() < d_star_lite.initial_plan_expansions());
+
+        // Free the wall, the path goes straight
+        for y in 5..25 {
+            grid.clear_title(TitleCoords { x: 15, y }).unwrap();
+        }
+        grid.clear_title(blocked).unwrap();
+        run_until_completed(&mut d_star_lite, &mut grid);
+        assert_eq!(26.0, d_star_lite.statistics().path_cost);
+
+        // Wall the goal off
+        for title in surrounding_titles(&grid, goal) {
+            grid.set_obstacle(title).unwrap();
+        }
+        run_until_completed(&mut d_star_lite, &mut grid);
+        assert!(d_star_lite.solution_path().is_empty());
+        assert!(!d_star_lite.statistics().goal_reachable);
+    }
+
+    #[test]
+    fn d_star_lite_repairs_random_edits() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let random_title = |rng: &mut StdRng| TitleCoords {
+            x: rng.random_range(0..16),
+            y: rng.random_range(0..16),
+        };
+        for neighbourhood in [
+            Neighbourhood::Eight,
+            Neighbourhood::EightNoCornerCutting,
+            Neighbourhood::Four,
+            Neighbourhood::Hex,
+        ] {
+            for _ in 0..10 {
+                let mut grid = Grid::new(0, 0, 16, 16, 1);
+                grid.set_neighbourhood(neighbourhood);
+                let mut start = TitleCoords { x: 0, y: 0 };
+                let goal = TitleCoords { x: 15, y: 15 };
+                grid.set_start(start).unwrap();
+                grid.set_goal(goal).unwrap();
+                for _ in 0..rng.random_range(20..120) {
+                    let title = random_title(&mut rng);
+                    if title != start && title != goal && !grid.is_obstacle(title) {
+                        grid.set_obstacle(title).unwrap();
+                    }
+                }
+                let mut d_star_lite = DStarLite::default();
+                d_star_lite.start(&mut grid).unwrap();
+                run_until_completed(&mut d_star_lite, &mut grid);
+
+                for _ in 0..12 {
+                    // Titles are blocked, freed, repainted and the start moves between the repairs,
+                    // sometimes while the repair runs
+                    for _ in 0..rng.random_range(1..6) {
+                        let title = random_title(&mut rng);
+                        if title == start || title == goal {
+                            continue;
+                        }
+                        match rng.random_range(0..4) {
+                            0 if !grid.is_obstacle(title) => grid.set_obstacle(title).unwrap(),
+                            1 => grid.clear_title(title).unwrap(),
+                            2 => grid.set_terrain(title, Terrain::ALL[rng.random_range(0..4)]),
+                            3 if !grid.is_obstacle(title) => {
+                                grid.set_start(title).unwrap();
+                                start = title;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if rng.random_range(0..3) == 0 {
+                        for _ in 0..rng.random_range(1..20) {
+                            d_star_lite.execute_step(&mut grid, ONE_ITERATION_TIME_SEC);
+                        }
+                        let title = random_title(&mut rng);
+                        if title != start && title != goal && !grid.is_obstacle(title) {
+                            grid.set_obstacle(title).unwrap();
+                        }
+                    }
+                    run_until_completed(&mut d_star_lite, &mut grid);
+
+                    let repaired = d_star_lite.statistics();
+                    let fresh = solve(&grid, start, goal, Dijkstra::default()).unwrap();
+                    assert_eq!(fresh.statistics.goal_reachable, repaired.goal_reachable);
+                    assert!(
+                        (fresh.cost - repaired.path_cost).abs() < 1e-9,
+                        "{:?}: repaired cost {} instead of {}",
+                        neighbourhood,
+                        repaired.path_cost,
+                        fresh.cost
+                    );
+                }
+            }
+        }
+    }
+}
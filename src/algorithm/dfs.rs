@@ -0,0 +1,123 @@
+use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, SimulationCoordinator};
+use crate::{
+    algorithm::statistics::SearchStatistics,
+    map::{grid::Grid, TitleCoords},
+};
+use std::collections::HashSet;
+
+/// # Depth-First Search Algorithm
+/// The algorithm follows one direction as deep as possible before it backtracks.
+///
+/// It keeps only the branch it explores, but the found path is usually far from the shortest one.
+#[derive(Default)]
+pub struct Dfs {
+    title_processing_stack: Vec<TitleCoords>,
+    visited_titles: HashSet<TitleCoords>,
+    sim_coordinator: SimulationCoordinator,
+    path_finder: Pathfinder,
+}
+
+impl Measurable for Dfs {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(self.name(), &[], &self.sim_coordinator, &self.path_finder)
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.visited_titles.len()
+    }
+}
+
+impl Algorithm for Dfs {
+    /// # start
+    /// DFS Algorithm starts.
+    ///
+    /// Init the algorithm values
+    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
+        if grid.start_title.is_none() || grid.goal_title.is_none() {
+            return Err(AlgorithmError::InvalidInputData);
+        }
+        let start = grid.start_title.unwrap();
+
+        self.title_processing_stack.push(start);
+        self.sim_coordinator
+            .record_generated(self.title_processing_stack.len());
+        self.visited_titles.insert(start);
+        self.path_finder.add_to_path(start, None);
+        self.sim_coordinator.start_processing();
+        Ok(())
+    }
+
+    /// # execute_step
+    /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
+    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+        if !self.sim_coordinator.is_ready_to_execute(delta_time) {
+            return;
+        }
+        if let Some(current) = self.title_processing_stack.pop() {
+            self.sim_coordinator.increase_step_count();
+            let goal = grid.goal_title.unwrap();
+            let start = grid.start_title.unwrap();
+
+            if self.sim_coordinator.process_goal_reached(current, goal) {
+                self.path_finder.reconstruct_path(start, goal);
+                self.path_finder.trace_back_path(grid);
+                return;
+            }
+            grid.mark_visited(current);
+            self.sim_coordinator.record_expansion();
+
+            // The first neighbour is explored first
+            for neighbor_title in grid.get_neighbors(current).into_iter().rev() {
+                if self.visited_titles.insert(neighbor_title) {
+                    grid.mark_process(neighbor_title);
+                    self.path_finder.add_to_path(neighbor_title, Some(current));
+                    self.title_processing_stack.push(neighbor_title);
+                    self.sim_coordinator
+                        .record_generated(self.title_processing_stack.len());
+                }
+            }
+            // Check if goal is unreachable
+            if self.title_processing_stack.is_empty() {
+                self.sim_coordinator.has_completed = true;
+                self.sim_coordinator.stop_processing();
+            }
+        } else {
+            self.sim_coordinator.stop_processing();
+        }
+    }
+
+    /// # reset
+    /// Reset the algorithm processing and clear its search overlay
+    fn reset(&mut self, grid: &mut Grid) {
+        *self = Dfs::default();
+        grid.clear_overlay();
+    }
+
+    /// # has_completed
+    /// Check if processing is done
+    fn has_completed(&self) -> bool {
+        self.sim_coordinator.has_completed
+    }
+
+    /// # name
+    /// Algorithm name
+    fn name(&self) -> String {
+        "Depth First Search".to_string()
+    }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the goal is reached
+    fn solution_path(&self) -> &[TitleCoords] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(TitleCoords, TitleCoords)> {
+        self.path_finder.take_parent_updates()
+    }
+}
@@ -1,8 +1,8 @@
-use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, SimulationCoordinator};
-use crate::{
-    algorithm::get_statistics,
-    map::{grid::Grid, TitleCoords},
+use super::{
+    search_space::SearchSpace, Algorithm, AlgorithmError, Measurable, Pathfinder, Priority,
+    SimulationCoordinator,
 };
+use crate::{algorithm::statistics::SearchStatistics, map::grid::Grid};
 use priority_queue::DoublePriorityQueue;
 use std::collections::HashMap;
 /// # Dijkstra's Algorithm **(Uniform Cost Search)**
@@ -10,38 +10,63 @@ use std::collections::HashMap;
 ///
 /// Works well to find the shortest path but explores titles in
 /// directions that aren’t promising.
-#[derive(Default)]
-pub struct Dijkstra {
-    priority_titles: DoublePriorityQueue<TitleCoords, i32>,
-    cost_so_far: HashMap<TitleCoords, i32>,
+///
+/// Searches the grid by default, or any other search space created with `Dijkstra::new()`
+pub struct Dijkstra<S: SearchSpace = Grid> {
+    priority_titles: DoublePriorityQueue<S::Node, Priority>,
+    cost_so_far: HashMap<S::Node, f64>,
+    start: Option<S::Node>,
     sim_coordinator: SimulationCoordinator,
-    path_finder: Pathfinder,
+    path_finder: Pathfinder<S::Node>,
+}
+
+impl<S: SearchSpace> Dijkstra<S> {
+    /// # new
+    /// Dijkstra's Algorithm in the search space
+    pub fn new() -> Self {
+        Self {
+            priority_titles: DoublePriorityQueue::new(),
+            cost_so_far: HashMap::new(),
+            start: None,
+            sim_coordinator: SimulationCoordinator::default(),
+            path_finder: Pathfinder::default(),
+        }
+    }
 }
 
-impl Measurable for Dijkstra {
-    fn output_statistics(&self) -> String {
-        get_statistics(
-            self.name().as_str(),
-            self.path_finder.get_path().len(),
-            self.sim_coordinator.steps,
-            self.cost_so_far.len(),
-        )
+impl Default for Dijkstra {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Algorithm for Dijkstra {
+impl<S: SearchSpace> Measurable for Dijkstra<S> {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(self.name(), &[], &self.sim_coordinator, &self.path_finder)
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.cost_so_far.len()
+    }
+}
+
+impl<S: SearchSpace> Algorithm<S> for Dijkstra<S> {
     /// # start
     /// Dijkstra's Algorithm starts.
     ///
     /// Init the algorithm values
-    fn start(&mut self, grid: &mut Grid) -> Result<(), super::AlgorithmError> {
-        if grid.start_title.is_none() || grid.goal_title.is_none() {
-            return Err(AlgorithmError::InvalidInputData);
-        }
-        let start = grid.start_title.unwrap();
-
-        self.priority_titles.push(start, 0);
-        self.cost_so_far.insert(start, 0);
+    fn start(&mut self, space: &mut S) -> Result<(), super::AlgorithmError> {
+        let start = space.start_node().ok_or(AlgorithmError::InvalidInputData)?;
+
+        self.start = Some(start);
+        self.priority_titles.push(start, Priority(0.0));
+        self.sim_coordinator
+            .record_generated(self.priority_titles.len());
+        self.cost_so_far.insert(start, 0.0);
         self.path_finder.add_to_path(start, None);
         self.sim_coordinator.start_processing();
 
@@ -50,40 +75,42 @@ impl Algorithm for Dijkstra {
 
     /// # execute_step
     /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
-    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+    fn execute_step(&mut self, space: &mut S, delta_time: f64) {
         if !self.sim_coordinator.is_ready_to_execute(delta_time) {
             return;
         }
 
         if let Some(current_title) = self.priority_titles.pop_min() {
             self.sim_coordinator.increase_step_count();
-            let goal = grid.goal_title.unwrap();
-            let start = grid.start_title.unwrap();
+            let start = self.start.unwrap();
             let current = current_title.0;
             let _priority = current_title.1;
 
             // Early exit
-            if self.sim_coordinator.process_goal_reached(current, goal) {
-                self.path_finder.reconstruct_path(start, goal);
-                for element in self.path_finder.get_path().iter() {
-                    grid.set_trace_back_path(*element);
-                }
+            if self
+                .sim_coordinator
+                .process_goal_test(space.is_goal(current))
+            {
+                self.path_finder.reconstruct_path(start, current);
+                self.path_finder.trace_back_path(space);
                 return;
             }
-            grid.mark_visited(current);
-            let neighbors = grid.get_neighbors(current);
+            space.mark_visited(current);
+            self.sim_coordinator.record_expansion();
+            let neighbors = space.successors(current);
 
-            for neighbor in neighbors {
-                let new_cost =
-                    *self.cost_so_far.get(&current).unwrap() + grid.cost(current, neighbor);
+            for (neighbor, cost) in neighbors {
+                let new_cost = *self.cost_so_far.get(&current).unwrap() + cost;
 
                 if !self.cost_so_far.contains_key(&neighbor)
                     || new_cost < *self.cost_so_far.get(&neighbor).unwrap()
                 {
-                    grid.mark_process(neighbor);
+                    space.mark_process(neighbor);
                     self.cost_so_far.insert(neighbor, new_cost);
                     let priority = new_cost;
-                    self.priority_titles.push(neighbor, priority);
+                    self.priority_titles.push(neighbor, Priority(priority));
+                    self.sim_coordinator
+                        .record_generated(self.priority_titles.len());
                     self.path_finder.add_to_path(neighbor, Some(current));
                 }
             }
@@ -98,10 +125,10 @@ impl Algorithm for Dijkstra {
     }
 
     /// # reset
-    /// Reset the algorithm processing
-    fn reset(&mut self, grid: &mut Grid) {
-        *self = Dijkstra::default();
-        grid.reset();
+    /// Reset the algorithm processing and clear its search overlay
+    fn reset(&mut self, space: &mut S) {
+        *self = Dijkstra::new();
+        space.clear_marks();
     }
 
     /// # has_completed
@@ -115,12 +142,24 @@ impl Algorithm for Dijkstra {
     fn name(&self) -> String {
         "Dijkstra".to_string()
     }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the goal is reached
+    fn solution_path(&self) -> &[S::Node] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(S::Node, S::Node)> {
+        self.path_finder.take_parent_updates()
+    }
 }
 
 #[cfg(test)]
 mod unit_test {
     use super::*;
-    use crate::algorithm::ONE_ITERATION_TIME_SEC;
+    use crate::{algorithm::ONE_ITERATION_TIME_SEC, map::TitleCoords};
     #[test]
     fn dijkstra_start() {
         let mut dijkstra = Dijkstra::default();
@@ -134,8 +173,11 @@ mod unit_test {
         grid.goal_title = Some(TitleCoords { x: 1, y: 1 });
 
         assert!(dijkstra.start(&mut grid).is_ok());
-        assert_eq!(*dijkstra.cost_so_far.get(&start).unwrap(), 0);
-        assert_eq!(dijkstra.priority_titles.pop_min().unwrap(), (start, 0));
+        assert_eq!(*dijkstra.cost_so_far.get(&start).unwrap(), 0.0);
+        assert_eq!(
+            dijkstra.priority_titles.pop_min().unwrap(),
+            (start, Priority(0.0))
+        );
         assert_eq!(*dijkstra.path_finder.path.get(&start).unwrap(), None);
     }
 
@@ -152,10 +194,13 @@ mod unit_test {
 
         assert_eq!(dijkstra.cost_so_far.len(), 5);
 
-        let cost = dijkstra.cost_so_far.iter().last().unwrap();
-        assert_eq!(*cost.1, 1);
+        let neighbor_cost = dijkstra
+            .cost_so_far
+            .get(&TitleCoords { x: 5, y: 4 })
+            .unwrap();
+        assert_eq!(*neighbor_cost, 1.0);
 
         let priority = dijkstra.priority_titles.pop_min().unwrap().1;
-        assert_eq!(priority, 1);
+        assert_eq!(priority, Priority(1.0));
     }
 }
@@ -1,45 +1,98 @@
-use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, SimulationCoordinator};
+use super::{
+    search_space::SearchSpace, Algorithm, AlgorithmError, Measurable, Pathfinder, Priority,
+    SimulationCoordinator,
+};
 use crate::{
-    algorithm::get_statistics,
-    map::{grid::Grid, TitleCoords},
+    algorithm::{
+        heuristic::{Heuristic, HeuristicSelection},
+        statistics::SearchStatistics,
+    },
+    map::grid::Grid,
 };
 use priority_queue::DoublePriorityQueue;
+use std::collections::HashSet;
 
 /// # Greedy Best First Search Algorithm
 /// Explores titles in promising directions but it may not find the shortest path.
-#[derive(Default)]
-pub struct GreedyBfs {
-    priority_titles: DoublePriorityQueue<TitleCoords, i32>,
-    visited_titles: Vec<TitleCoords>,
+///
+/// Searches the grid by default, or any other search space created with `GreedyBfs::new()`
+pub struct GreedyBfs<S: SearchSpace = Grid> {
+    priority_titles: DoublePriorityQueue<S::Node, Priority>,
+    visited_titles: HashSet<S::Node>,
+    start: Option<S::Node>,
     sim_coordinator: SimulationCoordinator,
-    path_finder: Pathfinder,
+    path_finder: Pathfinder<S::Node>,
+    heuristic: HeuristicSelection<S>,
+    heuristic_name: String,
+}
+
+impl<S: SearchSpace> GreedyBfs<S> {
+    /// # new
+    /// Greedy Best First Search in the search space guided by its estimation
+    pub fn new() -> Self {
+        Self {
+            priority_titles: DoublePriorityQueue::new(),
+            visited_titles: HashSet::new(),
+            start: None,
+            sim_coordinator: SimulationCoordinator::default(),
+            path_finder: Pathfinder::default(),
+            heuristic: HeuristicSelection::default(),
+            heuristic_name: String::new(),
+        }
+    }
+}
+
+impl Default for GreedyBfs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GreedyBfs {
+    /// # with_heuristic
+    /// Create the algorithm with the heuristic. By default the grid distance metric is used
+    pub fn with_heuristic(heuristic: impl Heuristic + 'static) -> Self {
+        Self {
+            heuristic: HeuristicSelection::new(heuristic),
+            ..Default::default()
+        }
+    }
 }
 
-impl Measurable for GreedyBfs {
-    fn output_statistics(&self) -> String {
-        get_statistics(
-            self.name().as_str(),
-            self.path_finder.get_path().len(),
-            self.sim_coordinator.steps,
-            self.visited_titles.len(),
+impl<S: SearchSpace> Measurable for GreedyBfs<S> {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(
+            self.name(),
+            &[("Heuristic", self.heuristic_name.clone())],
+            &self.sim_coordinator,
+            &self.path_finder,
         )
     }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.visited_titles.len()
+    }
 }
 
-impl Algorithm for GreedyBfs {
+impl<S: SearchSpace> Algorithm<S> for GreedyBfs<S> {
     /// # start
     /// Greedy BFS Algorithm starts.
     ///
     /// Init the algorithm values
-    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
-        if grid.start_title.is_none() || grid.goal_title.is_none() {
-            return Err(AlgorithmError::InvalidInputData);
-        }
+    fn start(&mut self, space: &mut S) -> Result<(), AlgorithmError> {
+        let start = space.start_node().ok_or(AlgorithmError::InvalidInputData)?;
 
-        let start = grid.start_title.unwrap();
-        self.priority_titles.push(start, 0);
+        self.start = Some(start);
+        self.priority_titles.push(start, Priority(0.0));
+        self.sim_coordinator
+            .record_generated(self.priority_titles.len());
         self.path_finder.add_to_path(start, None);
-        self.visited_titles.push(start);
+        self.visited_titles.insert(start);
+        self.heuristic_name = self.heuristic.name(space);
         self.sim_coordinator.start_processing();
 
         Ok(())
@@ -47,7 +100,7 @@ impl Algorithm for GreedyBfs {
 
     /// # execute_step
     /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
-    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+    fn execute_step(&mut self, space: &mut S, delta_time: f64) {
         if !self.sim_coordinator.is_ready_to_execute(delta_time) {
             return;
         }
@@ -56,26 +109,29 @@ impl Algorithm for GreedyBfs {
             self.sim_coordinator.increase_step_count();
             let current = current_title.0;
             let _priority = current_title.1;
-            let goal = grid.goal_title.unwrap();
-            let start = grid.start_title.unwrap();
+            let start = self.start.unwrap();
 
-            if self.sim_coordinator.process_goal_reached(current, goal) {
-                self.path_finder.reconstruct_path(start, goal);
-                for element in self.path_finder.get_path().iter() {
-                    grid.set_trace_back_path(*element);
-                }
+            if self
+                .sim_coordinator
+                .process_goal_test(space.is_goal(current))
+            {
+                self.path_finder.reconstruct_path(start, current);
+                self.path_finder.trace_back_path(space);
                 return;
             }
 
-            grid.mark_visited(current);
-            let neighbors = grid.get_neighbors(current);
+            space.mark_visited(current);
+            self.sim_coordinator.record_expansion();
+            let neighbors = space.successors(current);
 
-            for neighbor in neighbors {
-                if !self.visited_titles.contains(&neighbor) {
-                    let heuristic_priority = grid.heuristic(neighbor, goal);
-                    self.priority_titles.push(neighbor, heuristic_priority);
-                    self.visited_titles.push(neighbor);
-                    grid.mark_process(neighbor);
+            for (neighbor, _cost) in neighbors {
+                if self.visited_titles.insert(neighbor) {
+                    let heuristic_priority = self.heuristic.estimate(space, neighbor);
+                    self.priority_titles
+                        .push(neighbor, Priority(heuristic_priority));
+                    self.sim_coordinator
+                        .record_generated(self.priority_titles.len());
+                    space.mark_process(neighbor);
                     self.path_finder.add_to_path(neighbor, Some(current));
                 }
             }
@@ -90,10 +146,15 @@ impl Algorithm for GreedyBfs {
     }
 
     /// # reset
-    /// Reset the algorithm processing
-    fn reset(&mut self, grid: &mut Grid) {
-        *self = GreedyBfs::default();
-        grid.reset();
+    /// Reset the algorithm processing and clear its search overlay.
+    ///
+    /// The heuristic is kept
+    fn reset(&mut self, space: &mut S) {
+        *self = Self {
+            heuristic: std::mem::take(&mut self.heuristic),
+            ..Self::new()
+        };
+        space.clear_marks();
     }
 
     /// # has_completed
@@ -107,4 +168,16 @@ impl Algorithm for GreedyBfs {
     fn name(&self) -> String {
         "Greedy Best First Search".to_string()
     }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the goal is reached
+    fn solution_path(&self) -> &[S::Node] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(S::Node, S::Node)> {
+        self.path_finder.take_parent_updates()
+    }
 }
@@ -0,0 +1,124 @@
+//! # Heuristic
+//! Estimation of the remaining cost to the goal used by A* and Greedy Best First Search.
+//!
+//! Built-in heuristics are the [`DistanceMetric`]s (Manhattan, Euclidean, octile,
+//! Chebyshev, hex and zero). Any closure `Fn(TitleCoords, TitleCoords) -> f64` is a heuristic too.
+//!
+//! On the grid the estimate is scaled by [`Grid::heuristic_scale`], so it stays admissible
+//! when some terrain is cheaper than 1.
+use crate::{
+    algorithm::search_space::SearchSpace,
+    map::{grid::Grid, DistanceMetric, TitleCoords},
+};
+
+pub trait Heuristic {
+    /// # estimate
+    /// Estimated cost of the path from one title to the other
+    fn estimate(&self, from: TitleCoords, to: TitleCoords) -> f64;
+
+    /// # name
+    /// Heuristic name shown in the statistics
+    fn name(&self) -> String {
+        "Custom".to_string()
+    }
+}
+
+impl Heuristic for DistanceMetric {
+    fn estimate(&self, from: TitleCoords, to: TitleCoords) -> f64 {
+        self.distance(from, to)
+    }
+
+    fn name(&self) -> String {
+        DistanceMetric::name(self).to_string()
+    }
+}
+
+impl<F: Fn(TitleCoords, TitleCoords) -> f64> Heuristic for F {
+    fn estimate(&self, from: TitleCoords, to: TitleCoords) -> f64 {
+        self(from, to)
+    }
+}
+
+/// Estimation of the node in the search space
+type Estimate<S> = Box<dyn Fn(&S, <S as SearchSpace>::Node) -> f64>;
+
+/// # HeuristicSelection
+/// Heuristic used by the algorithm. Without explicit heuristic the estimation of the search
+/// space is used, on the grid it is the grid distance metric, so it follows the grid movement
+pub(crate) struct HeuristicSelection<S: SearchSpace> {
+    heuristic: Option<(Estimate<S>, String)>,
+}
+
+impl<S: SearchSpace> Default for HeuristicSelection<S> {
+    fn default() -> Self {
+        Self { heuristic: None }
+    }
+}
+
+impl HeuristicSelection<Grid> {
+    /// The heuristic estimates the cost to the goal of the grid, scaled by the cheapest terrain
+    pub fn new(heuristic: impl Heuristic + 'static) -> Self {
+        let name = heuristic.name();
+        let estimate = move |grid: &Grid, title: TitleCoords| {
+            grid.goal_title.map_or(0.0, |goal| {
+                grid.heuristic_scale() * heuristic.estimate(title, goal)
+            })
+        };
+        Self {
+            heuristic: Some((Box::new(estimate), name)),
+        }
+    }
+}
+
+impl<S: SearchSpace> HeuristicSelection<S> {
+    pub fn estimate(&self, space: &S, node: S::Node) -> f64 {
+        match &self.heuristic {
+            Some((estimate, _)) => estimate(space, node),
+            None => space.estimate(node),
+        }
+    }
+
+    pub fn name(&self, space: &S) -> String {
+        match &self.heuristic {
+            Some((_, name)) => name.clone(),
+            None => space.heuristic_name(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::map::Terrain;
+
+    #[test]
+    fn heuristic_selection() {
+        let mut grid = Grid::new(0, 0, 10, 10, 1);
+        grid.set_terrain_cost(Terrain::Road, 1.0);
+        let from = TitleCoords { x: 0, y: 0 };
+        let to = TitleCoords { x: 3, y: 4 };
+        grid.goal_title = Some(to);
+
+        let grid_metric = HeuristicSelection::default();
+        assert_eq!(7.0, grid_metric.estimate(&grid, from));
+        assert_eq!("Manhattan (grid)", grid_metric.name(&grid));
+        grid.set_distance_metric(DistanceMetric::Chebyshev);
+        assert_eq!(4.0, grid_metric.estimate(&grid, from));
+
+        let euclidean = HeuristicSelection::new(DistanceMetric::Euclidean);
+        assert_eq!(5.0, euclidean.estimate(&grid, from));
+        assert_eq!("Euclidean", euclidean.name(&grid));
+
+        let custom = HeuristicSelection::new(|from: TitleCoords, to: TitleCoords| {
+            2.0 * from.x.abs_diff(to.x) as f64
+        });
+        assert_eq!(6.0, custom.estimate(&grid, from));
+        assert_eq!("Custom", custom.name(&grid));
+
+        // Road is cheaper than 1, the estimates are scaled down by its cost
+        grid.set_terrain_cost(Terrain::Road, 0.5);
+        assert_eq!(2.0, grid_metric.estimate(&grid, from));
+        assert_eq!(2.5, euclidean.estimate(&grid, from));
+        assert_eq!(3.0, custom.estimate(&grid, from));
+    }
+}
@@ -0,0 +1,365 @@
+use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, SimulationCoordinator};
+use crate::{
+    algorithm::statistics::SearchStatistics,
+    map::{grid::Grid, TitleCoords},
+};
+use std::{collections::HashMap, marker::PhantomData};
+
+/// # DeepeningBound
+/// Value bounded by one iteration of the iterative deepening search.
+///
+/// The bound of a title is its cost from the start plus its estimation to the goal.
+/// The next iteration raises the limit to the lowest bound which exceeded the current one
+pub trait DeepeningBound {
+    /// # name
+    /// Name of the limit, e.g. depth limit
+    fn name() -> &'static str;
+    /// # algorithm_name
+    /// Name of the algorithm using the bound
+    fn algorithm_name() -> &'static str;
+    /// # step_cost
+    /// Cost of the move between neighbours
+    fn step_cost(grid: &Grid, from: TitleCoords, to: TitleCoords) -> f64;
+    /// # estimate
+    /// Estimated cost from the title to the goal
+    fn estimate(grid: &Grid, title: TitleCoords, goal: TitleCoords) -> f64;
+}
+
+/// # DepthLimit
+/// Limits the number of moves from the start - Iterative Deepening Dfs
+#[derive(Debug, Default)]
+pub struct DepthLimit;
+
+impl DeepeningBound for DepthLimit {
+    fn name() -> &'static str {
+        "Depth limit"
+    }
+
+    fn algorithm_name() -> &'static str {
+        "Iterative Deepening Dfs"
+    }
+
+    fn step_cost(_grid: &Grid, _from: TitleCoords, _to: TitleCoords) -> f64 {
+        1.0
+    }
+
+    fn estimate(_grid: &Grid, _title: TitleCoords, _goal: TitleCoords) -> f64 {
+        0.0
+    }
+}
+
+/// # CostThreshold
+/// Limits the movement cost plus the grid heuristic (f = g + h) - IDA*
+#[derive(Debug, Default)]
+pub struct CostThreshold;
+
+impl DeepeningBound for CostThreshold {
+    fn name() -> &'static str {
+        "f-threshold"
+    }
+
+    fn algorithm_name() -> &'static str {
+        "IDA*"
+    }
+
+    fn step_cost(grid: &Grid, from: TitleCoords, to: TitleCoords) -> f64 {
+        grid.cost(from, to)
+    }
+
+    fn estimate(grid: &Grid, title: TitleCoords, goal: TitleCoords) -> f64 {
+        grid.heuristic(title, goal)
+    }
+}
+
+/// Title on the explored branch with its neighbours waiting to be explored
+struct Frame {
+    title: TitleCoords,
+    cost: f64,
+    neighbors: Vec<TitleCoords>,
+}
+
+/// # Iterative Deepening Search
+/// Depth-first search repeated with a growing limit. Only the explored branch is kept
+/// in memory, and the path is as short as the one found by Bfs (Iddfs) or A* (IDA*).
+///
+/// Every step moves one title deeper into the branch or backtracks from it. The branch is drawn
+/// as titles in processing, and the overlay is cleared when the next iteration starts.
+/// Titles reached again within one iteration at no lower cost are not explored twice,
+/// which keeps the iterations tractable on grids.
+pub struct IterativeDeepening<B: DeepeningBound> {
+    branch: Vec<Frame>,
+    /// The lowest cost of the titles reached in the current iteration
+    best_costs: HashMap<TitleCoords, f64>,
+    limit: f64,
+    next_limit: f64,
+    iterations: u32,
+    sim_coordinator: SimulationCoordinator,
+    path_finder: Pathfinder,
+    bound: PhantomData<B>,
+}
+
+/// # Iddfs
+/// Iterative Deepening Depth-First Search
+pub type Iddfs = IterativeDeepening<DepthLimit>;
+
+/// # IdaStar
+/// Iterative Deepening A*
+pub type IdaStar = IterativeDeepening<CostThreshold>;
+
+impl<B: DeepeningBound> Default for IterativeDeepening<B> {
+    fn default() -> Self {
+        Self {
+            branch: Vec::new(),
+            best_costs: HashMap::new(),
+            limit: 0.0,
+            next_limit: f64::INFINITY,
+            iterations: 0,
+            sim_coordinator: SimulationCoordinator::default(),
+            path_finder: Pathfinder::default(),
+            bound: PhantomData,
+        }
+    }
+}
+
+impl<B: DeepeningBound> IterativeDeepening<B> {
+    /// # limit
+    /// Limit of the current iteration
+    pub fn limit(&self) -> f64 {
+        self.limit
+    }
+
+    /// # iterations
+    /// Number of started iterations
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Start the next iteration from the start with the given limit
+    fn start_iteration(&mut self, grid: &mut Grid, start: TitleCoords, limit: f64) {
+        grid.clear_overlay();
+        self.best_costs.clear();
+        self.limit = limit;
+        self.next_limit = f64::INFINITY;
+        self.iterations += 1;
+        self.push_frame(grid, start, 0.0);
+    }
+
+    fn push_frame(&mut self, grid: &mut Grid, title: TitleCoords, cost: f64) {
+        grid.mark_process(title);
+        self.best_costs.insert(title, cost);
+        // The first neighbour is explored first
+        let mut neighbors = grid.get_neighbors(title);
+        neighbors.reverse();
+        self.branch.push(Frame {
+            title,
+            cost,
+            neighbors,
+        });
+        self.sim_coordinator.record_expansion();
+        self.sim_coordinator.record_generated(self.branch.len());
+    }
+
+    /// The goal was reached from the branch
+    fn finish(&mut self, grid: &mut Grid, start: TitleCoords, goal: TitleCoords) {
+        self.sim_coordinator.has_completed = true;
+        self.sim_coordinator.stop_processing();
+        self.path_finder.add_to_path(start, None);
+        for step in self.branch.windows(2) {
+            self.path_finder
+                .add_to_path(step[1].title, Some(step[0].title));
+        }
+        let last = self.branch.last().map_or(start, |frame| frame.title);
+        self.path_finder.add_to_path(goal, Some(last));
+        self.path_finder.reconstruct_path(start, goal);
+        self.path_finder.trace_back_path(grid);
+    }
+}
+
+impl<B: DeepeningBound> Measurable for IterativeDeepening<B> {
+    fn statistics(&self) -> SearchStatistics {
+        SearchStatistics::collect(
+            self.name(),
+            &[
+                (B::name(), format!("{:.2}", self.limit)),
+                ("Iterations", self.iterations.to_string()),
+            ],
+            &self.sim_coordinator,
+            &self.path_finder,
+        )
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.best_costs.len()
+    }
+}
+
+impl<B: DeepeningBound> Algorithm for IterativeDeepening<B> {
+    /// # start
+    /// Iterative deepening starts.
+    ///
+    /// The first limit is the estimation of the start
+    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
+        if grid.start_title.is_none() || grid.goal_title.is_none() {
+            return Err(AlgorithmError::InvalidInputData);
+        }
+        let start = grid.start_title.unwrap();
+        let goal = grid.goal_title.unwrap();
+
+        self.path_finder.add_to_path(start, None);
+        self.sim_coordinator.start_processing();
+        if start == goal {
+            self.finish(grid, start, goal);
+            return Ok(());
+        }
+        self.start_iteration(grid, start, B::estimate(grid, start, goal));
+
+        Ok(())
+    }
+
+    /// # execute_step
+    /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
+    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+        if !self.sim_coordinator.is_ready_to_execute(delta_time) {
+            return;
+        }
+        self.sim_coordinator.increase_step_count();
+        let start = grid.start_title.unwrap();
+        let goal = grid.goal_title.unwrap();
+
+        let Some(frame) = self.branch.last_mut() else {
+            // Nothing exceeded the limit, so there is nothing left to explore
+            if self.next_limit.is_infinite() {
+                self.sim_coordinator.has_completed = true;
+                self.sim_coordinator.stop_processing();
+                return;
+            }
+            self.start_iteration(grid, start, self.next_limit);
+            return;
+        };
+
+        let Some(neighbor) = frame.neighbors.pop() else {
+            // Backtrack
+            let frame = self.branch.pop().unwrap();
+            grid.mark_visited(frame.title);
+            return;
+        };
+        let (current, current_cost) = (frame.title, frame.cost);
+
+        let cost = current_cost + B::step_cost(grid, current, neighbor);
+        let bound = cost + B::estimate(grid, neighbor, goal);
+        if bound > self.limit {
+            self.next_limit = self.next_limit.min(bound);
+            return;
+        }
+        if self
+            .best_costs
+            .get(&neighbor)
+            .is_some_and(|best_cost| *best_cost <= cost)
+        {
+            return;
+        }
+        if neighbor == goal {
+            self.finish(grid, start, goal);
+            return;
+        }
+        self.push_frame(grid, neighbor, cost);
+    }
+
+    /// # reset
+    /// Reset the algorithm processing and clear its search overlay
+    fn reset(&mut self, grid: &mut Grid) {
+        *self = Self::default();
+        grid.clear_overlay();
+    }
+
+    /// # has_completed
+    /// Check if processing is done
+    fn has_completed(&self) -> bool {
+        self.sim_coordinator.has_completed
+    }
+
+    /// # name
+    /// Algorithm name
+    fn name(&self) -> String {
+        B::algorithm_name().to_string()
+    }
+
+    /// # solution_path
+    /// Path from the goal back to the start. Empty until the goal is reached
+    fn solution_path(&self) -> &[TitleCoords] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(TitleCoords, TitleCoords)> {
+        self.path_finder.take_parent_updates()
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{a_star::AStar, bfs::Bfs, dfs::Dfs, solver::solve},
+        map::{Neighbourhood, Terrain},
+    };
+
+    #[test]
+    fn iterative_deepening_is_optimal() {
+        let mut grid = Grid::new(0, 0, 12, 12, 1);
+        grid.set_neighbourhood(Neighbourhood::Eight);
+        for y in 0..9 {
+            grid.set_obstacle(TitleCoords { x: 6, y }).unwrap();
+        }
+        for x in 2..5 {
+            grid.set_terrain(TitleCoords { x, y: 9 }, Terrain::Water);
+        }
+        let start = TitleCoords { x: 2, y: 2 };
+        let goal = TitleCoords { x: 10, y: 3 };
+
+        let bfs = solve(&grid, start, goal, Bfs::default()).unwrap();
+        let iddfs = solve(&grid, start, goal, Iddfs::default()).unwrap();
+        assert_eq!(bfs.path.len(), iddfs.path.len());
+        assert_eq!(
+            Some(&(
+                "Depth limit".to_string(),
+                format!("{:.2}", (bfs.path.len() - 1) as f64)
+            )),
+            iddfs.statistics.parameters.first()
+        );
+
+        let a_star = solve(&grid, start, goal, AStar::default()).unwrap();
+        let ida_star = solve(&grid, start, goal, IdaStar::default()).unwrap();
+        assert!((a_star.cost - ida_star.cost).abs() < 1e-9);
+        // Only the branch is kept, every move costs at least 1
+        assert!(ida_star.statistics.max_frontier_size as f64 <= ida_star.cost + 1.0);
+
+        // Plain Dfs finds a path, but not a short one
+        let dfs = solve(&grid, start, goal, Dfs::default()).unwrap();
+        assert!(dfs.is_goal_reachable());
+        assert!(dfs.path.len() > bfs.path.len());
+    }
+
+    #[test]
+    fn iterative_deepening_unreachable_goal() {
+        let mut grid = Grid::new(0, 0, 6, 6, 1);
+        for y in 0..6 {
+            grid.set_obstacle(TitleCoords { x: 3, y }).unwrap();
+        }
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 5, y: 5 };
+
+        for result in [
+            solve(&grid, start, goal, Iddfs::default()).unwrap(),
+            solve(&grid, start, goal, IdaStar::default()).unwrap(),
+            solve(&grid, start, goal, Dfs::default()).unwrap(),
+        ] {
+            assert!(!result.is_goal_reachable());
+        }
+    }
+}
@@ -0,0 +1,578 @@
+use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, Priority, SimulationCoordinator};
+use crate::{
+    algorithm::statistics::SearchStatistics,
+    map::{grid::Grid, DistanceMetric, Neighbourhood, TitleCoords},
+};
+use priority_queue::DoublePriorityQueue;
+use std::collections::HashMap;
+
+/// Movement direction as (dx, dy)
+type Direction = (isize, isize);
+
+/// Straight directions first, then diagonals
+const DIRECTIONS: [Direction; 8] = [
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// # Jump Point Search
+/// A* for uniform-cost grids. Instead of adding every neighbour to the queue it jumps
+/// along straight and diagonal lines and expands only the jump points - titles with
+/// forced neighbours, where an optimal path may turn.
+///
+/// The search works on 8-way grids without cutting corners of obstacles and on hex grids,
+/// where all passable titles cost the same. Other grids are rejected when the search starts.
+/// The hex grid has no jumps - every neighbour is a jump point, so the search expands
+/// the titles as the uniform-cost A*.
+///
+/// The JPS+ variant ([`JumpPointSearch::plus`]) precomputes the jump distances of the static map
+/// when the search starts, so no jump has to be scanned during the search.
+#[derive(Default)]
+pub struct JumpPointSearch {
+    priority_titles: DoublePriorityQueue<TitleCoords, Priority>,
+    cost_so_far: HashMap<TitleCoords, f64>,
+    /// Direction of the jump which reached the title
+    directions: HashMap<TitleCoords, Direction>,
+    sim_coordinator: SimulationCoordinator,
+    path_finder: Pathfinder,
+    precompute: bool,
+    jump_distances: Option<JumpDistances>,
+    is_hex: bool,
+}
+
+impl JumpPointSearch {
+    /// # plus
+    /// Create the JPS+ variant with the precomputed jump distances
+    pub fn plus() -> Self {
+        Self {
+            precompute: true,
+            ..Default::default()
+        }
+    }
+
+    /// Jump points reachable from the title travelling in the direction
+    fn successors(
+        &self,
+        grid: &Grid,
+        current: TitleCoords,
+        direction: Option<Direction>,
+        goal: TitleCoords,
+    ) -> Vec<TitleCoords> {
+        if self.is_hex {
+            return grid.get_neighbors(current);
+        }
+        let from = (current.x as isize, current.y as isize);
+        let goal = (goal.x as isize, goal.y as isize);
+        successor_directions(direction)
+            .into_iter()
+            .filter_map(|direction| match &self.jump_distances {
+                Some(jump_distances) => jump_with_distances(jump_distances, from, direction, goal),
+                None => jump(grid, from, direction, goal),
+            })
+            .map(|(x, y)| TitleCoords {
+                x: x as usize,
+                y: y as usize,
+            })
+            .collect()
+    }
+
+    fn heuristic(&self, from: TitleCoords, to: TitleCoords) -> f64 {
+        if self.is_hex {
+            return DistanceMetric::Hex.distance(from, to);
+        }
+        DistanceMetric::Octile.distance(from, to)
+    }
+}
+
+impl Measurable for JumpPointSearch {
+    fn statistics(&self) -> SearchStatistics {
+        let movement = if self.is_hex {
+            "hex, no jumps"
+        } else {
+            "8-way, no corner cutting"
+        };
+        SearchStatistics::collect(
+            self.name(),
+            &[("Movement", movement.to_string())],
+            &self.sim_coordinator,
+            &self.path_finder,
+        )
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.cost_so_far.len()
+    }
+}
+
+impl Algorithm for JumpPointSearch {
+    /// # start
+    /// Jump Point Search starts.
+    ///
+    /// Init the algorithm values. JPS+ precomputes the jump distances of the map.
+    ///
+    /// Fails with [`AlgorithmError::UnsupportedGrid`] unless the grid has 8-way movement
+    /// without corner cutting or hex movement, and uniform terrain
+    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
+        if grid.start_title.is_none() || grid.goal_title.is_none() {
+            return Err(AlgorithmError::InvalidInputData);
+        }
+        let movement_supported = matches!(
+            grid.neighbourhood(),
+            Neighbourhood::EightNoCornerCutting | Neighbourhood::Hex
+        );
+        if !movement_supported || !has_uniform_terrain(grid) {
+            return Err(AlgorithmError::UnsupportedGrid);
+        }
+        let start = grid.start_title.unwrap();
+
+        self.is_hex = grid.neighbourhood() == Neighbourhood::Hex;
+        if self.precompute && !self.is_hex {
+            self.jump_distances = Some(JumpDistances::new(grid));
+        }
+        self.priority_titles.push(start, Priority(0.0));
+        self.sim_coordinator
+            .record_generated(self.priority_titles.len());
+        self.cost_so_far.insert(start, 0.0);
+        self.path_finder.add_to_path(start, None);
+        self.sim_coordinator.start_processing();
+
+        Ok(())
+    }
+
+    /// # execute_step
+    /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
+    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+        if !self.sim_coordinator.is_ready_to_execute(delta_time) {
+            return;
+        }
+
+        if let Some((current, _priority)) = self.priority_titles.pop_min() {
+            self.sim_coordinator.increase_step_count();
+            let start = grid.start_title.unwrap();
+            let goal = grid.goal_title.unwrap();
+
+            if self.sim_coordinator.process_goal_reached(current, goal) {
+                self.path_finder.reconstruct_path(start, goal);
+                self.path_finder.interpolate_path();
+                self.path_finder.trace_back_path(grid);
+                return;
+            }
+            self.sim_coordinator.record_expansion();
+
+            let direction = self.directions.get(&current).copied();
+            for jump_point in self.successors(grid, current, direction, goal) {
+                let new_cost = *self.cost_so_far.get(&current).unwrap()
+                    + grid.centre_distance(current, jump_point);
+
+                if !self.cost_so_far.contains_key(&jump_point)
+                    || new_cost < *self.cost_so_far.get(&jump_point).unwrap()
+                {
+                    grid.mark_jump_point(jump_point);
+                    self.cost_so_far.insert(jump_point, new_cost);
+                    self.directions
+                        .insert(jump_point, direction_between(current, jump_point));
+                    let priority = new_cost + self.heuristic(jump_point, goal);
+                    self.priority_titles.push(jump_point, Priority(priority));
+                    self.sim_coordinator
+                        .record_generated(self.priority_titles.len());
+                    self.path_finder.add_to_path(jump_point, Some(current));
+                }
+            }
+            // Check if goal is unreachable
+            if self.priority_titles.is_empty() {
+                self.sim_coordinator.has_completed = true;
+                self.sim_coordinator.stop_processing();
+            }
+        } else {
+            self.sim_coordinator.stop_processing();
+        }
+    }
+
+    /// # reset
+    /// Reset the algorithm processing and clear its search overlay.
+    ///
+    /// The variant (JPS or JPS+) is kept
+    fn reset(&mut self, grid: &mut Grid) {
+        *self = Self {
+            precompute: self.precompute,
+            ..Default::default()
+        };
+        grid.clear_overlay();
+    }
+
+    /// # has_completed
+    /// Check if processing is done
+    fn has_completed(&self) -> bool {
+        self.sim_coordinator.has_completed
+    }
+
+    /// # name
+    /// Algorithm name
+    fn name(&self) -> String {
+        if self.precompute {
+            "Jump Point Search Plus".to_string()
+        } else {
+            "Jump Point Search".to_string()
+        }
+    }
+
+    /// # solution_path
+    /// Path from the goal back to the start, including the titles between the jump points.
+    /// Empty until the goal is reached
+    fn solution_path(&self) -> &[TitleCoords] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(TitleCoords, TitleCoords)> {
+        self.path_finder.take_parent_updates()
+    }
+}
+
+/// # JumpDistances
+/// JPS+ table with the jump distance of every title in every direction.
+///
+/// Positive distance leads to the jump point in that direction,
+/// otherwise it is the negated number of steps until the wall
+struct JumpDistances {
+    rows: usize,
+    distances: Vec<[i32; 8]>,
+}
+
+impl JumpDistances {
+    fn new(grid: &Grid) -> Self {
+        let columns = grid.columns as isize;
+        let rows = grid.rows as isize;
+        let mut jump_distances = Self {
+            rows: rows as usize,
+            distances: vec![[0; 8]; (columns * rows) as usize],
+        };
+
+        // Straight distances are computed first, the diagonal ones are based on them
+        for (direction_id, &direction) in DIRECTIONS.iter().enumerate() {
+            let (dx, dy) = direction;
+            // The next title in the direction is always computed before the current one
+            for x in scan_order(columns, dx) {
+                for y in scan_order(rows, dy) {
+                    if !is_walkable(grid, x, y) {
+                        continue;
+                    }
+                    let next = (x + dx, y + dy);
+                    let distance = if !can_move(grid, (x, y), direction) {
+                        0
+                    } else if dx != 0 && dy != 0 {
+                        let turns_to_jump_point = jump_distances.get(next, (dx, 0)) > 0
+                            || jump_distances.get(next, (0, dy)) > 0;
+                        if turns_to_jump_point {
+                            1
+                        } else {
+                            extend(jump_distances.get(next, direction))
+                        }
+                    } else if has_forced_neighbour(grid, next, direction) {
+                        1
+                    } else {
+                        extend(jump_distances.get(next, direction))
+                    };
+                    let id = jump_distances.index((x, y));
+                    jump_distances.distances[id][direction_id] = distance;
+                }
+            }
+        }
+        jump_distances
+    }
+
+    fn get(&self, title: (isize, isize), direction: Direction) -> i32 {
+        self.distances[self.index(title)][direction_id(direction)]
+    }
+
+    fn index(&self, (x, y): (isize, isize)) -> usize {
+        x as usize * self.rows + y as usize
+    }
+}
+
+/// One step further from the jump point or from the wall
+fn extend(distance: i32) -> i32 {
+    if distance > 0 {
+        distance + 1
+    } else {
+        distance - 1
+    }
+}
+
+fn scan_order(size: isize, direction: isize) -> Box<dyn Iterator<Item = isize>> {
+    if direction > 0 {
+        Box::new((0..size).rev())
+    } else {
+        Box::new(0..size)
+    }
+}
+
+fn direction_id(direction: Direction) -> usize {
+    DIRECTIONS
+        .iter()
+        .position(|candidate| *candidate == direction)
+        .expect("Direction is one of the 8 directions")
+}
+
+fn direction_between(from: TitleCoords, to: TitleCoords) -> Direction {
+    (
+        (to.x as isize - from.x as isize).signum(),
+        (to.y as isize - from.y as isize).signum(),
+    )
+}
+
+/// Directions worth exploring after arriving in the direction.
+/// Directions blocked by obstacles are pruned by the jump itself
+fn successor_directions(direction: Option<Direction>) -> Vec<Direction> {
+    match direction {
+        None => DIRECTIONS.to_vec(),
+        Some((dx, 0)) => vec![(dx, 0), (0, -1), (0, 1), (dx, -1), (dx, 1)],
+        Some((0, dy)) => vec![(0, dy), (-1, 0), (1, 0), (-1, dy), (1, dy)],
+        Some((dx, dy)) => vec![(dx, 0), (0, dy), (dx, dy)],
+    }
+}
+
+fn is_walkable(grid: &Grid, x: isize, y: isize) -> bool {
+    x >= 0
+        && y >= 0
+        && !grid.is_obstacle(TitleCoords {
+            x: x as usize,
+            y: y as usize,
+        })
+}
+
+/// Check the step in the direction. Diagonal steps cannot cut corners of obstacles
+fn can_move(grid: &Grid, (x, y): (isize, isize), (dx, dy): Direction) -> bool {
+    is_walkable(grid, x + dx, y + dy)
+        && (dx == 0 || dy == 0 || (is_walkable(grid, x + dx, y) && is_walkable(grid, x, y + dy)))
+}
+
+/// Check if the title entered by the straight move has a neighbour
+/// which cannot be reached optimally without passing through it
+fn has_forced_neighbour(grid: &Grid, (x, y): (isize, isize), (dx, dy): Direction) -> bool {
+    if dx != 0 {
+        (is_walkable(grid, x, y - 1) && !is_walkable(grid, x - dx, y - 1))
+            || (is_walkable(grid, x, y + 1) && !is_walkable(grid, x - dx, y + 1))
+    } else {
+        (is_walkable(grid, x - 1, y) && !is_walkable(grid, x - 1, y - dy))
+            || (is_walkable(grid, x + 1, y) && !is_walkable(grid, x + 1, y - dy))
+    }
+}
+
+/// Scan from the title in the direction until the jump point, the goal or the wall
+fn jump(
+    grid: &Grid,
+    (mut x, mut y): (isize, isize),
+    direction: Direction,
+    goal: (isize, isize),
+) -> Option<(isize, isize)> {
+    let (dx, dy) = direction;
+    loop {
+        if !can_move(grid, (x, y), direction) {
+            return None;
+        }
+        x += dx;
+        y += dy;
+
+        if (x, y) == goal {
+            return Some((x, y));
+        }
+        if dx != 0 && dy != 0 {
+            if jump(grid, (x, y), (dx, 0), goal).is_some()
+                || jump(grid, (x, y), (0, dy), goal).is_some()
+            {
+                return Some((x, y));
+            }
+        } else if has_forced_neighbour(grid, (x, y), direction) {
+            return Some((x, y));
+        }
+    }
+}
+
+/// JPS+ jump read from the precomputed table.
+/// The goal, or the title in line with it, is returned when it lies before the jump point or the wall
+fn jump_with_distances(
+    jump_distances: &JumpDistances,
+    (x, y): (isize, isize),
+    direction: Direction,
+    (goal_x, goal_y): (isize, isize),
+) -> Option<(isize, isize)> {
+    let (dx, dy) = direction;
+    let distance = jump_distances.get((x, y), direction) as isize;
+    let (to_goal_x, to_goal_y) = (goal_x - x, goal_y - y);
+
+    let target_steps = if dx != 0 && dy != 0 {
+        (to_goal_x.signum() == dx && to_goal_y.signum() == dy)
+            .then(|| to_goal_x.abs().min(to_goal_y.abs()))
+    } else if dx != 0 {
+        (to_goal_y == 0 && to_goal_x.signum() == dx).then(|| to_goal_x.abs())
+    } else {
+        (to_goal_x == 0 && to_goal_y.signum() == dy).then(|| to_goal_y.abs())
+    };
+
+    match target_steps {
+        Some(steps) if steps <= distance.abs() => Some((x + steps * dx, y + steps * dy)),
+        _ if distance > 0 => Some((x + distance * dx, y + distance * dy)),
+        _ => None,
+    }
+}
+
+/// All passable titles cost the same, so the jumps measure the path cost
+fn has_uniform_terrain(grid: &Grid) -> bool {
+    let mut costs = (0..grid.columns as usize)
+        .flat_map(|x| (0..grid.rows as usize).map(move |y| TitleCoords { x, y }))
+        .filter(|title| !grid.is_obstacle(*title))
+        .map(|title| grid.terrain_cost(grid.terrain(title)));
+    let Some(first_cost) = costs.next() else {
+        return true;
+    };
+    costs.all(|cost| cost == first_cost)
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{a_star::AStar, solver::solve},
+        map::{Neighbourhood, SearchState, Terrain},
+    };
+
+    fn maze_grid() -> Grid {
+        let mut grid = Grid::new(0, 0, 40, 30, 1);
+        // Deterministic scattered obstacles
+        let mut seed: u32 = 7;
+        for x in 0..40 {
+            for y in 0..30 {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                if (seed >> 16).is_multiple_of(4) {
+                    grid.set_obstacle(TitleCoords { x, y }).unwrap();
+                }
+            }
+        }
+        grid.set_neighbourhood(Neighbourhood::EightNoCornerCutting);
+        grid
+    }
+
+    #[test]
+    fn jump_point_search_is_optimal() {
+        let grid = maze_grid();
+        let pairs = [
+            (TitleCoords { x: 0, y: 0 }, TitleCoords { x: 39, y: 29 }),
+            (TitleCoords { x: 5, y: 20 }, TitleCoords { x: 33, y: 2 }),
+            (TitleCoords { x: 38, y: 1 }, TitleCoords { x: 2, y: 27 }),
+            (TitleCoords { x: 10, y: 10 }, TitleCoords { x: 10, y: 25 }),
+        ];
+
+        let mut reachable_pairs = 0;
+        for (start, goal) in pairs {
+            if grid.is_obstacle(start) || grid.is_obstacle(goal) {
+                continue;
+            }
+            let a_star = solve(&grid, start, goal, AStar::default()).unwrap();
+            let jps = solve(&grid, start, goal, JumpPointSearch::default()).unwrap();
+            let jps_plus = solve(&grid, start, goal, JumpPointSearch::plus()).unwrap();
+
+            assert_eq!(a_star.is_goal_reachable(), jps.is_goal_reachable());
+            assert_eq!(a_star.is_goal_reachable(), jps_plus.is_goal_reachable());
+            assert!((a_star.cost - jps.cost).abs() < 1e-9);
+            assert!((a_star.cost - jps_plus.cost).abs() < 1e-9);
+            if jps.is_goal_reachable() {
+                reachable_pairs += 1;
+                assert_eq!(start, jps.path[0]);
+                assert_eq!(goal, *jps_plus.path.last().unwrap());
+            }
+        }
+        assert!(reachable_pairs > 1);
+    }
+
+    #[test]
+    fn jump_point_search_on_open_grid() {
+        let mut grid = Grid::new(0, 0, 50, 50, 1);
+        grid.set_neighbourhood(Neighbourhood::EightNoCornerCutting);
+        for y in 10..40 {
+            grid.set_obstacle(TitleCoords { x: 25, y }).unwrap();
+        }
+        let start = TitleCoords { x: 3, y: 25 };
+        let goal = TitleCoords { x: 47, y: 24 };
+
+        let a_star = solve(&grid, start, goal, AStar::default()).unwrap();
+        let jps = solve(&grid, start, goal, JumpPointSearch::default()).unwrap();
+        let jps_plus = solve(&grid, start, goal, JumpPointSearch::plus()).unwrap();
+
+        assert!((a_star.cost - jps.cost).abs() < 1e-9);
+        assert!((a_star.cost - jps_plus.cost).abs() < 1e-9);
+        assert!(jps.statistics.expanded_nodes * 10 < a_star.statistics.expanded_nodes);
+        assert!(jps_plus.statistics.expanded_nodes * 10 < a_star.statistics.expanded_nodes);
+        assert!(jps.overlay.count(SearchState::JumpPoint) > 0);
+        // Consecutive path titles are neighbours
+        for step in jps_plus.path.windows(2) {
+            assert!(step[0].x.abs_diff(step[1].x) <= 1 && step[0].y.abs_diff(step[1].y) <= 1);
+        }
+    }
+
+    #[test]
+    fn jump_point_search_rejects_unsupported_grids() {
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 9, y: 9 };
+        for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight] {
+            let mut grid = Grid::new(0, 0, 10, 10, 1);
+            grid.set_neighbourhood(neighbourhood);
+            assert!(matches!(
+                solve(&grid, start, goal, JumpPointSearch::default()),
+                Err(AlgorithmError::UnsupportedGrid)
+            ));
+            assert!(matches!(
+                solve(&grid, start, goal, JumpPointSearch::plus()),
+                Err(AlgorithmError::UnsupportedGrid)
+            ));
+        }
+
+        for neighbourhood in [Neighbourhood::EightNoCornerCutting, Neighbourhood::Hex] {
+            let mut grid = Grid::new(0, 0, 10, 10, 1);
+            grid.set_neighbourhood(neighbourhood);
+            // Every title of the same terrain is uniform
+            for x in 0..10 {
+                for y in 0..10 {
+                    grid.set_terrain(TitleCoords { x, y }, Terrain::Mud);
+                }
+            }
+            grid.set_obstacle(TitleCoords { x: 5, y: 5 }).unwrap();
+            assert!(solve(&grid, start, goal, JumpPointSearch::default())
+                .unwrap()
+                .is_goal_reachable());
+
+            grid.set_terrain(TitleCoords { x: 3, y: 4 }, Terrain::Water);
+            assert!(matches!(
+                solve(&grid, start, goal, JumpPointSearch::default()),
+                Err(AlgorithmError::UnsupportedGrid)
+            ));
+            // Terrain of the same cost is uniform too
+            grid.set_terrain_cost(Terrain::Water, grid.terrain_cost(Terrain::Mud));
+            assert!(solve(&grid, start, goal, JumpPointSearch::plus()).is_ok());
+        }
+    }
+
+    #[test]
+    fn jump_distances() {
+        let mut grid = Grid::new(0, 0, 5, 3, 1);
+        grid.set_obstacle(TitleCoords { x: 2, y: 0 }).unwrap();
+        let jump_distances = JumpDistances::new(&grid);
+
+        // Moving east the obstacle at (2, 0) forces the neighbour (3, 0) of the title (3, 1)
+        assert_eq!(3, jump_distances.get((0, 1), (1, 0)));
+        assert_eq!(-4, jump_distances.get((0, 2), (1, 0)));
+        assert_eq!(0, jump_distances.get((1, 0), (1, 0)));
+        assert_eq!(-1, jump_distances.get((4, 1), (0, 1)));
+    }
+}
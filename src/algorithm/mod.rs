@@ -1,28 +1,263 @@
 use crate::map::{grid::Grid, TitleCoords};
-use std::collections::HashMap;
+use search_space::SearchSpace;
+use statistics::SearchStatistics;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
 pub mod a_star;
 pub mod bfs;
+pub mod bidirectional;
+pub mod d_star_lite;
+pub mod dfs;
 pub mod dijkstra;
 pub mod greedy_bfs;
+pub mod heuristic;
+pub mod iterative_deepening;
+pub mod jump_point_search;
+pub mod race;
+pub mod search_space;
+pub mod solver;
+pub mod statistics;
+pub mod stepping;
+pub mod theta_star;
+pub mod timeline;
 
-const ONE_ITERATION_TIME_SEC: f64 = 0.01;
+pub(crate) const ONE_ITERATION_TIME_SEC: f64 = 0.01;
+
+/// # Priority
+/// Priority of the title in the priority queue.
+///
+/// Movement costs are floating point numbers, so they are ordered with `total_cmp`
+#[derive(Debug, Clone, Copy)]
+pub struct Priority(pub f64);
+
+impl PartialEq for Priority {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Priority {}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
 
 #[derive(Debug)]
 pub enum AlgorithmError {
     InvalidInputData,
     AlgorithmDoesNotExist,
+    /// The algorithm works on the grid only
+    UnsupportedSearchSpace,
+    /// The movement or the terrain of the grid is not supported by the algorithm
+    UnsupportedGrid,
+    /// The algorithm took no step although it has not completed
+    Stalled,
 }
 
 pub trait Measurable {
-    fn output_statistics(&self) -> String;
+    /// # statistics
+    /// Statistics of the current search
+    fn statistics(&self) -> SearchStatistics;
+    fn steps_taken(&self) -> u32;
+    fn visited_nodes(&self) -> usize;
+
+    /// # output_statistics
+    /// Statistics formatted for the screen
+    fn output_statistics(&self) -> String {
+        self.statistics().to_string()
+    }
 }
 
-pub trait Algorithm: Measurable {
-    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError>;
-    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64);
-    fn reset(&mut self, grid: &mut Grid);
+/// # Algorithm
+/// Step by step search in the search space, the grid by default
+pub trait Algorithm<S: SearchSpace = Grid>: Measurable {
+    fn start(&mut self, space: &mut S) -> Result<(), AlgorithmError>;
+    fn execute_step(&mut self, space: &mut S, delta_time: f64);
+    fn reset(&mut self, space: &mut S);
     fn has_completed(&self) -> bool;
     fn name(&self) -> String;
+    fn solution_path(&self) -> &[S::Node];
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call, e.g. to record the search.
+    ///
+    /// The first call starts the recording. Nothing is recorded by default
+    fn take_parent_updates(&mut self) -> Vec<(S::Node, S::Node)> {
+        Vec::new()
+    }
+}
+
+impl<A: Measurable + ?Sized> Measurable for Box<A> {
+    fn statistics(&self) -> SearchStatistics {
+        (**self).statistics()
+    }
+    fn steps_taken(&self) -> u32 {
+        (**self).steps_taken()
+    }
+    fn visited_nodes(&self) -> usize {
+        (**self).visited_nodes()
+    }
+}
+
+impl<S: SearchSpace, A: Algorithm<S> + ?Sized> Algorithm<S> for Box<A> {
+    fn start(&mut self, space: &mut S) -> Result<(), AlgorithmError> {
+        (**self).start(space)
+    }
+    fn execute_step(&mut self, space: &mut S, delta_time: f64) {
+        (**self).execute_step(space, delta_time)
+    }
+    fn reset(&mut self, space: &mut S) {
+        (**self).reset(space)
+    }
+    fn has_completed(&self) -> bool {
+        (**self).has_completed()
+    }
+    fn name(&self) -> String {
+        (**self).name()
+    }
+    fn solution_path(&self) -> &[S::Node] {
+        (**self).solution_path()
+    }
+    fn take_parent_updates(&mut self) -> Vec<(S::Node, S::Node)> {
+        (**self).take_parent_updates()
+    }
+}
+
+/// # AlgorithmKind
+/// Identifies the algorithm, e.g. to select it from the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlgorithmKind {
+    Bfs,
+    Dijkstra,
+    GreedyBfs,
+    AStar,
+    JumpPointSearch,
+    JumpPointSearchPlus,
+    BidirectionalBfs,
+    BidirectionalDijkstra,
+    BidirectionalAStar,
+    DStarLite,
+    ThetaStar,
+    LazyThetaStar,
+    Dfs,
+    Iddfs,
+    IdaStar,
+}
+
+impl AlgorithmKind {
+    pub const ALL: [AlgorithmKind; 15] = [
+        AlgorithmKind::Bfs,
+        AlgorithmKind::Dijkstra,
+        AlgorithmKind::GreedyBfs,
+        AlgorithmKind::AStar,
+        AlgorithmKind::JumpPointSearch,
+        AlgorithmKind::JumpPointSearchPlus,
+        AlgorithmKind::BidirectionalBfs,
+        AlgorithmKind::BidirectionalDijkstra,
+        AlgorithmKind::BidirectionalAStar,
+        AlgorithmKind::DStarLite,
+        AlgorithmKind::ThetaStar,
+        AlgorithmKind::LazyThetaStar,
+        AlgorithmKind::Dfs,
+        AlgorithmKind::Iddfs,
+        AlgorithmKind::IdaStar,
+    ];
+
+    /// # id
+    /// Short name of the algorithm: bfs, dijkstra, greedy_bfs, a_star, jps, jps_plus,
+    /// bi_bfs, bi_dijkstra, bi_a_star, d_star_lite, theta_star, lazy_theta_star, dfs, iddfs, ida_star
+    pub fn id(&self) -> &'static str {
+        match self {
+            AlgorithmKind::Bfs => "bfs",
+            AlgorithmKind::Dijkstra => "dijkstra",
+            AlgorithmKind::GreedyBfs => "greedy_bfs",
+            AlgorithmKind::AStar => "a_star",
+            AlgorithmKind::JumpPointSearch => "jps",
+            AlgorithmKind::JumpPointSearchPlus => "jps_plus",
+            AlgorithmKind::BidirectionalBfs => "bi_bfs",
+            AlgorithmKind::BidirectionalDijkstra => "bi_dijkstra",
+            AlgorithmKind::BidirectionalAStar => "bi_a_star",
+            AlgorithmKind::DStarLite => "d_star_lite",
+            AlgorithmKind::ThetaStar => "theta_star",
+            AlgorithmKind::LazyThetaStar => "lazy_theta_star",
+            AlgorithmKind::Dfs => "dfs",
+            AlgorithmKind::Iddfs => "iddfs",
+            AlgorithmKind::IdaStar => "ida_star",
+        }
+    }
+
+    /// # is_iterative_deepening
+    /// Iterative deepening repeats the search for every limit, which takes very long on big maps
+    pub fn is_iterative_deepening(&self) -> bool {
+        matches!(self, AlgorithmKind::Iddfs | AlgorithmKind::IdaStar)
+    }
+
+    /// # from_id
+    /// Find the algorithm by its short name
+    pub fn from_id(id: &str) -> Result<Self, AlgorithmError> {
+        AlgorithmKind::ALL
+            .into_iter()
+            .find(|kind| kind.id() == id)
+            .ok_or(AlgorithmError::AlgorithmDoesNotExist)
+    }
+
+    /// # create
+    /// Create a fresh instance of the algorithm
+    pub fn create(&self) -> Box<dyn Algorithm> {
+        match self {
+            AlgorithmKind::Bfs => Box::new(bfs::Bfs::default()),
+            AlgorithmKind::Dijkstra => Box::new(dijkstra::Dijkstra::default()),
+            AlgorithmKind::GreedyBfs => Box::new(greedy_bfs::GreedyBfs::default()),
+            AlgorithmKind::AStar => Box::new(a_star::AStar::default()),
+            AlgorithmKind::JumpPointSearch => {
+                Box::new(jump_point_search::JumpPointSearch::default())
+            }
+            AlgorithmKind::JumpPointSearchPlus => {
+                Box::new(jump_point_search::JumpPointSearch::plus())
+            }
+            AlgorithmKind::BidirectionalBfs => Box::new(bidirectional::BidirectionalSearch::bfs()),
+            AlgorithmKind::BidirectionalDijkstra => {
+                Box::new(bidirectional::BidirectionalSearch::dijkstra())
+            }
+            AlgorithmKind::BidirectionalAStar => {
+                Box::new(bidirectional::BidirectionalSearch::a_star())
+            }
+            AlgorithmKind::DStarLite => Box::new(d_star_lite::DStarLite::default()),
+            AlgorithmKind::ThetaStar => Box::new(theta_star::ThetaStar::default()),
+            AlgorithmKind::LazyThetaStar => Box::new(theta_star::ThetaStar::lazy()),
+            AlgorithmKind::Dfs => Box::new(dfs::Dfs::default()),
+            AlgorithmKind::Iddfs => Box::new(iterative_deepening::Iddfs::default()),
+            AlgorithmKind::IdaStar => Box::new(iterative_deepening::IdaStar::default()),
+        }
+    }
+
+    /// # create_for
+    /// Create a fresh instance of the algorithm searching any search space, e.g. a road graph.
+    ///
+    /// Only Bfs, Dijkstra, Greedy Bfs and A* are not tied to the grid
+    pub fn create_for<S: SearchSpace + 'static>(
+        &self,
+    ) -> Result<Box<dyn Algorithm<S>>, AlgorithmError> {
+        match self {
+            AlgorithmKind::Bfs => Ok(Box::new(bfs::Bfs::new())),
+            AlgorithmKind::Dijkstra => Ok(Box::new(dijkstra::Dijkstra::new())),
+            AlgorithmKind::GreedyBfs => Ok(Box::new(greedy_bfs::GreedyBfs::new())),
+            AlgorithmKind::AStar => Ok(Box::new(a_star::AStar::new())),
+            _ => Err(AlgorithmError::UnsupportedSearchSpace),
+        }
+    }
 }
 
 pub struct SimulationCoordinator {
@@ -30,6 +265,11 @@ pub struct SimulationCoordinator {
     steps: u32,
     accumulated_time: f64,
     has_completed: bool,
+    expanded_nodes: u32,
+    generated_nodes: u32,
+    max_frontier_size: usize,
+    started_at: Option<Instant>,
+    elapsed_time: Duration,
 }
 
 impl Default for SimulationCoordinator {
@@ -39,6 +279,11 @@ impl Default for SimulationCoordinator {
             steps: 0,
             accumulated_time: 0.0,
             has_completed: false,
+            expanded_nodes: 0,
+            generated_nodes: 0,
+            max_frontier_size: 0,
+            started_at: None,
+            elapsed_time: Duration::ZERO,
         }
     }
 }
@@ -54,16 +299,44 @@ impl SimulationCoordinator {
         self.steps += 1;
     }
 
+    /// Count the title whose neighbours are explored
+    pub fn record_expansion(&mut self) {
+        self.expanded_nodes += 1;
+    }
+
+    /// Count the title added to the frontier of the given size
+    pub fn record_generated(&mut self, frontier_size: usize) {
+        self.generated_nodes += 1;
+        self.max_frontier_size = self.max_frontier_size.max(frontier_size);
+    }
+
     pub fn start_processing(&mut self) {
         self.is_processing = true;
+        self.started_at.get_or_insert_with(Instant::now);
     }
 
     pub fn stop_processing(&mut self) {
         self.is_processing = false;
+        if let Some(started_at) = self.started_at {
+            self.elapsed_time = started_at.elapsed();
+        }
+    }
+
+    /// Wall time from the start of the search until it stopped or until now
+    pub fn elapsed_time(&self) -> Duration {
+        match self.started_at {
+            Some(started_at) if self.is_processing => started_at.elapsed(),
+            _ => self.elapsed_time,
+        }
     }
 
     pub fn process_goal_reached(&mut self, current: TitleCoords, goal: TitleCoords) -> bool {
-        if current == goal {
+        self.process_goal_test(current == goal)
+    }
+
+    /// Complete the search if the goal test of the search space passed
+    pub fn process_goal_test(&mut self, is_goal: bool) -> bool {
+        if is_goal {
             self.has_completed = true;
             self.stop_processing();
             return true;
@@ -81,53 +354,169 @@ impl SimulationCoordinator {
     }
 }
 
-fn get_statistics(
-    algorithm_name: &str,
-    path_length: usize,
-    steps: u32,
-    num_visited_titles: usize,
-) -> String {
-    if path_length == 0 {
-        return "Goal is unreachable !".to_string();
-    }
-
-    format!(
-            " {}\n\n Statistics:\n - Path length: {}\n - Steps taken: {}\n - Visited nodes: {}\n - Time per iteration: {:.2} sec\n - Total time: {:.2} sec",
-            algorithm_name,
-            path_length,
-            steps,
-            num_visited_titles,
-            ONE_ITERATION_TIME_SEC,
-            steps as f64 * ONE_ITERATION_TIME_SEC
-        )
+/// # Pathfinder
+/// Remembers where every node was reached from and reconstructs the path
+pub struct Pathfinder<N = TitleCoords> {
+    path: HashMap<N, Option<N>>,
+    solution_path: Vec<N>,
+    path_cost: f64,
+    euclidean_length: f64,
+    /// (node, parent) pairs set since the last take, when they are recorded
+    parent_updates: Option<Vec<(N, N)>>,
 }
 
-#[derive(Default)]
-pub struct Pathfinder {
-    path: HashMap<TitleCoords, Option<TitleCoords>>,
-    solution_path: Vec<TitleCoords>,
+impl<N> Default for Pathfinder<N> {
+    fn default() -> Self {
+        Self {
+            path: HashMap::new(),
+            solution_path: Vec::new(),
+            path_cost: 0.0,
+            euclidean_length: 0.0,
+            parent_updates: None,
+        }
+    }
 }
 
-impl Pathfinder {
-    pub fn add_to_path(&mut self, came_from: TitleCoords, to: Option<TitleCoords>) {
+impl<N: Copy + Eq + Hash> Pathfinder<N> {
+    pub fn add_to_path(&mut self, came_from: N, to: Option<N>) {
+        if let (Some(parent_updates), Some(parent)) = (&mut self.parent_updates, to) {
+            parent_updates.push((came_from, parent));
+        }
         self.path.insert(came_from, to);
     }
-    pub fn reconstruct_path(&mut self, start: TitleCoords, goal: TitleCoords) {
-        let mut current = goal;
 
-        while current != start {
-            self.solution_path.push(current);
+    /// (node, parent) pairs set since the last call. The first call starts the recording
+    pub fn take_parent_updates(&mut self) -> Vec<(N, N)> {
+        self.parent_updates.replace(Vec::new()).unwrap_or_default()
+    }
+
+    /// The title the title was reached from. None for the first title or unknown titles
+    pub fn parent(&self, title: N) -> Option<N> {
+        self.path.get(&title).copied().flatten()
+    }
+    pub fn reconstruct_path(&mut self, start: N, goal: N) {
+        let path = self.follow_path(goal, start);
+        self.solution_path.extend(path);
+    }
+
+    /// Join the half-paths of the bidirectional search which met in the meeting title.
+    ///
+    /// This path finder holds the half from the start, the backward one holds the half
+    /// from the goal, where every title points towards the goal
+    pub fn reconstruct_joined_path(
+        &mut self,
+        backward: &Pathfinder<N>,
+        start: N,
+        meeting: N,
+        goal: N,
+    ) {
+        let mut path = backward.follow_path(meeting, goal);
+        path.reverse();
+        path.pop();
+        path.extend(self.follow_path(meeting, start));
+        self.solution_path.extend(path);
+    }
+
+    /// Titles from the title to the end of its path, both included
+    fn follow_path(&self, from: N, to: N) -> Vec<N> {
+        let mut path = Vec::new();
+        let mut current = from;
+
+        while current != to {
+            path.push(current);
             let c = *self
                 .path
                 .get(&current)
                 .expect("Title for path reconstructing is wrong");
             current = c.unwrap();
         }
-        self.solution_path.push(start);
+        path.push(to);
+        path
     }
-    pub fn get_path(&self) -> &Vec<TitleCoords> {
+
+    pub fn get_path(&self) -> &Vec<N> {
         &self.solution_path
     }
+
+    /// Show the reconstructed path in the search space and sum its movement cost
+    pub fn trace_back_path<S: SearchSpace<Node = N>>(&mut self, space: &mut S) {
+        for element in self.solution_path.iter() {
+            space.mark_path(*element);
+        }
+        // The path goes from the goal back to the start. The empty sum of f64 is -0.0,
+        // so the sums are folded from 0.0 for the path of one title
+        self.path_cost = self
+            .solution_path
+            .windows(2)
+            .map(|step| space.cost(step[1], step[0]))
+            .fold(0.0, |sum, cost| sum + cost);
+        self.euclidean_length = self
+            .solution_path
+            .windows(2)
+            .filter_map(|step| space.distance(step[0], step[1]))
+            .fold(0.0, |sum, distance| sum + distance);
+    }
+
+    /// Length of the path measured along the straight lines between its titles.
+    /// 0 if the nodes of the search space have no position
+    pub fn euclidean_length(&self) -> f64 {
+        self.euclidean_length
+    }
+
+    pub fn path_cost(&self) -> f64 {
+        self.path_cost
+    }
+}
+
+impl Pathfinder {
+    /// Fill the titles between consecutive path points connected by a straight or diagonal line,
+    /// e.g. between jump points
+    pub fn interpolate_path(&mut self) {
+        let mut path = Vec::with_capacity(self.solution_path.len());
+        for segment in self.solution_path.windows(2) {
+            let (mut current, to) = (segment[0], segment[1]);
+            while current != to {
+                path.push(current);
+                current = TitleCoords {
+                    x: step_towards(current.x, to.x),
+                    y: step_towards(current.y, to.y),
+                };
+            }
+        }
+        path.extend(self.solution_path.last());
+        self.solution_path = path;
+    }
+
+    /// Show the reconstructed any-angle path on the grid as straight segments between
+    /// the waypoints.
+    ///
+    /// Every title entered by a segment costs its terrain cost times the part of the segment
+    /// crossing it, so a step between neighbours costs the same as on the grid
+    pub fn trace_back_waypoints(&mut self, grid: &mut Grid) {
+        self.euclidean_length = 0.0;
+        self.path_cost = 0.0;
+        for segment in self.solution_path.windows(2) {
+            let titles = grid.line_titles(segment[0], segment[1]);
+            let length = grid.centre_distance(segment[0], segment[1]);
+            let crossed_length = length / (titles.len() - 1).max(1) as f64;
+            for title in titles.iter().skip(1) {
+                self.path_cost += grid.terrain_cost(grid.terrain(*title)) * crossed_length;
+            }
+            for title in titles {
+                grid.set_trace_back_path(title);
+            }
+            self.euclidean_length += length;
+        }
+        grid.set_path_waypoints(&self.solution_path);
+    }
+}
+
+fn step_towards(from: usize, to: usize) -> usize {
+    match from.cmp(&to) {
+        Ordering::Less => from + 1,
+        Ordering::Greater => from - 1,
+        Ordering::Equal => from,
+    }
 }
 
 #[cfg(test)]
@@ -185,4 +574,56 @@ mod unit_test {
 
         assert_eq!(exp_solution_path, *path_finder.get_path());
     }
+
+    #[test]
+    fn joined_path_finding() {
+        // [start] -> [1] -> [meeting] <- [2] <- [goal]
+        let start = TitleCoords { x: 0, y: 0 };
+        let path_1 = TitleCoords { x: 1, y: 0 };
+        let meeting = TitleCoords { x: 2, y: 0 };
+        let path_2 = TitleCoords { x: 3, y: 0 };
+        let goal = TitleCoords { x: 4, y: 0 };
+
+        let mut forward = Pathfinder::default();
+        forward.add_to_path(start, None);
+        forward.add_to_path(path_1, Some(start));
+        forward.add_to_path(meeting, Some(path_1));
+
+        let mut backward = Pathfinder::default();
+        backward.add_to_path(goal, None);
+        backward.add_to_path(path_2, Some(goal));
+        backward.add_to_path(meeting, Some(path_2));
+
+        forward.reconstruct_joined_path(&backward, start, meeting, goal);
+
+        assert_eq!(
+            vec![goal, path_2, meeting, path_1, start],
+            *forward.get_path()
+        );
+    }
+
+    #[test]
+    fn path_interpolation() {
+        let mut path_finder = Pathfinder::default();
+        let start = TitleCoords { x: 0, y: 0 };
+        let jump_point = TitleCoords { x: 2, y: 2 };
+        let goal = TitleCoords { x: 2, y: 4 };
+
+        path_finder.add_to_path(start, None);
+        path_finder.add_to_path(jump_point, Some(start));
+        path_finder.add_to_path(goal, Some(jump_point));
+        path_finder.reconstruct_path(start, goal);
+        path_finder.interpolate_path();
+
+        assert_eq!(
+            vec![
+                goal,
+                TitleCoords { x: 2, y: 3 },
+                jump_point,
+                TitleCoords { x: 1, y: 1 },
+                start
+            ],
+            *path_finder.get_path()
+        );
+    }
 }
@@ -0,0 +1,251 @@
+//! # Race
+//! Several algorithms search their own copy of the same map step by step in lockstep,
+//! so their expansions and paths can be compared side by side.
+use super::{statistics::SearchStatistics, Algorithm, AlgorithmError, AlgorithmKind};
+use crate::map::grid::Grid;
+
+/// Algorithms raced by default
+pub const DEFAULT_KINDS: [AlgorithmKind; 4] = [
+    AlgorithmKind::Bfs,
+    AlgorithmKind::Dijkstra,
+    AlgorithmKind::GreedyBfs,
+    AlgorithmKind::AStar,
+];
+/// Height of the name above every viewport
+pub const LABEL_HEIGHT: u32 = 16;
+
+/// # RaceLane
+/// The algorithm with its own copy of the map
+pub struct RaceLane {
+    algorithm: Box<dyn Algorithm>,
+    grid: Grid,
+    /// Race step in which the search completed
+    finished_at: Option<u32>,
+}
+
+impl RaceLane {
+    /// # algorithm
+    /// The racing algorithm
+    pub fn algorithm(&self) -> &dyn Algorithm {
+        self.algorithm.as_ref()
+    }
+
+    /// # grid
+    /// Map searched by the algorithm
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// # grid_mut
+    /// Map searched by the algorithm, e.g. to render it
+    pub fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    /// # finished_at
+    /// Race step in which the search completed. None while it runs
+    pub fn finished_at(&self) -> Option<u32> {
+        self.finished_at
+    }
+}
+
+/// # Race
+/// Algorithms searching the same map in lockstep.
+///
+/// ```
+/// use r_path_finder::algorithm::race::{Race, DEFAULT_KINDS};
+/// use r_path_finder::map::{grid::Grid, TitleCoords};
+///
+/// let grid = Grid::builder(10, 10)
+///     .start(TitleCoords { x: 0, y: 0 })
+///     .goal(TitleCoords { x: 9, y: 9 })
+///     .build()
+///     .unwrap();
+/// let mut race = Race::from_kinds(&grid, &DEFAULT_KINDS).unwrap();
+/// while race.step() {}
+/// println!("{}", race.standings());
+/// ```
+pub struct Race {
+    lanes: Vec<RaceLane>,
+    /// Number of executed race steps
+    steps: u32,
+}
+
+impl Race {
+    /// # new
+    /// Start every algorithm on its own copy of the map. The search of the map is not copied
+    pub fn new(grid: &Grid, algorithms: Vec<Box<dyn Algorithm>>) -> Result<Race, AlgorithmError> {
+        let lanes = algorithms
+            .into_iter()
+            .map(|mut algorithm| {
+                let mut grid = grid.clone();
+                grid.record_overlay_changes(false);
+                grid.clear_overlay();
+                algorithm.start(&mut grid)?;
+                Ok(RaceLane {
+                    algorithm,
+                    grid,
+                    finished_at: None,
+                })
+            })
+            .collect::<Result<Vec<RaceLane>, AlgorithmError>>()?;
+        Ok(Race { lanes, steps: 0 })
+    }
+
+    /// # from_kinds
+    /// Race fresh instances of the algorithms
+    pub fn from_kinds(grid: &Grid, kinds: &[AlgorithmKind]) -> Result<Race, AlgorithmError> {
+        Race::new(grid, kinds.iter().map(AlgorithmKind::create).collect())
+    }
+
+    /// # lanes
+    /// Racing algorithms in the given order
+    pub fn lanes(&self) -> &[RaceLane] {
+        &self.lanes
+    }
+
+    /// # lanes_mut
+    /// Racing algorithms in the given order, e.g. to render their maps
+    pub fn lanes_mut(&mut self) -> &mut [RaceLane] {
+        &mut self.lanes
+    }
+
+    /// # steps
+    /// Number of executed race steps
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// # is_finished
+    /// Every search has completed
+    pub fn is_finished(&self) -> bool {
+        self.lanes.iter().all(|lane| lane.algorithm.has_completed())
+    }
+
+    /// # step
+    /// Execute one step of every running search. False when all of them have completed
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.steps += 1;
+        for lane in self.lanes.iter_mut() {
+            if lane.algorithm.has_completed() {
+                continue;
+            }
+            lane.algorithm
+                .execute_step(&mut lane.grid, super::ONE_ITERATION_TIME_SEC);
+            if lane.algorithm.has_completed() {
+                lane.finished_at = Some(self.steps);
+            }
+        }
+        true
+    }
+
+    /// # statistics
+    /// Statistics of every search in the order of the lanes
+    pub fn statistics(&self) -> Vec<SearchStatistics> {
+        self.lanes
+            .iter()
+            .map(|lane| lane.algorithm.statistics())
+            .collect()
+    }
+
+    /// # standings
+    /// Place, expansions and path of every search formatted for the screen.
+    ///
+    /// The searches which completed in the same step share the place
+    pub fn standings(&self) -> String {
+        let mut standings = format!(" Race after {} steps\n", self.steps);
+        for lane in self.lanes.iter() {
+            let statistics = lane.algorithm.statistics();
+            let state = match lane.finished_at {
+                None => "running".to_string(),
+                Some(_) if !statistics.goal_reachable => "no path".to_string(),
+                Some(finished_at) => {
+                    let place = 1 + self
+                        .lanes
+                        .iter()
+                        .filter(|other| other.finished_at.is_some_and(|other| other < finished_at))
+                        .count();
+                    format!("#{}, cost {:.1}", place, statistics.path_cost)
+                }
+            };
+            standings.push_str(&format!(
+                "\n {}: {}\n  expanded {}, frontier {}",
+                statistics.algorithm,
+                state,
+                statistics.expanded_nodes,
+                statistics.max_frontier_size
+            ));
+        }
+        standings
+    }
+
+    /// # arrange
+    /// Split the screen area (x, y, width, height) into the viewports of the lanes.
+    /// Every map is scaled into its viewport under the name of its algorithm
+    pub fn arrange(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let columns = (self.lanes.len() as f64).sqrt().ceil().max(1.0) as u32;
+        let rows = (self.lanes.len() as u32).div_ceil(columns).max(1);
+        let (viewport_width, viewport_height) = (width / columns, height / rows);
+        for (lane_id, lane) in self.lanes.iter_mut().enumerate() {
+            let (column, row) = (lane_id as u32 % columns, lane_id as u32 / columns);
+            lane.grid.fit_into(
+                viewport_width.saturating_sub(LABEL_HEIGHT / 2),
+                viewport_height.saturating_sub(LABEL_HEIGHT * 3 / 2),
+            );
+            lane.grid.set_offset(
+                x + column * viewport_width,
+                y + row * viewport_height + LABEL_HEIGHT,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::map::{SearchState, TitleCoords};
+
+    #[test]
+    fn lockstep_race() {
+        let grid = Grid::builder(12, 12)
+            .start(TitleCoords { x: 0, y: 0 })
+            .goal(TitleCoords { x: 11, y: 11 })
+            .obstacles((0..10).map(|x| TitleCoords { x, y: 6 }))
+            .build()
+            .unwrap();
+        let mut race = Race::from_kinds(&grid, &DEFAULT_KINDS).unwrap();
+        assert_eq!(4, race.lanes().len());
+
+        race.step();
+        race.step();
+        // Every running search executed the same number of steps
+        for lane in race.lanes() {
+            assert_eq!(2, lane.algorithm().steps_taken());
+        }
+        while race.step() {}
+        assert!(race.is_finished());
+        assert!(!race.step());
+
+        let statistics = race.statistics();
+        assert!(statistics
+            .iter()
+            .all(|statistics| statistics.goal_reachable));
+        // Bfs, Dijkstra and A* find the shortest path on the plain map
+        assert_eq!(statistics[0].path_cost, statistics[1].path_cost);
+        assert_eq!(statistics[1].path_cost, statistics[3].path_cost);
+        assert!(statistics[3].expanded_nodes <= statistics[1].expanded_nodes);
+        assert!(race.standings().contains("#1, cost"));
+
+        // The searched maps are copies
+        assert_eq!(0, grid.overlay().count(SearchState::Visited));
+        assert!(race.lanes()[0].grid().overlay().count(SearchState::Path) > 0);
+
+        race.arrange(0, 0, 400, 400);
+        assert_eq!((200, LABEL_HEIGHT), race.lanes()[1].grid().offset());
+        assert_eq!((0, 200 + LABEL_HEIGHT), race.lanes()[2].grid().offset());
+        assert_eq!(14, race.lanes()[3].grid().title_size());
+    }
+}
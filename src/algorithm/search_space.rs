@@ -0,0 +1,266 @@
+//! # Search space
+//! Graph the generic algorithms (Bfs, Dijkstra, Greedy Bfs and A*) search in.
+//!
+//! [`Grid`] is one search space, where the nodes are the titles. Road graphs, navmeshes
+//! or state-space problems implement [`SearchSpace`] to reuse the same algorithms.
+use crate::map::{grid::Grid, TitleCoords};
+use std::{fmt::Debug, hash::Hash};
+
+pub trait SearchSpace {
+    /// Node of the graph, e.g. the title coordinates
+    type Node: Copy + Eq + Hash + Debug;
+
+    /// # start_node
+    /// Node the search starts from. None if the search can not start, e.g. the goal is missing
+    fn start_node(&self) -> Option<Self::Node>;
+
+    /// # is_goal
+    /// Goal test
+    fn is_goal(&self, node: Self::Node) -> bool;
+
+    /// # successors
+    /// Nodes reachable in one move with the cost of the move
+    fn successors(&self, node: Self::Node) -> Vec<(Self::Node, f64)>;
+
+    /// # estimate
+    /// Heuristic - estimated cost from the node to the goal. No estimation by default
+    fn estimate(&self, _node: Self::Node) -> f64 {
+        0.0
+    }
+
+    /// # heuristic_name
+    /// Name of the heuristic shown in the statistics
+    fn heuristic_name(&self) -> String {
+        "Search space".to_string()
+    }
+
+    /// # cost
+    /// Cost of the move between the nodes. Infinite if the node is not a successor
+    fn cost(&self, from: Self::Node, to: Self::Node) -> f64 {
+        self.successors(from)
+            .into_iter()
+            .find(|(successor, _)| *successor == to)
+            .map_or(f64::INFINITY, |(_, cost)| cost)
+    }
+
+    /// # distance
+    /// Straight line distance between the nodes, if the nodes have a position.
+    /// Used to report the Euclidean length of the path
+    fn distance(&self, _from: Self::Node, _to: Self::Node) -> Option<f64> {
+        None
+    }
+
+    /// # mark_visited
+    /// Visualization of the expanded node. Nothing is drawn by default
+    fn mark_visited(&mut self, _node: Self::Node) {}
+
+    /// # mark_process
+    /// Visualization of the node added to the frontier. Nothing is drawn by default
+    fn mark_process(&mut self, _node: Self::Node) {}
+
+    /// # mark_path
+    /// Visualization of the node on the found path. Nothing is drawn by default
+    fn mark_path(&mut self, _node: Self::Node) {}
+
+    /// # clear_marks
+    /// Remove the visualization of the search. Nothing is drawn by default
+    fn clear_marks(&mut self) {}
+}
+
+impl SearchSpace for Grid {
+    type Node = TitleCoords;
+
+    fn start_node(&self) -> Option<TitleCoords> {
+        self.goal_title.and(self.start_title)
+    }
+
+    fn is_goal(&self, node: TitleCoords) -> bool {
+        self.goal_title == Some(node)
+    }
+
+    fn successors(&self, node: TitleCoords) -> Vec<(TitleCoords, f64)> {
+        self.get_neighbors(node)
+            .into_iter()
+            .map(|neighbor| (neighbor, Grid::cost(self, node, neighbor)))
+            .collect()
+    }
+
+    /// The grid distance metric to the goal
+    fn estimate(&self, node: TitleCoords) -> f64 {
+        self.goal_title
+            .map_or(0.0, |goal| self.heuristic(node, goal))
+    }
+
+    fn heuristic_name(&self) -> String {
+        match self.heuristic_scale() {
+            1.0 => format!("{} (grid)", self.distance_metric().name()),
+            scale => format!("{} x {} (grid)", self.distance_metric().name(), scale),
+        }
+    }
+
+    /// The terrain cost of the next title, also between titles which are not neighbours
+    fn cost(&self, from: TitleCoords, to: TitleCoords) -> f64 {
+        Grid::cost(self, from, to)
+    }
+
+    fn distance(&self, from: TitleCoords, to: TitleCoords) -> Option<f64> {
+        Some(self.centre_distance(from, to))
+    }
+
+    fn mark_visited(&mut self, node: TitleCoords) {
+        Grid::mark_visited(self, node)
+    }
+
+    fn mark_process(&mut self, node: TitleCoords) {
+        Grid::mark_process(self, node)
+    }
+
+    fn mark_path(&mut self, node: TitleCoords) {
+        self.set_trace_back_path(node)
+    }
+
+    fn clear_marks(&mut self) {
+        self.clear_overlay()
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::algorithm::{
+        a_star::AStar,
+        bfs::Bfs,
+        dijkstra::Dijkstra,
+        greedy_bfs::GreedyBfs,
+        solver::{search, solve},
+        Algorithm,
+    };
+    use std::collections::HashMap;
+
+    /// Road network: cities with positions and roads with lengths
+    struct Roads {
+        positions: HashMap<&'static str, (f64, f64)>,
+        roads: Vec<(&'static str, &'static str, f64)>,
+        start: &'static str,
+        goal: &'static str,
+        visited: Vec<&'static str>,
+    }
+
+    impl Roads {
+        fn new(start: &'static str, goal: &'static str) -> Self {
+            Self {
+                positions: HashMap::from([
+                    ("A", (0.0, 0.0)),
+                    ("B", (4.0, 0.0)),
+                    ("C", (4.0, 3.0)),
+                    ("D", (8.0, 0.0)),
+                    ("E", (20.0, 20.0)),
+                ]),
+                roads: vec![
+                    ("A", "B", 4.0),
+                    ("A", "C", 5.0),
+                    ("B", "D", 10.0),
+                    ("C", "D", 5.0),
+                ],
+                start,
+                goal,
+                visited: Vec::new(),
+            }
+        }
+    }
+
+    impl SearchSpace for Roads {
+        type Node = &'static str;
+
+        fn start_node(&self) -> Option<&'static str> {
+            Some(self.start)
+        }
+
+        fn is_goal(&self, node: &'static str) -> bool {
+            node == self.goal
+        }
+
+        fn successors(&self, node: &'static str) -> Vec<(&'static str, f64)> {
+            self.roads
+                .iter()
+                .filter_map(|&(from, to, length)| match node {
+                    _ if node == from => Some((to, length)),
+                    _ if node == to => Some((from, length)),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        fn estimate(&self, node: &'static str) -> f64 {
+            self.distance(node, self.goal).unwrap()
+        }
+
+        fn distance(&self, from: &'static str, to: &'static str) -> Option<f64> {
+            let (from, to) = (self.positions[from], self.positions[to]);
+            Some((to.0 - from.0).hypot(to.1 - from.1))
+        }
+
+        fn mark_visited(&mut self, node: &'static str) {
+            self.visited.push(node);
+        }
+    }
+
+    #[test]
+    fn algorithms_on_road_graph() {
+        let mut roads = Roads::new("A", "D");
+        let dijkstra = search(&mut roads, Dijkstra::new()).unwrap();
+        assert_eq!(vec!["A", "C", "D"], dijkstra.path);
+        assert_eq!(10.0, dijkstra.statistics.path_cost);
+        assert_eq!(10.0, dijkstra.statistics.euclidean_length);
+        assert!(roads.visited.contains(&"A"));
+
+        let a_star = search(&mut Roads::new("A", "D"), AStar::new().weighted(1.0)).unwrap();
+        assert_eq!(dijkstra.path, a_star.path);
+        assert_eq!("Search space", a_star.statistics.parameters[0].1);
+
+        // Bfs counts the moves only
+        let bfs = search(&mut Roads::new("A", "D"), Bfs::new()).unwrap();
+        assert_eq!(3, bfs.path.len());
+        assert_eq!(14.0, bfs.statistics.path_cost);
+        assert!(
+            search(&mut Roads::new("A", "D"), GreedyBfs::new())
+                .unwrap()
+                .statistics
+                .goal_reachable
+        );
+
+        let mut unreachable = Roads::new("A", "E");
+        let mut dijkstra = Dijkstra::new();
+        dijkstra.start(&mut unreachable).unwrap();
+        while !dijkstra.has_completed() {
+            dijkstra.execute_step(&mut unreachable, 1.0);
+        }
+        assert!(dijkstra.solution_path().is_empty());
+    }
+
+    #[test]
+    fn grid_search_space() {
+        let mut grid = Grid::new(0, 0, 5, 5, 1);
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 4, y: 4 };
+        assert_eq!(None, grid.start_node());
+        grid.set_start(start).unwrap();
+        grid.set_goal(goal).unwrap();
+        grid.set_obstacle(TitleCoords { x: 1, y: 0 }).unwrap();
+
+        assert_eq!(Some(start), grid.start_node());
+        assert!(grid.is_goal(goal));
+        assert_eq!(
+            vec![(TitleCoords { x: 0, y: 1 }, 1.0)],
+            grid.successors(start)
+        );
+        assert_eq!(
+            8.0 * grid.heuristic_scale(),
+            SearchSpace::estimate(&grid, start)
+        );
+        assert_eq!(
+            solve(&grid, start, goal, AStar::default()).unwrap().path,
+            search(&mut grid, AStar::default()).unwrap().path
+        );
+    }
+}
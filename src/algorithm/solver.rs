@@ -0,0 +1,369 @@
+use super::{
+    search_space::SearchSpace, statistics::SearchStatistics, Algorithm, AlgorithmError,
+    ONE_ITERATION_TIME_SEC,
+};
+use crate::map::{grid::Grid, overlay::SearchOverlay, TitleCoords};
+
+/// # SearchResult
+/// Outcome of the headless search
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// Path from the start to the goal. Empty when the goal is unreachable
+    pub path: Vec<TitleCoords>,
+    /// Sum of the movement costs along the path
+    pub cost: f64,
+    /// Number of executed algorithm steps
+    pub steps: u32,
+    /// Number of titles discovered by the algorithm
+    pub visited_nodes: usize,
+    /// Titles explored by the algorithm, e.g. to compare it with other algorithms
+    pub overlay: SearchOverlay,
+    /// Measurements of the search
+    pub statistics: SearchStatistics,
+}
+
+impl SearchResult {
+    /// # is_goal_reachable
+    /// Check if the algorithm found a path to the goal
+    pub fn is_goal_reachable(&self) -> bool {
+        !self.path.is_empty()
+    }
+}
+
+/// # solve
+/// Run the algorithm on the grid from start to goal synchronously.
+///
+/// No window, rendering or step throttling is involved. The grid is cloned, so
+/// the caller's grid and its search overlay are left untouched. The start and the goal are placed
+/// on the copy, replacing its own ones. The algorithm should be a fresh instance.
+///
+/// Fails with [`AlgorithmError::Stalled`] when the algorithm takes no step
+/// before the search completes.
+pub fn solve(
+    grid: &Grid,
+    start: TitleCoords,
+    goal: TitleCoords,
+    mut algorithm: impl Algorithm,
+) -> Result<SearchResult, AlgorithmError> {
+    let mut search_grid = search_grid(grid, start, goal)?;
+
+    algorithm.start(&mut search_grid)?;
+    while !algorithm.has_completed() {
+        let steps_taken = algorithm.steps_taken();
+        // Every step is ready to execute when a whole iteration time has passed
+        algorithm.execute_step(&mut search_grid, ONE_ITERATION_TIME_SEC);
+        // The search would never complete, e.g. it was not started
+        if algorithm.steps_taken() == steps_taken && !algorithm.has_completed() {
+            return Err(AlgorithmError::Stalled);
+        }
+    }
+
+    let mut path = algorithm.solution_path().to_vec();
+    path.reverse();
+    let statistics = algorithm.statistics();
+
+    Ok(SearchResult {
+        path,
+        cost: statistics.path_cost,
+        steps: algorithm.steps_taken(),
+        visited_nodes: algorithm.visited_nodes(),
+        overlay: search_grid.take_overlay(),
+        statistics,
+    })
+}
+
+/// Copy of the grid without the search overlay. The start and the goal titles are placed
+/// like by the mouse clicks, so the copy is rendered and saved with them
+fn search_grid(grid: &Grid, start: TitleCoords, goal: TitleCoords) -> Result<Grid, AlgorithmError> {
+    let mut search_grid = grid.clone();
+    search_grid.clear_overlay();
+    // The start and the goal of the grid may swap places
+    for endpoint in [search_grid.start_title, search_grid.goal_title]
+        .into_iter()
+        .flatten()
+    {
+        search_grid
+            .clear_title(endpoint)
+            .map_err(|_| AlgorithmError::InvalidInputData)?;
+    }
+    search_grid
+        .set_start(start)
+        .map_err(|_| AlgorithmError::InvalidInputData)?;
+    if goal == start {
+        // The title shows the start, which is the goal too
+        search_grid.goal_title = Some(goal);
+    } else {
+        search_grid
+            .set_goal(goal)
+            .map_err(|_| AlgorithmError::InvalidInputData)?;
+    }
+    Ok(search_grid)
+}
+
+/// # SearchOutcome
+/// Outcome of the search in any search space
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchOutcome<N> {
+    /// Nodes from the start to the goal. Empty when the goal is unreachable
+    pub path: Vec<N>,
+    /// Measurements of the search
+    pub statistics: SearchStatistics,
+}
+
+impl<N> SearchOutcome<N> {
+    /// # cost
+    /// Sum of the movement costs along the path
+    pub fn cost(&self) -> f64 {
+        self.statistics.path_cost
+    }
+
+    /// # is_goal_reachable
+    /// Check if the algorithm found a path to the goal
+    pub fn is_goal_reachable(&self) -> bool {
+        !self.path.is_empty()
+    }
+}
+
+/// # search
+/// Run the algorithm in the search space until it completes, e.g. on a road graph.
+///
+/// Unlike `solve`, the search space is not cloned, so its visualization hooks see the search.
+/// The algorithm should be a fresh instance. Like `solve`, it fails with
+/// [`AlgorithmError::Stalled`] instead of waiting for a search which makes no progress.
+pub fn search<S: SearchSpace>(
+    space: &mut S,
+    mut algorithm: impl Algorithm<S>,
+) -> Result<SearchOutcome<S::Node>, AlgorithmError> {
+    algorithm.start(space)?;
+    while !algorithm.has_completed() {
+        let steps_taken = algorithm.steps_taken();
+        // Every step is ready to execute when a whole iteration time has passed
+        algorithm.execute_step(space, ONE_ITERATION_TIME_SEC);
+        // The search would never complete, e.g. it was not started
+        if algorithm.steps_taken() == steps_taken && !algorithm.has_completed() {
+            return Err(AlgorithmError::Stalled);
+        }
+    }
+
+    let mut path = algorithm.solution_path().to_vec();
+    path.reverse();
+    Ok(SearchOutcome {
+        path,
+        statistics: algorithm.statistics(),
+    })
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::AlgorithmKind,
+        algorithm::{
+            a_star::AStar, bfs::Bfs, dijkstra::Dijkstra, greedy_bfs::GreedyBfs,
+            theta_star::ThetaStar,
+        },
+        map::{DistanceMetric, Neighbourhood, SearchState, Terrain, Title},
+    };
+
+    #[test]
+    fn solve_without_window() {
+        let grid = Grid::new(0, 0, 10, 10, 1);
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 3, y: 4 };
+
+        let bfs = solve(&grid, start, goal, Bfs::default()).unwrap();
+        let dijkstra = solve(&grid, start, goal, Dijkstra::default()).unwrap();
+        let greedy_bfs = solve(&grid, start, goal, GreedyBfs::default()).unwrap();
+        let a_star = solve(&grid, start, goal, AStar::default()).unwrap();
+
+        for result in [&bfs, &dijkstra, &greedy_bfs, &a_star] {
+            assert!(result.is_goal_reachable());
+            assert_eq!(start, result.path[0]);
+            assert_eq!(goal, *result.path.last().unwrap());
+            assert_eq!(7.0, result.cost);
+        }
+        assert!(grid.start_title.is_none());
+        assert_eq!(0, grid.overlay().count(SearchState::Visited));
+        // The searched copy shows the start and the goal
+        let searched = search_grid(&grid, start, goal).unwrap();
+        assert_eq!(Some(&Title::Start), searched.title(start));
+        assert_eq!(Some(&Title::End), searched.title(goal));
+        assert_eq!(Some(start), searched.start_title);
+        assert!(searched.to_ascii().starts_with('S'));
+        assert!(
+            a_star.overlay.count(SearchState::Visited) <= bfs.overlay.count(SearchState::Visited)
+        );
+        assert_eq!(8, a_star.statistics.path_length);
+        // The goal is taken from the frontier but not expanded
+        assert_eq!(a_star.steps - 1, a_star.statistics.expanded_nodes);
+    }
+
+    #[test]
+    fn solve_weighted_and_unreachable() {
+        let mut grid = Grid::new(0, 0, 3, 3, 1);
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 2, y: 0 };
+        grid.set_terrain(TitleCoords { x: 1, y: 0 }, Terrain::Water);
+
+        let dijkstra = solve(&grid, start, goal, Dijkstra::default()).unwrap();
+        assert_eq!(4.0, dijkstra.cost);
+
+        // The detour along the road is cheaper than the straight line, A* finds it too
+        let mut road_grid = Grid::new(0, 0, 5, 3, 1);
+        for x in 0..5 {
+            road_grid.set_terrain(TitleCoords { x, y: 0 }, Terrain::Road);
+        }
+        let (road_start, road_goal) = (TitleCoords { x: 0, y: 1 }, TitleCoords { x: 4, y: 1 });
+        let dijkstra = solve(&road_grid, road_start, road_goal, Dijkstra::default()).unwrap();
+        let a_star = solve(&road_grid, road_start, road_goal, AStar::default()).unwrap();
+        assert_eq!(3.5, dijkstra.cost);
+        assert_eq!(dijkstra.cost, a_star.cost);
+
+        grid.on_mouse_clicked(&[1.5, 0.5], crate::map::Title::Obstacle);
+        grid.on_mouse_clicked(&[1.5, 1.5], crate::map::Title::Obstacle);
+        grid.on_mouse_clicked(&[1.5, 2.5], crate::map::Title::Obstacle);
+
+        let bfs = solve(&grid, start, goal, Bfs::default()).unwrap();
+        assert!(!bfs.is_goal_reachable());
+        assert!(!bfs.statistics.goal_reachable);
+
+        let blocked_goal = TitleCoords { x: 1, y: 1 };
+        assert!(solve(&grid, start, blocked_goal, Bfs::default()).is_err());
+    }
+
+    #[test]
+    fn solve_eight_directional() {
+        let mut grid = Grid::new(0, 0, 10, 10, 1);
+        grid.set_neighbourhood(Neighbourhood::Eight);
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 3, y: 4 };
+        let octile_distance = 1.0 + 3.0 * std::f64::consts::SQRT_2;
+
+        let dijkstra = solve(&grid, start, goal, Dijkstra::default()).unwrap();
+        let a_star = solve(&grid, start, goal, AStar::default()).unwrap();
+
+        assert_eq!(5, dijkstra.path.len());
+        assert!((dijkstra.cost - octile_distance).abs() < 1e-9);
+        assert!((a_star.cost - octile_distance).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solve_hex() {
+        // Wall across the hex grid with a gap at the bottom
+        let mut grid = Grid::new(0, 0, 8, 8, 1);
+        grid.set_neighbourhood(Neighbourhood::Hex);
+        for y in 0..7 {
+            grid.set_obstacle(TitleCoords { x: 4, y }).unwrap();
+        }
+        let start = TitleCoords { x: 1, y: 1 };
+        let goal = TitleCoords { x: 6, y: 1 };
+
+        let a_star = solve(&grid, start, goal, AStar::default()).unwrap();
+        assert_eq!(DistanceMetric::Hex, grid.distance_metric());
+        for step in a_star.path.windows(2) {
+            assert_eq!(1.0, DistanceMetric::Hex.distance(step[0], step[1]));
+        }
+
+        for kind in AlgorithmKind::ALL {
+            let result = solve(&grid, start, goal, kind.create()).unwrap();
+            assert!(result.is_goal_reachable(), "{}", kind.id());
+            assert_eq!(goal, *result.path.last().unwrap());
+            match kind {
+                AlgorithmKind::Bfs
+                | AlgorithmKind::Dijkstra
+                | AlgorithmKind::JumpPointSearch
+                | AlgorithmKind::JumpPointSearchPlus
+                | AlgorithmKind::BidirectionalDijkstra
+                | AlgorithmKind::BidirectionalAStar
+                | AlgorithmKind::DStarLite
+                | AlgorithmKind::IdaStar => assert_eq!(a_star.cost, result.cost, "{}", kind.id()),
+                // Any-angle paths are shorter than the moves between the neighbours
+                AlgorithmKind::ThetaStar | AlgorithmKind::LazyThetaStar => {
+                    assert!(result.cost < a_star.cost)
+                }
+                _ => assert!(result.cost >= a_star.cost, "{}", kind.id()),
+            }
+        }
+    }
+
+    /// Never completes and never takes a step, like an algorithm which was not started
+    struct StalledSearch;
+
+    impl crate::algorithm::Measurable for StalledSearch {
+        fn statistics(&self) -> SearchStatistics {
+            SearchStatistics::default()
+        }
+        fn steps_taken(&self) -> u32 {
+            0
+        }
+        fn visited_nodes(&self) -> usize {
+            0
+        }
+    }
+
+    impl<S: SearchSpace> Algorithm<S> for StalledSearch {
+        fn start(&mut self, _space: &mut S) -> Result<(), AlgorithmError> {
+            Ok(())
+        }
+        fn execute_step(&mut self, _space: &mut S, _delta_time: f64) {}
+        fn reset(&mut self, _space: &mut S) {}
+        fn has_completed(&self) -> bool {
+            false
+        }
+        fn name(&self) -> String {
+            "Stalled".to_string()
+        }
+        fn solution_path(&self) -> &[S::Node] {
+            &[]
+        }
+    }
+
+    #[test]
+    fn solve_stalled_algorithm() {
+        let mut grid = Grid::new(0, 0, 4, 4, 1);
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 3, y: 3 };
+
+        let solved = solve(&grid, start, goal, StalledSearch);
+        assert!(matches!(solved, Err(AlgorithmError::Stalled)));
+
+        grid.set_start(start).unwrap();
+        grid.set_goal(goal).unwrap();
+        let searched = search(&mut grid, StalledSearch);
+        assert!(matches!(searched, Err(AlgorithmError::Stalled)));
+    }
+
+    #[test]
+    fn solve_any_angle_weighted() {
+        let mut grid = Grid::new(0, 0, 10, 10, 1);
+        for x in 0..10 {
+            for y in 0..10 {
+                grid.set_terrain(TitleCoords { x, y }, Terrain::Water);
+            }
+        }
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 9, y: 9 };
+        let water_cost = grid.terrain_cost(Terrain::Water);
+
+        let dijkstra = solve(&grid, start, goal, Dijkstra::default()).unwrap();
+        assert_eq!(18.0 * water_cost, dijkstra.cost);
+        for theta_star in [ThetaStar::default(), ThetaStar::lazy()] {
+            let result = solve(&grid, start, goal, theta_star).unwrap();
+            let length = 9.0 * std::f64::consts::SQRT_2;
+            assert!((result.statistics.euclidean_length - length).abs() < 1e-9);
+            assert!((result.cost - length * water_cost).abs() < 1e-9);
+        }
+
+        // The straight segment pays for the water title it crosses
+        let mut grid = Grid::new(0, 0, 5, 1, 1);
+        grid.set_terrain(TitleCoords { x: 2, y: 0 }, Terrain::Water);
+        let result = solve(
+            &grid,
+            TitleCoords { x: 0, y: 0 },
+            TitleCoords { x: 4, y: 0 },
+            ThetaStar::default(),
+        )
+        .unwrap();
+        assert_eq!(3.0 + water_cost, result.cost);
+    }
+}
@@ -0,0 +1,157 @@
+use super::{Pathfinder, SimulationCoordinator};
+use serde::{Deserialize, Serialize};
+use std::{fmt, hash::Hash};
+
+/// # SearchStatistics
+/// Measurements of one search, e.g. to compare the algorithms or to export them.
+///
+/// The on-screen statistics are formatted from it with `Display`
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SearchStatistics {
+    pub algorithm: String,
+    /// Algorithm settings as (name, value) pairs, e.g. the heuristic
+    pub parameters: Vec<(String, String)>,
+    /// Number of titles on the path including the start and the goal. 0 when no path was found
+    pub path_length: usize,
+    /// Sum of the movement costs along the path
+    pub path_cost: f64,
+    /// Length of the path measured along the straight lines between its titles
+    pub euclidean_length: f64,
+    /// Number of titles whose neighbours were explored
+    pub expanded_nodes: u32,
+    /// Number of titles added to the frontier
+    pub generated_nodes: u32,
+    /// The largest number of titles waiting in the frontier at once
+    pub max_frontier_size: usize,
+    /// Number of executed algorithm steps
+    pub steps: u32,
+    /// Wall time of the search in seconds
+    pub elapsed_time_sec: f64,
+    pub goal_reachable: bool,
+}
+
+impl SearchStatistics {
+    /// # collect
+    /// Gather the statistics of the algorithm from its simulation coordinator and path finder
+    pub(crate) fn collect<N: Copy + Eq + Hash>(
+        algorithm: String,
+        parameters: &[(&str, String)],
+        sim_coordinator: &SimulationCoordinator,
+        path_finder: &Pathfinder<N>,
+    ) -> Self {
+        let path_length = path_finder.get_path().len();
+        Self {
+            algorithm,
+            parameters: parameters
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            path_length,
+            path_cost: path_finder.path_cost(),
+            euclidean_length: path_finder.euclidean_length(),
+            expanded_nodes: sim_coordinator.expanded_nodes,
+            generated_nodes: sim_coordinator.generated_nodes,
+            max_frontier_size: sim_coordinator.max_frontier_size,
+            steps: sim_coordinator.steps,
+            elapsed_time_sec: sim_coordinator.elapsed_time().as_secs_f64(),
+            goal_reachable: path_length > 0,
+        }
+    }
+}
+
+impl fmt::Display for SearchStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, " {}\n\n Statistics:\n", self.algorithm)?;
+        for (name, value) in self.parameters.iter() {
+            writeln!(f, " - {}: {}", name, value)?;
+        }
+        if self.goal_reachable {
+            write!(
+                f,
+                " - Path length: {}\n - Path cost: {:.2}\n - Euclidean length: {:.2}\n",
+                self.path_length, self.path_cost, self.euclidean_length
+            )?;
+        } else {
+            writeln!(f, " - Goal is unreachable !")?;
+        }
+        write!(
+            f,
+            " - Steps taken: {}\n - Expanded nodes: {}\n - Generated nodes: {}\n - Max frontier: {}\n - Total time: {:.2} sec",
+            self.steps,
+            self.expanded_nodes,
+            self.generated_nodes,
+            self.max_frontier_size,
+            self.elapsed_time_sec
+        )
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{
+            dijkstra::Dijkstra, solver::solve, Algorithm, AlgorithmKind, Measurable,
+            ONE_ITERATION_TIME_SEC,
+        },
+        map::{grid::Grid, Neighbourhood, Terrain, TitleCoords},
+    };
+
+    #[test]
+    fn search_statistics() {
+        let mut grid = Grid::new(0, 0, 4, 1, 1);
+        grid.set_terrain(TitleCoords { x: 1, y: 0 }, Terrain::Mud);
+        grid.start_title = Some(TitleCoords { x: 0, y: 0 });
+        grid.goal_title = Some(TitleCoords { x: 3, y: 0 });
+
+        let mut dijkstra = Dijkstra::default();
+        assert!(!dijkstra.statistics().goal_reachable);
+        dijkstra.start(&mut grid).unwrap();
+        while !dijkstra.has_completed() {
+            dijkstra.execute_step(&mut grid, ONE_ITERATION_TIME_SEC);
+        }
+
+        let statistics = dijkstra.statistics();
+        assert!(statistics.goal_reachable);
+        assert_eq!(4, statistics.path_length);
+        assert_eq!(7.0, statistics.path_cost);
+        assert_eq!(3.0, statistics.euclidean_length);
+        assert_eq!(3, statistics.expanded_nodes);
+        assert_eq!(4, statistics.generated_nodes);
+        assert_eq!(1, statistics.max_frontier_size);
+        assert_eq!(4, statistics.steps);
+        assert!(dijkstra.output_statistics().contains("Path cost: 7.00"));
+
+        let json = serde_json::to_string(&statistics).unwrap();
+        assert!(json.contains("\"path_cost\":7.0"));
+        assert_eq!(
+            statistics,
+            serde_json::from_str::<SearchStatistics>(&json).unwrap()
+        );
+    }
+
+    #[test]
+    fn start_is_goal_statistics() {
+        // Jump Point Search needs the movement without corner cutting
+        let mut grid = Grid::new(0, 0, 4, 4, 1);
+        grid.set_neighbourhood(Neighbourhood::EightNoCornerCutting);
+        let title = TitleCoords { x: 1, y: 2 };
+
+        for kind in AlgorithmKind::ALL {
+            let statistics = solve(&grid, title, title, kind.create())
+                .unwrap()
+                .statistics;
+            assert!(statistics.goal_reachable, "{}", kind.id());
+            assert_eq!(1, statistics.path_length, "{}", kind.id());
+            // Not -0.0, which is printed as -0
+            assert!(statistics.path_cost.is_sign_positive(), "{}", kind.id());
+            assert_eq!(0.0, statistics.path_cost, "{}", kind.id());
+            assert_eq!("0", statistics.path_cost.to_string(), "{}", kind.id());
+            assert!(
+                statistics.euclidean_length.is_sign_positive(),
+                "{}",
+                kind.id()
+            );
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! # Stepping
+//! Runtime control of the search speed: pause, single step, steps per second and finish.
+//!
+//! The algorithm executes its step when `ONE_ITERATION_TIME_SEC` has passed, so [`StepControl`]
+//! passes that time to every step it wants executed. Many steps can be executed in one update.
+use super::{search_space::SearchSpace, Algorithm, ONE_ITERATION_TIME_SEC};
+
+/// Steps per second to choose from
+pub const SPEEDS: [f64; 9] = [
+    1.0, 5.0, 20.0, 100.0, 500.0, 2_000.0, 10_000.0, 50_000.0, 250_000.0,
+];
+/// The default speed is one step every `ONE_ITERATION_TIME_SEC`
+const DEFAULT_SPEED_ID: usize = 3;
+/// Upper limit of the steps executed in one update, so a very long search
+/// keeps the window responsive when it is finished
+pub const MAX_STEPS_PER_UPDATE: u32 = 100_000;
+
+/// # RunMode
+/// How the steps are executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// Steps follow the selected speed
+    Running,
+    /// Only the requested single steps are executed
+    Paused,
+    /// As many steps as possible until the search completes
+    Finishing,
+}
+
+/// # StepControl
+/// Decides how many steps of the algorithm are executed in the update
+#[derive(Debug, Clone)]
+pub struct StepControl {
+    mode: RunMode,
+    speed_id: usize,
+    /// Fraction of the step carried to the next update
+    accumulated_steps: f64,
+    single_steps: u32,
+}
+
+impl Default for StepControl {
+    fn default() -> Self {
+        Self {
+            mode: RunMode::Running,
+            speed_id: DEFAULT_SPEED_ID,
+            accumulated_steps: 0.0,
+            single_steps: 0,
+        }
+    }
+}
+
+impl StepControl {
+    /// # mode
+    /// How the steps are executed now
+    pub fn mode(&self) -> RunMode {
+        self.mode
+    }
+
+    /// # steps_per_second
+    /// Selected speed of the running search
+    pub fn steps_per_second(&self) -> f64 {
+        SPEEDS[self.speed_id]
+    }
+
+    /// # toggle_pause
+    /// Pause the search or resume it with the selected speed
+    pub fn toggle_pause(&mut self) {
+        self.mode = match self.mode {
+            RunMode::Paused => RunMode::Running,
+            RunMode::Running | RunMode::Finishing => RunMode::Paused,
+        };
+        self.accumulated_steps = 0.0;
+    }
+
+    /// # step_once
+    /// Pause the search and execute one step in the next update
+    pub fn step_once(&mut self) {
+        self.mode = RunMode::Paused;
+        self.single_steps += 1;
+    }
+
+    /// # change_speed
+    /// Select the faster (positive step) or slower (negative step) speed
+    pub fn change_speed(&mut self, step: isize) {
+        self.speed_id = self
+            .speed_id
+            .saturating_add_signed(step)
+            .min(SPEEDS.len() - 1);
+    }
+
+    /// # finish
+    /// Execute the remaining steps of the search at once
+    pub fn finish(&mut self) {
+        self.mode = RunMode::Finishing;
+    }
+
+    /// # pause
+    /// Pause the search, the requested single steps are kept
+    pub fn pause(&mut self) {
+        self.mode = RunMode::Paused;
+        self.accumulated_steps = 0.0;
+    }
+
+    /// # due_steps
+    /// Number of steps due in the update, e.g. to replay the recorded ones
+    pub fn due_steps(&mut self, delta_time: f64) -> u32 {
+        match self.mode {
+            RunMode::Paused => std::mem::take(&mut self.single_steps),
+            RunMode::Running => {
+                self.accumulated_steps += delta_time * self.steps_per_second();
+                let steps = self.accumulated_steps.floor();
+                self.accumulated_steps -= steps;
+                (steps as u32).min(MAX_STEPS_PER_UPDATE)
+            }
+            RunMode::Finishing => MAX_STEPS_PER_UPDATE,
+        }
+    }
+
+    /// # update
+    /// Execute the steps of the algorithm due in the update. Returns the number of executed steps
+    pub fn update<S: SearchSpace, A: Algorithm<S> + ?Sized>(
+        &mut self,
+        algorithm: &mut A,
+        space: &mut S,
+        delta_time: f64,
+    ) -> u32 {
+        self.update_with(algorithm, space, delta_time, |_, _| {})
+    }
+
+    /// # update_with
+    /// Execute the steps due in the update and call `after_step` after every one of them,
+    /// e.g. to record the timeline. Returns the number of executed steps
+    pub fn update_with<S: SearchSpace, A: Algorithm<S> + ?Sized>(
+        &mut self,
+        algorithm: &mut A,
+        space: &mut S,
+        delta_time: f64,
+        mut after_step: impl FnMut(&mut A, &mut S),
+    ) -> u32 {
+        let steps = self.due_steps(delta_time);
+        for executed in 0..steps {
+            if self.mode == RunMode::Finishing && algorithm.has_completed() {
+                self.mode = RunMode::Running;
+                return executed;
+            }
+            let steps_taken = algorithm.steps_taken();
+            algorithm.execute_step(space, ONE_ITERATION_TIME_SEC);
+            after_step(algorithm, space);
+            // Nothing to finish, e.g. the search has not started yet
+            if self.mode == RunMode::Finishing
+                && algorithm.steps_taken() == steps_taken
+                && !algorithm.has_completed()
+            {
+                self.mode = RunMode::Running;
+                return executed;
+            }
+        }
+        steps
+    }
+
+    /// # run
+    /// Call `step` for the steps due in the update until it returns false, e.g. to step
+    /// several searches in lockstep. Returns the number of the steps which returned true
+    pub fn run(&mut self, delta_time: f64, mut step: impl FnMut() -> bool) -> u32 {
+        let steps = self.due_steps(delta_time);
+        for executed in 0..steps {
+            if !step() {
+                if self.mode == RunMode::Finishing {
+                    self.mode = RunMode::Running;
+                }
+                return executed;
+            }
+        }
+        steps
+    }
+
+    /// # status
+    /// Speed or mode formatted for the screen
+    pub fn status(&self) -> String {
+        match self.mode {
+            RunMode::Running => format!("{} steps/s", self.steps_per_second()),
+            RunMode::Paused => "paused".to_string(),
+            RunMode::Finishing => "finishing".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{bfs::Bfs, Measurable},
+        map::{grid::Grid, TitleCoords},
+    };
+
+    #[test]
+    fn step_controls() {
+        let mut grid = Grid::builder(30, 30)
+            .start(TitleCoords { x: 0, y: 0 })
+            .goal(TitleCoords { x: 29, y: 29 })
+            .build()
+            .unwrap();
+        let mut bfs = Bfs::default();
+        let mut control = StepControl::default();
+        Algorithm::start(&mut bfs, &mut grid).unwrap();
+
+        // 100 steps/s
+        assert_eq!(1, control.update(&mut bfs, &mut grid, 0.015));
+        assert_eq!(2, control.update(&mut bfs, &mut grid, 0.015));
+        assert_eq!(3, bfs.steps_taken());
+
+        control.toggle_pause();
+        assert_eq!(0, control.update(&mut bfs, &mut grid, 1.0));
+        control.step_once();
+        control.step_once();
+        assert_eq!(2, control.update(&mut bfs, &mut grid, 1.0));
+        assert_eq!(RunMode::Paused, control.mode());
+        assert_eq!("paused", control.status());
+
+        // Many steps in one frame
+        control.toggle_pause();
+        control.change_speed(3);
+        assert_eq!(10_000.0, control.steps_per_second());
+        assert_eq!(100, control.update(&mut bfs, &mut grid, 0.01));
+        control.change_speed(100);
+        assert_eq!(250_000.0, control.steps_per_second());
+        control.change_speed(-100);
+        assert_eq!(1.0, control.steps_per_second());
+
+        control.finish();
+        control.update(&mut bfs, &mut grid, 0.01);
+        assert!(Algorithm::<Grid>::has_completed(&bfs));
+        control.update(&mut bfs, &mut grid, 0.01);
+        assert_eq!(RunMode::Running, control.mode());
+
+        // Steps of anything else, e.g. a race
+        let mut remaining_steps = 3;
+        control.finish();
+        let executed = control.run(0.01, || {
+            remaining_steps -= 1;
+            remaining_steps >= 0
+        });
+        assert_eq!(3, executed);
+        assert_eq!(RunMode::Running, control.mode());
+    }
+}
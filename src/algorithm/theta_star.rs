@@ -0,0 +1,280 @@
+use super::{Algorithm, AlgorithmError, Measurable, Pathfinder, Priority, SimulationCoordinator};
+use crate::{
+    algorithm::statistics::SearchStatistics,
+    map::{grid::Grid, TitleCoords},
+};
+use priority_queue::DoublePriorityQueue;
+use std::collections::{HashMap, HashSet};
+
+/// # Theta* Algorithm
+/// Any-angle variant of A*. A title may take the parent of the title it was reached from,
+/// if the parent is in line of sight, so the path is made of straight segments
+/// between non-adjacent titles instead of zig-zagging along the grid.
+///
+/// The search minimizes the Euclidean distance, the terrain is not taken into account.
+/// The cost of the found path includes the terrain crossed by its segments.
+///
+/// Lazy Theta* assumes the line of sight when the title is generated and checks it
+/// only once the title is expanded, which saves most of the checks.
+///
+/// Theta* is created by default.
+#[derive(Default)]
+pub struct ThetaStar {
+    is_lazy: bool,
+    priority_titles: DoublePriorityQueue<TitleCoords, Priority>,
+    cost_so_far: HashMap<TitleCoords, f64>,
+    expanded_titles: HashSet<TitleCoords>,
+    line_of_sight_checks: u32,
+    sim_coordinator: SimulationCoordinator,
+    path_finder: Pathfinder,
+}
+
+impl ThetaStar {
+    /// # lazy
+    /// Lazy Theta* - the line of sight is checked when the title is expanded
+    pub fn lazy() -> Self {
+        Self {
+            is_lazy: true,
+            ..Default::default()
+        }
+    }
+
+    fn has_line_of_sight(&mut self, grid: &Grid, from: TitleCoords, to: TitleCoords) -> bool {
+        self.line_of_sight_checks += 1;
+        grid.has_line_of_sight(from, to)
+    }
+
+    /// The title the title was reached from. The start is its own parent
+    fn parent(&self, title: TitleCoords) -> TitleCoords {
+        self.path_finder.parent(title).unwrap_or(title)
+    }
+
+    fn cost(&self, title: TitleCoords) -> f64 {
+        *self.cost_so_far.get(&title).unwrap_or(&f64::INFINITY)
+    }
+
+    /// Lazy Theta*: if the parent of the expanded title is not in line of sight,
+    /// take the best expanded neighbour as the parent instead
+    fn set_vertex(&mut self, grid: &Grid, title: TitleCoords) {
+        let parent = self.parent(title);
+        if parent == title || self.has_line_of_sight(grid, parent, title) {
+            return;
+        }
+        let best_neighbor = grid
+            .get_neighbors(title)
+            .into_iter()
+            .filter(|neighbor| self.expanded_titles.contains(neighbor))
+            .map(|neighbor| {
+                let cost = self.cost(neighbor) + grid.centre_distance(neighbor, title);
+                (neighbor, cost)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((neighbor, cost)) = best_neighbor {
+            self.cost_so_far.insert(title, cost);
+            self.path_finder.add_to_path(title, Some(neighbor));
+        }
+    }
+}
+
+impl Measurable for ThetaStar {
+    fn statistics(&self) -> SearchStatistics {
+        let line_of_sight = if self.is_lazy {
+            "checked when expanded"
+        } else {
+            "checked when generated"
+        };
+        SearchStatistics::collect(
+            self.name(),
+            &[
+                ("Line of sight", line_of_sight.to_string()),
+                (
+                    "Line of sight checks",
+                    self.line_of_sight_checks.to_string(),
+                ),
+            ],
+            &self.sim_coordinator,
+            &self.path_finder,
+        )
+    }
+
+    fn steps_taken(&self) -> u32 {
+        self.sim_coordinator.steps
+    }
+
+    fn visited_nodes(&self) -> usize {
+        self.cost_so_far.len()
+    }
+}
+
+impl Algorithm for ThetaStar {
+    /// # start
+    /// Theta* Algorithm starts.
+    ///
+    /// Init the algorithm values
+    fn start(&mut self, grid: &mut Grid) -> Result<(), AlgorithmError> {
+        if grid.start_title.is_none() || grid.goal_title.is_none() {
+            return Err(AlgorithmError::InvalidInputData);
+        }
+        let start = grid.start_title.unwrap();
+
+        self.priority_titles.push(start, Priority(0.0));
+        self.sim_coordinator
+            .record_generated(self.priority_titles.len());
+        self.cost_so_far.insert(start, 0.0);
+        self.path_finder.add_to_path(start, None);
+        self.sim_coordinator.start_processing();
+
+        Ok(())
+    }
+
+    /// # execute_step
+    /// Algorithm processing update every ONE_ITERATION_TIME_SEC until reach the goal
+    fn execute_step(&mut self, grid: &mut Grid, delta_time: f64) {
+        if !self.sim_coordinator.is_ready_to_execute(delta_time) {
+            return;
+        }
+
+        let Some((current, _priority)) = self.priority_titles.pop_min() else {
+            self.sim_coordinator.stop_processing();
+            return;
+        };
+        self.sim_coordinator.increase_step_count();
+
+        let start = grid.start_title.unwrap();
+        let goal = grid.goal_title.unwrap();
+
+        if self.is_lazy {
+            self.set_vertex(grid, current);
+        }
+
+        //Early exit
+        if self.sim_coordinator.process_goal_reached(current, goal) {
+            self.path_finder.reconstruct_path(start, goal);
+            self.path_finder.trace_back_waypoints(grid);
+            return;
+        }
+
+        grid.mark_visited(current);
+        self.expanded_titles.insert(current);
+        self.sim_coordinator.record_expansion();
+
+        let parent = self.parent(current);
+        for neighbor in grid.get_neighbors(current) {
+            if self.expanded_titles.contains(&neighbor) {
+                continue;
+            }
+            // Theta* takes the parent's shortcut only if it is in line of sight,
+            // Lazy Theta* takes it always and verifies it on the expansion
+            let takes_shortcut = parent != current
+                && (self.is_lazy || self.has_line_of_sight(grid, parent, neighbor));
+            let came_from = if takes_shortcut { parent } else { current };
+            let new_cost = self.cost(came_from) + grid.centre_distance(came_from, neighbor);
+
+            if new_cost < self.cost(neighbor) {
+                grid.mark_process(neighbor);
+                self.cost_so_far.insert(neighbor, new_cost);
+                let priority = Priority(new_cost + grid.centre_distance(neighbor, goal));
+                self.priority_titles.push(neighbor, priority);
+                self.sim_coordinator
+                    .record_generated(self.priority_titles.len());
+                self.path_finder.add_to_path(neighbor, Some(came_from));
+            }
+        }
+        // Check if goal is unreachable
+        if self.priority_titles.is_empty() {
+            self.sim_coordinator.has_completed = true;
+            self.sim_coordinator.stop_processing();
+        }
+    }
+
+    /// # reset
+    /// Reset the algorithm processing and clear its search overlay.
+    ///
+    /// The lazy variant is kept
+    fn reset(&mut self, grid: &mut Grid) {
+        *self = Self {
+            is_lazy: self.is_lazy,
+            ..Default::default()
+        };
+        grid.clear_overlay();
+    }
+
+    /// # has_completed
+    /// Check if processing is done
+    fn has_completed(&self) -> bool {
+        self.sim_coordinator.has_completed
+    }
+
+    /// # name
+    /// Algorithm name
+    fn name(&self) -> String {
+        if self.is_lazy {
+            return "Lazy Theta*".to_string();
+        }
+        "Theta*".to_string()
+    }
+
+    /// # solution_path
+    /// Waypoints of the path from the goal back to the start.
+    /// Consecutive waypoints are connected by straight lines. Empty until the goal is reached
+    fn solution_path(&self) -> &[TitleCoords] {
+        self.path_finder.get_path()
+    }
+
+    /// # take_parent_updates
+    /// (node, parent) pairs set since the last call
+    fn take_parent_updates(&mut self) -> Vec<(TitleCoords, TitleCoords)> {
+        self.path_finder.take_parent_updates()
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{a_star::AStar, solver::solve},
+        map::{Neighbourhood, SearchState},
+    };
+
+    #[test]
+    fn theta_star_any_angle_path() {
+        let mut grid = Grid::new(0, 0, 20, 20, 1);
+        grid.set_neighbourhood(Neighbourhood::EightNoCornerCutting);
+        for y in 0..15 {
+            grid.set_obstacle(TitleCoords { x: 10, y }).unwrap();
+        }
+        let start = TitleCoords { x: 2, y: 2 };
+        let goal = TitleCoords { x: 18, y: 3 };
+        let corner = TitleCoords { x: 10, y: 15 };
+
+        let a_star = solve(&grid, start, goal, AStar::default()).unwrap();
+        for theta_star in [ThetaStar::default(), ThetaStar::lazy()] {
+            let result = solve(&grid, start, goal, theta_star).unwrap();
+
+            // Straight lines from the start around the wall to the goal
+            assert!(result.path.len() <= 4);
+            for segment in result.path.windows(2) {
+                assert!(grid.has_line_of_sight(segment[0], segment[1]));
+            }
+            let shortest = grid.centre_distance(start, corner) + grid.centre_distance(corner, goal);
+            assert!(result.cost < a_star.cost);
+            assert!(result.cost <= shortest + 1.5);
+            // Plain terrain costs 1 per crossed title
+            assert!((result.cost - result.statistics.euclidean_length).abs() < 1e-9);
+            assert_eq!(result.path.len(), result.overlay.waypoints().len());
+            assert!(result.overlay.count(SearchState::Path) > 0);
+        }
+
+        let lazy = solve(&grid, start, goal, ThetaStar::lazy()).unwrap();
+        let eager = solve(&grid, start, goal, ThetaStar::default()).unwrap();
+        let checks = |result: &crate::SearchResult| result.statistics.parameters[1].1.clone();
+        assert!(checks(&lazy).parse::<u32>().unwrap() < checks(&eager).parse::<u32>().unwrap());
+
+        // Walled goal
+        for title in grid.get_neighbors(goal) {
+            grid.set_obstacle(title).unwrap();
+        }
+        let result = solve(&grid, start, goal, ThetaStar::lazy()).unwrap();
+        assert!(!result.is_goal_reachable());
+    }
+}
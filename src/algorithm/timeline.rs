@@ -0,0 +1,419 @@
+//! # Timeline
+//! Recording of the search on the grid, which can be scrubbed backward and forward
+//! and replayed from the trace file without running the algorithm again.
+//!
+//! The trace file has one JSON object per line: the [`TraceHeader`] with the map
+//! followed by the [`StepRecord`] of every step.
+use super::{Algorithm, AlgorithmError};
+use crate::map::{
+    grid::Grid, map_file::MapFileError, overlay::TitleChange, Neighbourhood, SearchState,
+    TitleCoords,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+/// # TraceHeader
+/// The first line of the trace: the searched map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    pub algorithm: String,
+    /// Name of the [`Neighbourhood`]
+    pub neighbourhood: String,
+    /// ASCII map, see [`crate::map::map_file`]
+    pub map: String,
+}
+
+/// # ParentUpdate
+/// The node was reached from the parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParentUpdate {
+    pub node: TitleCoords,
+    pub parent: TitleCoords,
+}
+
+/// # StepRecord
+/// Changes made by one step of the algorithm
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct StepRecord {
+    /// Number of steps taken by the algorithm after this one
+    pub step: u32,
+    /// Titles whose neighbours were explored
+    pub expanded: Vec<TitleCoords>,
+    /// Titles added to the frontier
+    pub discovered: Vec<TitleCoords>,
+    pub parents: Vec<ParentUpdate>,
+    /// Every search state change of the overlay, in order
+    pub changes: Vec<TitleChange>,
+    /// Turning points of the path drawn as straight segments, set by this step
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waypoints: Vec<TitleCoords>,
+}
+
+/// # TimelineError
+/// Errors reported when the trace file is loaded
+#[derive(Debug)]
+pub enum TimelineError {
+    Io(io::Error),
+    /// The trace has no header line
+    MissingHeader,
+    /// The line (counted from 1) is not a valid record
+    InvalidLine {
+        line: usize,
+        error: serde_json::Error,
+    },
+    /// The map in the header is not valid
+    InvalidMap(MapFileError),
+}
+
+impl From<io::Error> for TimelineError {
+    fn from(error: io::Error) -> Self {
+        TimelineError::Io(error)
+    }
+}
+
+impl From<MapFileError> for TimelineError {
+    fn from(error: MapFileError) -> Self {
+        TimelineError::InvalidMap(error)
+    }
+}
+
+/// # Timeline
+/// Recorded steps of the search and the position of the displayed one.
+///
+/// ```
+/// use r_path_finder::algorithm::{bfs::Bfs, timeline::Timeline, Algorithm};
+/// use r_path_finder::map::{grid::Grid, TitleCoords};
+///
+/// let mut grid = Grid::builder(5, 5)
+///     .start(TitleCoords { x: 0, y: 0 })
+///     .goal(TitleCoords { x: 4, y: 4 })
+///     .build()
+///     .unwrap();
+/// let mut bfs = Bfs::default();
+/// let mut timeline = Timeline::start(&mut bfs, &mut grid).unwrap();
+/// while !bfs.has_completed() {
+///     bfs.execute_step(&mut grid, 0.01);
+///     timeline.record(&mut bfs, &mut grid);
+/// }
+/// timeline.seek(&mut grid, 0);
+/// assert_eq!(0, timeline.position());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    header: TraceHeader,
+    steps: Vec<StepRecord>,
+    /// Number of the steps shown on the grid
+    position: usize,
+}
+
+impl Timeline {
+    /// # start
+    /// Start the algorithm on the grid and record its first changes
+    pub fn start<A: Algorithm + ?Sized>(
+        algorithm: &mut A,
+        grid: &mut Grid,
+    ) -> Result<Timeline, AlgorithmError> {
+        grid.record_overlay_changes(true);
+        algorithm.take_parent_updates();
+        if let Err(error) = algorithm.start(grid) {
+            grid.record_overlay_changes(false);
+            return Err(error);
+        }
+        let mut timeline = Timeline {
+            header: TraceHeader {
+                algorithm: algorithm.name(),
+                neighbourhood: grid.neighbourhood().name().to_string(),
+                map: grid.to_ascii(),
+            },
+            steps: Vec::new(),
+            position: 0,
+        };
+        timeline.record(algorithm, grid);
+        Ok(timeline)
+    }
+
+    /// # record
+    /// Record the changes of the executed step. Steps which changed nothing are skipped.
+    ///
+    /// The grid should show the end of the timeline
+    pub fn record<A: Algorithm + ?Sized>(&mut self, algorithm: &mut A, grid: &mut Grid) -> bool {
+        let changes = grid.take_overlay_changes();
+        let parents: Vec<ParentUpdate> = algorithm
+            .take_parent_updates()
+            .into_iter()
+            .map(|(node, parent)| ParentUpdate { node, parent })
+            .collect();
+        if changes.is_empty() && parents.is_empty() {
+            return false;
+        }
+
+        let titles_changed_to = |states: &[SearchState]| -> Vec<TitleCoords> {
+            changes
+                .iter()
+                .filter(|change| states.contains(&change.after))
+                .map(|change| change.title)
+                .collect()
+        };
+        let expanded = titles_changed_to(&[SearchState::Visited, SearchState::BackwardVisited]);
+        let discovered = titles_changed_to(&[
+            SearchState::Process,
+            SearchState::BackwardProcess,
+            SearchState::JumpPoint,
+        ]);
+        let waypoints = if changes.is_empty() {
+            Vec::new()
+        } else {
+            grid.overlay().waypoints().to_vec()
+        };
+        self.steps.push(StepRecord {
+            step: algorithm.steps_taken(),
+            expanded,
+            discovered,
+            parents,
+            changes,
+            waypoints,
+        });
+        self.position = self.steps.len();
+        true
+    }
+
+    /// # header
+    /// Algorithm and the map of the recorded search
+    pub fn header(&self) -> &TraceHeader {
+        &self.header
+    }
+
+    /// # steps
+    /// All recorded steps
+    pub fn steps(&self) -> &[StepRecord] {
+        &self.steps
+    }
+
+    /// # len
+    /// Number of recorded steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// # is_empty
+    /// Nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// # position
+    /// Number of the steps shown on the grid
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// # is_at_end
+    /// All recorded steps are shown
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.steps.len()
+    }
+
+    /// # step_back
+    /// Undo the last shown step on the grid. False at the beginning
+    pub fn step_back(&mut self, grid: &mut Grid) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        for change in self.steps[self.position].changes.iter().rev() {
+            grid.set_search_state(change.title, change.before);
+        }
+        let waypoints = self.steps[..self.position]
+            .iter()
+            .rev()
+            .find(|step| !step.waypoints.is_empty())
+            .map(|step| step.waypoints.clone())
+            .unwrap_or_default();
+        grid.set_path_waypoints(&waypoints);
+        // The scrubbing is not a change of the search
+        grid.take_overlay_changes();
+        true
+    }
+
+    /// # step_forward
+    /// Show the next recorded step on the grid. False at the end
+    pub fn step_forward(&mut self, grid: &mut Grid) -> bool {
+        let Some(step) = self.steps.get(self.position) else {
+            return false;
+        };
+        for change in step.changes.iter() {
+            grid.set_search_state(change.title, change.after);
+        }
+        if !step.waypoints.is_empty() {
+            grid.set_path_waypoints(&step.waypoints);
+        }
+        self.position += 1;
+        grid.take_overlay_changes();
+        true
+    }
+
+    /// # seek
+    /// Show the grid after the given number of steps
+    pub fn seek(&mut self, grid: &mut Grid, position: usize) {
+        while self.position > position && self.step_back(grid) {}
+        while self.position < position && self.step_forward(grid) {}
+    }
+
+    /// # grid
+    /// Grid with the recorded map and an empty overlay, ready to replay the steps
+    pub fn grid(&self) -> Result<Grid, MapFileError> {
+        let mut grid = Grid::from_ascii(&self.header.map)?;
+        if let Some(neighbourhood) = Neighbourhood::ALL
+            .into_iter()
+            .find(|neighbourhood| neighbourhood.name() == self.header.neighbourhood)
+        {
+            grid.set_neighbourhood(neighbourhood);
+        }
+        Ok(grid)
+    }
+
+    /// # load
+    /// Load the trace file. The timeline starts at the beginning
+    pub fn load(path: impl AsRef<Path>) -> Result<Timeline, TimelineError> {
+        Timeline::from_json_lines(&fs::read_to_string(path)?)
+    }
+
+    /// # save
+    /// Save the timeline to the trace file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TimelineError> {
+        fs::write(path, self.to_json_lines())?;
+        Ok(())
+    }
+
+    /// # from_json_lines
+    /// Parse the trace: the header line followed by one line per step
+    pub fn from_json_lines(content: &str) -> Result<Timeline, TimelineError> {
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let (header_id, header) = lines.next().ok_or(TimelineError::MissingHeader)?;
+        let header = serde_json::from_str(header).map_err(|error| TimelineError::InvalidLine {
+            line: header_id + 1,
+            error,
+        })?;
+        let steps = lines
+            .map(|(line_id, line)| {
+                serde_json::from_str(line).map_err(|error| TimelineError::InvalidLine {
+                    line: line_id + 1,
+                    error,
+                })
+            })
+            .collect::<Result<Vec<StepRecord>, _>>()?;
+        Ok(Timeline {
+            header,
+            steps,
+            position: 0,
+        })
+    }
+
+    /// # to_json_lines
+    /// Format the trace: the header line followed by one line per step
+    pub fn to_json_lines(&self) -> String {
+        let mut content = String::new();
+        let header = serde_json::to_string(&self.header).expect("Header is always serializable");
+        content.push_str(&header);
+        content.push('\n');
+        for step in self.steps.iter() {
+            let step = serde_json::to_string(step).expect("Step is always serializable");
+            content.push_str(&step);
+            content.push('\n');
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::algorithm::{a_star::AStar, theta_star::ThetaStar, Measurable};
+
+    fn grid() -> Grid {
+        Grid::builder(8, 6)
+            .start(TitleCoords { x: 0, y: 0 })
+            .goal(TitleCoords { x: 7, y: 5 })
+            .obstacles((0..5).map(|y| TitleCoords { x: 4, y }))
+            .build()
+            .unwrap()
+    }
+
+    fn record(algorithm: &mut dyn Algorithm, grid: &mut Grid) -> Timeline {
+        let mut timeline = Timeline::start(algorithm, grid).unwrap();
+        while !algorithm.has_completed() {
+            algorithm.execute_step(grid, 0.01);
+            timeline.record(algorithm, grid);
+        }
+        timeline
+    }
+
+    #[test]
+    fn timeline_scrubbing() {
+        let mut grid = grid();
+        let mut a_star = AStar::default();
+        let mut timeline = record(&mut a_star, &mut grid);
+        let searched = grid.overlay().clone();
+
+        assert!(timeline.is_at_end());
+        assert_eq!(a_star.steps_taken(), timeline.steps().last().unwrap().step);
+        let expanded: usize = timeline
+            .steps()
+            .iter()
+            .map(|step| step.expanded.len())
+            .sum();
+        // The start and the goal titles are not marked
+        assert!(expanded > 0 && expanded <= a_star.statistics().expanded_nodes as usize);
+        // Every discovered title got its parent
+        let first = &timeline.steps()[1];
+        assert_eq!(first.discovered.len(), first.parents.len());
+
+        timeline.seek(&mut grid, 0);
+        assert_eq!(0, timeline.position());
+        assert_eq!(48, grid.overlay().count(SearchState::Unvisited));
+        assert!(!timeline.step_back(&mut grid));
+
+        timeline.step_forward(&mut grid);
+        timeline.step_forward(&mut grid);
+        assert_eq!(1, grid.overlay().count(SearchState::Visited));
+        timeline.seek(&mut grid, timeline.len());
+        assert_eq!(&searched, grid.overlay());
+        assert!(!timeline.step_forward(&mut grid));
+
+        // The scrubbing is not recorded
+        assert!(!timeline.record(&mut a_star, &mut grid));
+    }
+
+    #[test]
+    fn trace_replay() {
+        let mut grid = grid();
+        let mut theta_star = ThetaStar::default();
+        let timeline = record(&mut theta_star, &mut grid);
+
+        let trace = timeline.to_json_lines();
+        assert_eq!(timeline.len() + 1, trace.lines().count());
+        let mut replayed = Timeline::from_json_lines(&trace).unwrap();
+        assert_eq!(timeline.steps(), replayed.steps());
+        assert_eq!(0, replayed.position());
+
+        let mut replay_grid = replayed.grid().unwrap();
+        assert_eq!(grid.to_ascii(), replay_grid.to_ascii());
+        replayed.seek(&mut replay_grid, replayed.len());
+        assert_eq!(grid.overlay(), replay_grid.overlay());
+        assert!(!replay_grid.overlay().waypoints().is_empty());
+        replayed.step_back(&mut replay_grid);
+        assert!(replay_grid.overlay().waypoints().is_empty());
+
+        assert!(matches!(
+            Timeline::from_json_lines(""),
+            Err(TimelineError::MissingHeader)
+        ));
+        assert!(matches!(
+            Timeline::from_json_lines(&format!("{}{{\"step\": 1}}\n", trace)),
+            Err(TimelineError::InvalidLine { .. })
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+//! # Benchmark
+//! Runs the algorithms headlessly on MovingAI benchmark sets (`.map` + `.scen`)
+//! and reports the results to CSV or JSON.
+pub mod scenario;
+
+use crate::{
+    algorithm::{solver::solve, AlgorithmKind},
+    map::{grid::Grid, TitleCoords},
+};
+use scenario::Scenario;
+use serde::{Serialize, Serializer};
+
+/// Path lengths closer than this are treated as equal
+const LENGTH_TOLERANCE: f64 = 1e-4;
+
+/// # BenchmarkRecord
+/// Result of one algorithm on one scenario
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkRecord {
+    /// Position of the scenario in the `.scen` file
+    #[serde(rename = "scenario")]
+    pub scenario_id: usize,
+    pub bucket: u32,
+    #[serde(serialize_with = "serialize_algorithm_id")]
+    pub algorithm: AlgorithmKind,
+    pub start: TitleCoords,
+    pub goal: TitleCoords,
+    pub optimal_length: f64,
+    /// Cost of the found path. None when no path was found
+    pub path_length: Option<f64>,
+    pub expanded_nodes: u32,
+    pub visited_nodes: usize,
+    /// Wall time of the search in milliseconds
+    pub time_ms: f64,
+    /// Why the search did not run, e.g. the algorithm does not support the grid
+    pub error: Option<String>,
+}
+
+impl BenchmarkRecord {
+    /// # is_optimal
+    /// Check if the found path is as long as the expected optimal one
+    pub fn is_optimal(&self) -> bool {
+        self.path_length
+            .is_some_and(|length| (length - self.optimal_length).abs() < LENGTH_TOLERANCE)
+    }
+}
+
+/// # ReportFormat
+/// Output format of the benchmark report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+/// # run_benchmark
+/// Solve every scenario on the grid with every selected algorithm
+pub fn run_benchmark(
+    grid: &Grid,
+    scenarios: &[Scenario],
+    algorithms: &[AlgorithmKind],
+) -> Vec<BenchmarkRecord> {
+    let mut records = Vec::new();
+    for (scenario_id, scenario) in scenarios.iter().enumerate() {
+        for algorithm in algorithms {
+            let result = solve(grid, scenario.start, scenario.goal, algorithm.create());
+
+            let (path_length, expanded_nodes, visited_nodes, time_ms, error) = match result {
+                Ok(result) => (
+                    result.is_goal_reachable().then_some(result.cost),
+                    result.statistics.expanded_nodes,
+                    result.visited_nodes,
+                    result.statistics.elapsed_time_sec * 1000.0,
+                    None,
+                ),
+                Err(error) => (None, 0, 0, 0.0, Some(format!("{:?}", error))),
+            };
+
+            records.push(BenchmarkRecord {
+                scenario_id,
+                bucket: scenario.bucket,
+                algorithm: *algorithm,
+                start: scenario.start,
+                goal: scenario.goal,
+                optimal_length: scenario.optimal_length,
+                path_length,
+                expanded_nodes,
+                visited_nodes,
+                time_ms,
+                error,
+            });
+        }
+    }
+    records
+}
+
+/// # write_report
+/// Format the records in the selected format
+pub fn write_report(records: &[BenchmarkRecord], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Csv => to_csv(records),
+        ReportFormat::Json => to_json(records),
+    }
+}
+
+/// # to_csv
+/// Format the records as CSV with a header line
+pub fn to_csv(records: &[BenchmarkRecord]) -> String {
+    let mut csv = String::from(
+        "scenario,bucket,algorithm,start_x,start_y,goal_x,goal_y,optimal_length,path_length,optimal,expanded_nodes,visited_nodes,time_ms,error\n",
+    );
+    for record in records {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{:.3},{}\n",
+            record.scenario_id,
+            record.bucket,
+            record.algorithm.id(),
+            record.start.x,
+            record.start.y,
+            record.goal.x,
+            record.goal.y,
+            record.optimal_length,
+            record
+                .path_length
+                .map_or(String::new(), |length| length.to_string()),
+            record.is_optimal(),
+            record.expanded_nodes,
+            record.visited_nodes,
+            record.time_ms,
+            record.error.as_deref().unwrap_or_default()
+        ));
+    }
+    csv
+}
+
+/// # to_json
+/// Format the records as a JSON array
+pub fn to_json(records: &[BenchmarkRecord]) -> String {
+    let entries: Vec<JsonEntry> = records
+        .iter()
+        .map(|record| JsonEntry {
+            record,
+            optimal: record.is_optimal(),
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&entries).expect("Records are serializable");
+    json.push('\n');
+    json
+}
+
+/// Record in the JSON report, together with its optimality
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    #[serde(flatten)]
+    record: &'a BenchmarkRecord,
+    optimal: bool,
+}
+
+/// The algorithm is written by its command line id
+fn serialize_algorithm_id<S: Serializer>(
+    algorithm: &AlgorithmKind,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(algorithm.id())
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::map::Neighbourhood;
+
+    #[test]
+    fn benchmark_scenarios() {
+        let grid =
+            Grid::from_movingai("type octile\nheight 3\nwidth 4\nmap\n....\n.@@.\n....\n").unwrap();
+        let scenarios = scenario::parse_scenarios(
+            "version 1\n0\ttest.map\t4\t3\t0\t1\t3\t1\t4\n0\ttest.map\t4\t3\t0\t0\t1\t1\t1\n",
+        )
+        .unwrap();
+
+        let records = run_benchmark(
+            &grid,
+            &scenarios,
+            &[AlgorithmKind::Bfs, AlgorithmKind::AStar],
+        );
+
+        assert_eq!(4, records.len());
+        assert_eq!(AlgorithmKind::AStar, records[1].algorithm);
+        assert_eq!(Some(5.0), records[0].path_length);
+        assert!(!records[0].is_optimal());
+        assert!(records[0].expanded_nodes > 0);
+        // Goal is an obstacle
+        assert_eq!(None, records[3].path_length);
+
+        let csv = to_csv(&records);
+        assert_eq!(5, csv.lines().count());
+        assert!(csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("0,0,bfs,0,1,3,1,4,5,false,"));
+
+        // Jump Point Search does not run on the 4-way grid
+        let mut four_way_grid = grid.clone();
+        four_way_grid.set_neighbourhood(Neighbourhood::Four);
+        let records = run_benchmark(
+            &four_way_grid,
+            &scenarios[..1],
+            &[AlgorithmKind::JumpPointSearch],
+        );
+        assert_eq!(None, records[0].path_length);
+        assert_eq!(Some("UnsupportedGrid"), records[0].error.as_deref());
+        assert!(to_csv(&records).ends_with(",UnsupportedGrid\n"));
+
+        let mut records = records;
+        records[0].optimal_length = f64::INFINITY;
+        records[0].error = Some("Quoted \"error\"".to_string());
+        let json = to_json(&records);
+        assert!(json.contains("\"algorithm\": \"jps\""));
+        assert!(json.contains("\"path_length\": null"));
+        // Quotes are escaped and non-finite numbers are null, so the report stays valid JSON
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!("Quoted \"error\"", report[0]["error"]);
+        assert!(report[0]["optimal_length"].is_null());
+        assert_eq!(false, report[0]["optimal"]);
+        assert_eq!(3, report[0]["goal"]["x"]);
+    }
+}
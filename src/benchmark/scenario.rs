@@ -0,0 +1,123 @@
+use crate::map::TitleCoords;
+use std::{fs, io, path::Path};
+
+/// # Scenario
+/// One search problem of the MovingAI `.scen` file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub bucket: u32,
+    /// Map file the scenario was made for
+    pub map: String,
+    pub map_width: u32,
+    pub map_height: u32,
+    pub start: TitleCoords,
+    pub goal: TitleCoords,
+    /// Length of the optimal path
+    pub optimal_length: f64,
+}
+
+/// # ScenarioError
+/// Errors reported when the scenario file is loaded
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    /// The first line is not `version <number>`
+    InvalidVersion,
+    /// Malformed scenario in the given line (counted from 1)
+    InvalidLine(usize),
+}
+
+impl From<io::Error> for ScenarioError {
+    fn from(error: io::Error) -> Self {
+        ScenarioError::Io(error)
+    }
+}
+
+/// # load_scenarios
+/// Load all scenarios from the MovingAI `.scen` file
+pub fn load_scenarios(path: impl AsRef<Path>) -> Result<Vec<Scenario>, ScenarioError> {
+    parse_scenarios(&fs::read_to_string(path)?)
+}
+
+/// # parse_scenarios
+/// Parse MovingAI `.scen` content.
+///
+/// Every line after the version holds:
+/// `bucket map width height start_x start_y goal_x goal_y optimal_length`
+pub fn parse_scenarios(content: &str) -> Result<Vec<Scenario>, ScenarioError> {
+    let mut lines = content.lines().enumerate();
+
+    match lines.next().map(|(_, line)| line.split_whitespace().next()) {
+        Some(Some("version")) => {}
+        _ => return Err(ScenarioError::InvalidVersion),
+    }
+
+    lines
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_id, line)| parse_scenario(line).ok_or(ScenarioError::InvalidLine(line_id + 1)))
+        .collect()
+}
+
+fn parse_scenario(line: &str) -> Option<Scenario> {
+    let fields: Vec<&str> = if line.contains('\t') {
+        line.trim_end().split('\t').collect()
+    } else {
+        line.split_whitespace().collect()
+    };
+    if fields.len() != 9 {
+        return None;
+    }
+
+    Some(Scenario {
+        bucket: fields[0].parse().ok()?,
+        map: fields[1].to_string(),
+        map_width: fields[2].parse().ok()?,
+        map_height: fields[3].parse().ok()?,
+        start: TitleCoords {
+            x: fields[4].parse().ok()?,
+            y: fields[5].parse().ok()?,
+        },
+        goal: TitleCoords {
+            x: fields[6].parse().ok()?,
+            y: fields[7].parse().ok()?,
+        },
+        optimal_length: fields[8].parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn parse_scenario_file() {
+        let content = "version 1\n\
+                       0\tarena.map\t49\t49\t1\t11\t1\t12\t1.00000000\n\
+                       3\tarena.map\t49\t49\t23\t2\t11\t4\t12.82842712\n";
+
+        let scenarios = parse_scenarios(content).unwrap();
+
+        assert_eq!(2, scenarios.len());
+        assert_eq!(
+            Scenario {
+                bucket: 3,
+                map: "arena.map".to_string(),
+                map_width: 49,
+                map_height: 49,
+                start: TitleCoords { x: 23, y: 2 },
+                goal: TitleCoords { x: 11, y: 4 },
+                optimal_length: 12.82842712,
+            },
+            scenarios[1]
+        );
+
+        assert!(matches!(
+            parse_scenarios("0 arena.map 49 49 1 11 1 12 1.0\n"),
+            Err(ScenarioError::InvalidVersion)
+        ));
+        assert!(matches!(
+            parse_scenarios("version 1\n0 arena.map 49 49 1 11 1 1.0\n"),
+            Err(ScenarioError::InvalidLine(2))
+        ));
+    }
+}
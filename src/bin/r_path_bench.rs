@@ -0,0 +1,104 @@
+use r_path_finder::{
+    algorithm::AlgorithmKind,
+    benchmark::{run_benchmark, scenario::load_scenarios, write_report, ReportFormat},
+    map::{grid::Grid, Terrain},
+};
+use std::process::exit;
+
+const USAGE: &str = "Usage: r_path_bench --map <file.map> --scen <file.scen> \
+[--algorithms bfs,dijkstra,greedy_bfs,a_star,jps,jps_plus,bi_bfs,bi_dijkstra,bi_a_star,d_star_lite,theta_star,lazy_theta_star,dfs,iddfs,ida_star] \
+[--format csv|json] [--output <file>]";
+
+/// # Benchmark runner
+/// Runs the selected algorithms on every scenario of the MovingAI `.scen` file
+/// and writes the report to the output file or to stdout.
+/// Iterative deepening algorithms run only when they are selected
+fn main() {
+    let mut map_path = None;
+    let mut scenario_path = None;
+    let mut algorithms: Vec<AlgorithmKind> = AlgorithmKind::ALL
+        .into_iter()
+        .filter(|kind| !kind.is_iterative_deepening())
+        .collect();
+    let mut format = ReportFormat::Csv;
+    let mut output_path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .unwrap_or_else(|| fail(&format!("Missing value of {}", arg)));
+        match arg.as_str() {
+            "--map" => map_path = Some(value),
+            "--scen" => scenario_path = Some(value),
+            "--algorithms" => {
+                algorithms = value
+                    .split(',')
+                    .map(|id| {
+                        AlgorithmKind::from_id(id.trim())
+                            .unwrap_or_else(|_| fail(&format!("Unknown algorithm {}", id)))
+                    })
+                    .collect()
+            }
+            "--format" => {
+                format = match value.as_str() {
+                    "csv" => ReportFormat::Csv,
+                    "json" => ReportFormat::Json,
+                    _ => fail(&format!("Unknown format {}", value)),
+                }
+            }
+            "--output" => output_path = Some(value),
+            _ => fail(&format!("Unknown argument {}", arg)),
+        }
+    }
+
+    let map_path = map_path.unwrap_or_else(|| fail("Missing --map"));
+    let scenario_path = scenario_path.unwrap_or_else(|| fail("Missing --scen"));
+
+    let mut grid = Grid::load(&map_path)
+        .unwrap_or_else(|error| fail(&format!("Map {} was not loaded: {:?}", map_path, error)));
+    // MovingAI benchmarks use the same cost for all passable terrain
+    for terrain in Terrain::ALL {
+        grid.set_terrain_cost(terrain, 1.0);
+    }
+
+    let scenarios = load_scenarios(&scenario_path).unwrap_or_else(|error| {
+        fail(&format!(
+            "Scenarios {} were not loaded: {:?}",
+            scenario_path, error
+        ))
+    });
+
+    let records = run_benchmark(&grid, &scenarios, &algorithms);
+    for algorithm in &algorithms {
+        let error = records
+            .iter()
+            .filter(|record| record.algorithm == *algorithm)
+            .find_map(|record| record.error.as_ref());
+        if let Some(error) = error {
+            eprintln!("{} did not run: {}", algorithm.id(), error);
+        }
+    }
+    let report = write_report(&records, format);
+
+    match output_path {
+        Some(path) => {
+            if let Err(error) = std::fs::write(&path, report) {
+                fail(&format!("Report {} was not saved: {:?}", path, error));
+            }
+            let optimal = records.iter().filter(|record| record.is_optimal()).count();
+            println!(
+                "{} searches, {} optimal. Report saved to {}",
+                records.len(),
+                optimal,
+                path
+            );
+        }
+        None => print!("{}", report),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    exit(1);
+}
@@ -0,0 +1,84 @@
+use r_path_finder::{
+    algorithm::{Algorithm, AlgorithmKind},
+    graph::WeightedGraph,
+};
+use std::process::exit;
+
+const USAGE: &str = "Usage: r_path_graph --graph <file.gr|file.csv> --from <node> --to <node> \
+[--coordinates <file.co>] [--algorithm bfs|dijkstra|greedy_bfs|a_star]";
+
+/// # Graph query
+/// Finds the path between two nodes of the DIMACS or CSV graph without a window
+/// and prints the nodes of the path, its cost and the search statistics.
+/// A* is used by default
+fn main() {
+    let mut graph_path = None;
+    let mut coordinates_path = None;
+    let mut from = None;
+    let mut to = None;
+    let mut algorithm = AlgorithmKind::AStar;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .unwrap_or_else(|| fail(&format!("Missing value of {}", arg)));
+        match arg.as_str() {
+            "--graph" => graph_path = Some(value),
+            "--coordinates" => coordinates_path = Some(value),
+            "--from" => from = Some(value),
+            "--to" => to = Some(value),
+            "--algorithm" => {
+                algorithm = AlgorithmKind::from_id(&value)
+                    .unwrap_or_else(|_| fail(&format!("Unknown algorithm {}", value)))
+            }
+            _ => fail(&format!("Unknown argument {}", arg)),
+        }
+    }
+
+    let graph_path = graph_path.unwrap_or_else(|| fail("Missing --graph"));
+    let mut graph = WeightedGraph::load(&graph_path)
+        .unwrap_or_else(|error| fail(&format!("Graph {} was not loaded: {:?}", graph_path, error)));
+    if let Some(path) = coordinates_path {
+        if let Err(error) = graph.load_coordinates(&path) {
+            fail(&format!(
+                "Coordinates {} were not loaded: {:?}",
+                path, error
+            ));
+        }
+    }
+
+    let node = |label: Option<String>, argument: &str| {
+        let label = label.unwrap_or_else(|| fail(&format!("Missing {}", argument)));
+        graph
+            .node(&label)
+            .unwrap_or_else(|| fail(&format!("Unknown node {}", label)))
+    };
+    let (start, goal) = (node(from, "--from"), node(to, "--to"));
+
+    let algorithm = algorithm.create_for::<WeightedGraph>().unwrap_or_else(|_| {
+        fail(&format!(
+            "{} searches the grid only",
+            algorithm.create().name()
+        ))
+    });
+    let outcome = graph
+        .query(start, goal, algorithm)
+        .unwrap_or_else(|error| fail(&format!("Search failed: {:?}", error)));
+
+    if outcome.is_goal_reachable() {
+        let labels: Vec<&str> = outcome
+            .path
+            .iter()
+            .filter_map(|node| graph.label(*node))
+            .collect();
+        println!("Path: {}", labels.join(" -> "));
+        println!("Cost: {}", outcome.cost());
+    }
+    println!("{}", outcome.statistics);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    exit(1);
+}
@@ -0,0 +1,128 @@
+use r_path_finder::{
+    algorithm::{timeline::Timeline, AlgorithmKind},
+    export::ImageExport,
+    map::{grid::Grid, TitleCoords},
+    search,
+};
+use std::process::exit;
+
+const USAGE: &str = "Usage: r_path_render (--map <file> | --trace <file.jsonl>) \
+[--algorithm bfs|dijkstra|greedy_bfs|a_star|...] [--from <x,y>] [--to <x,y>] \
+[--png <file.png>] [--gif <file.gif>] [--title-size <pixels>] [--steps-per-frame <steps>] [--frame-delay <1/100 s>]";
+
+/// # Image renderer
+/// Draws the search without a window: the PNG of the final state and the animated GIF of the whole search.
+///
+/// The map is searched by the algorithm (A* by default) from the start to the goal of the map,
+/// unless they are given by `--from` and `--to`. The recorded trace is replayed instead
+fn main() {
+    let mut map_path = None;
+    let mut trace_path = None;
+    let mut algorithm = AlgorithmKind::AStar;
+    let mut from = None;
+    let mut to = None;
+    let mut png_path = None;
+    let mut gif_path = None;
+    let mut export = ImageExport::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .unwrap_or_else(|| fail(&format!("Missing value of {}", arg)));
+        match arg.as_str() {
+            "--map" => map_path = Some(value),
+            "--trace" => trace_path = Some(value),
+            "--algorithm" => {
+                algorithm = AlgorithmKind::from_id(&value)
+                    .unwrap_or_else(|_| fail(&format!("Unknown algorithm {}", value)))
+            }
+            "--from" => from = Some(title_coords(&value)),
+            "--to" => to = Some(title_coords(&value)),
+            "--png" => png_path = Some(value),
+            "--gif" => gif_path = Some(value),
+            "--title-size" => export = export.title_size(number(&value)),
+            "--steps-per-frame" => export = export.steps_per_frame(number(&value)),
+            "--frame-delay" => export = export.frame_delay(number(&value)),
+            _ => fail(&format!("Unknown argument {}", arg)),
+        }
+    }
+    if png_path.is_none() && gif_path.is_none() {
+        fail("Missing --png or --gif");
+    }
+
+    let grid = match (map_path, trace_path) {
+        (Some(map_path), None) => {
+            let mut grid = Grid::load(&map_path).unwrap_or_else(|error| {
+                fail(&format!("Map {} was not loaded: {:?}", map_path, error))
+            });
+            if let Some(from) = from {
+                grid.set_start(from)
+                    .unwrap_or_else(|error| fail(&format!("Invalid start: {:?}", error)));
+            }
+            if let Some(to) = to {
+                grid.set_goal(to)
+                    .unwrap_or_else(|error| fail(&format!("Invalid goal: {:?}", error)));
+            }
+            let mut algorithm = algorithm.create();
+            match &gif_path {
+                Some(path) => export
+                    .save_search_gif(&mut grid, &mut algorithm, path)
+                    .unwrap_or_else(|error| {
+                        fail(&format!("Animation {} was not saved: {:?}", path, error))
+                    }),
+                None => {
+                    search(&mut grid, algorithm)
+                        .unwrap_or_else(|error| fail(&format!("Search failed: {:?}", error)));
+                }
+            }
+            grid
+        }
+        (None, Some(trace_path)) => {
+            let mut timeline = Timeline::load(&trace_path).unwrap_or_else(|error| {
+                fail(&format!("Trace {} was not loaded: {:?}", trace_path, error))
+            });
+            let mut grid = timeline
+                .grid()
+                .unwrap_or_else(|error| fail(&format!("Invalid trace map: {:?}", error)));
+            match &gif_path {
+                Some(path) => export
+                    .save_timeline_gif(&mut timeline, &mut grid, path)
+                    .unwrap_or_else(|error| {
+                        fail(&format!("Animation {} was not saved: {:?}", path, error))
+                    }),
+                None => timeline.seek(&mut grid, timeline.len()),
+            }
+            grid
+        }
+        _ => fail("Expected either --map or --trace"),
+    };
+
+    if let Some(path) = png_path {
+        export
+            .save_png(&grid, &path)
+            .unwrap_or_else(|error| fail(&format!("Image {} was not saved: {:?}", path, error)));
+    }
+}
+
+/// Title coordinates given as `x,y`
+fn title_coords(value: &str) -> TitleCoords {
+    let (x, y) = value
+        .split_once(',')
+        .unwrap_or_else(|| fail(&format!("Expected x,y instead of {}", value)));
+    TitleCoords {
+        x: number(x.trim()),
+        y: number(y.trim()),
+    }
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("Invalid number {}", value)))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    exit(1);
+}
@@ -0,0 +1,207 @@
+//! # Building files
+//! Import and export of the building.
+//!
+//! The file holds the floors from the lowest one. Every floor starts with the line `floor`
+//! followed by its ASCII map (see [`crate::map::map_file`]): `#` wall, `S` start, `G` goal, `.` free,
+//! `g` grass, `m` mud, `w` water. The connectors are free plain titles:
+//! `U` stairs and `L` lift. The same connector on the same title of the following floors
+//! links them. Lines starting with `;` are comments
+use super::{Building, BuildingError, ConnectorKind, FloorCoords};
+use crate::map::{grid::Grid, map_file::MapFileError, TitleCoords};
+use std::{collections::HashMap, fs, io, path::Path};
+
+/// # BuildingFileError
+/// Errors reported when the building file is loaded
+#[derive(Debug)]
+pub enum BuildingFileError {
+    Io(io::Error),
+    /// The map lines (counted from 1) are not preceded by the `floor` line
+    MissingFloor(usize),
+    /// The map of the floor (counted from 0) is not valid
+    InvalidFloor {
+        floor: usize,
+        error: MapFileError,
+    },
+    InvalidBuilding(BuildingError),
+}
+
+impl From<io::Error> for BuildingFileError {
+    fn from(error: io::Error) -> Self {
+        BuildingFileError::Io(error)
+    }
+}
+
+impl From<BuildingError> for BuildingFileError {
+    fn from(error: BuildingError) -> Self {
+        BuildingFileError::InvalidBuilding(error)
+    }
+}
+
+impl Building {
+    /// # load
+    /// Load the building from the file
+    pub fn load(path: impl AsRef<Path>) -> Result<Building, BuildingFileError> {
+        Building::from_text(&fs::read_to_string(path)?)
+    }
+
+    /// # save
+    /// Save the building to the file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BuildingFileError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    /// # from_text
+    /// Parse the building from the text with the floor maps
+    pub fn from_text(content: &str) -> Result<Building, BuildingFileError> {
+        let mut floor_maps: Vec<String> = Vec::new();
+        let mut connector_titles: HashMap<(ConnectorKind, TitleCoords), Vec<usize>> =
+            HashMap::new();
+
+        for (line_id, line) in content.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            if line == "floor" {
+                floor_maps.push(String::new());
+                continue;
+            }
+            let floor = floor_maps.len().checked_sub(1);
+            let (Some(floor), Some(floor_map)) = (floor, floor_maps.last_mut()) else {
+                return Err(BuildingFileError::MissingFloor(line_id + 1));
+            };
+            let y = floor_map.lines().count();
+            for (x, character) in line.chars().enumerate() {
+                let kind = match character {
+                    'U' => ConnectorKind::Stairs,
+                    'L' => ConnectorKind::Lift,
+                    _ => {
+                        floor_map.push(character);
+                        continue;
+                    }
+                };
+                connector_titles
+                    .entry((kind, TitleCoords { x, y }))
+                    .or_default()
+                    .push(floor);
+                floor_map.push('.');
+            }
+            floor_map.push('\n');
+        }
+
+        let floors = floor_maps
+            .iter()
+            .enumerate()
+            .map(|(floor, floor_map)| {
+                Grid::from_ascii(floor_map)
+                    .map_err(|error| BuildingFileError::InvalidFloor { floor, error })
+            })
+            .collect::<Result<Vec<Grid>, _>>()?;
+        let mut building = Building::from_floors(floors)?;
+
+        // Sorted by the kind and the title, so the order does not depend on the hash map
+        let mut connector_titles: Vec<_> = connector_titles.into_iter().collect();
+        connector_titles.sort_by_key(|((kind, title), _)| (*kind as usize, title.y, title.x));
+        for ((kind, title), floors) in connector_titles {
+            for shaft in consecutive_floors(&floors) {
+                building.add_connector(kind, title, shaft)?;
+            }
+        }
+        Ok(building)
+    }
+
+    /// # to_text
+    /// Export the building to the text with the floor maps.
+    ///
+    /// The terrain of the connector titles is not exported
+    pub fn to_text(&self) -> String {
+        let mut content = String::new();
+        for (floor, grid) in self.floors.iter().enumerate() {
+            content.push_str("floor\n");
+            for (y, row) in grid.to_ascii().lines().enumerate() {
+                for (x, character) in row.chars().enumerate() {
+                    let floor_coords = FloorCoords {
+                        floor,
+                        title: TitleCoords { x, y },
+                    };
+                    content.push(match self.connector_at(floor_coords) {
+                        Some(connector) if connector.kind == ConnectorKind::Stairs => 'U',
+                        Some(_) => 'L',
+                        None => character,
+                    });
+                }
+                content.push('\n');
+            }
+        }
+        content
+    }
+}
+
+/// Ranges of the following floors, e.g. [0, 1, 3, 4, 5] -> 0..=1, 3..=5
+fn consecutive_floors(floors: &[usize]) -> Vec<std::ops::RangeInclusive<usize>> {
+    let mut ranges = Vec::new();
+    let mut range_start = 0;
+    for id in 1..=floors.len() {
+        if id == floors.len() || floors[id] != floors[id - 1] + 1 {
+            ranges.push(floors[range_start]..=floors[id - 1]);
+            range_start = id;
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::algorithm::search_space::SearchSpace;
+
+    const BUILDING: &str = "; Two floors with stairs and lift\n\
+        floor\n\
+        S.#U\n\
+        L.#.\n\
+        floor\n\
+        ...U\n\
+        L..G\n";
+
+    #[test]
+    fn building_round_trip() {
+        let mut building = Building::from_text(BUILDING).unwrap();
+        assert_eq!(2, building.floor_count());
+        assert_eq!(2, building.connectors().len());
+        assert_eq!(0..=1, building.connectors()[0].floors);
+        assert_eq!(ConnectorKind::Lift, building.connectors()[1].kind);
+        assert_eq!(Some(1), building.goal().map(|goal| goal.floor));
+        assert!(building
+            .floor(0)
+            .unwrap()
+            .is_obstacle(TitleCoords { x: 2, y: 1 }));
+        assert_eq!(BUILDING.split_once('\n').unwrap().1, building.to_text());
+
+        let (start, goal) = (building.start().unwrap(), building.goal().unwrap());
+        let outcome = building.shortest_path(start, goal).unwrap();
+        // Down to the lift, up and along the first floor
+        assert_eq!(1.0 + 2.0 + 3.0, outcome.cost());
+        assert!(building.is_goal(*outcome.path.last().unwrap()));
+
+        assert!(matches!(
+            Building::from_text("S.\nfloor\n..\n"),
+            Err(BuildingFileError::MissingFloor(1))
+        ));
+        assert!(matches!(
+            Building::from_text("floor\nU.\nfloor\n..\n"),
+            Err(BuildingFileError::InvalidBuilding(
+                BuildingError::InvalidConnector
+            ))
+        ));
+        assert!(matches!(
+            Building::from_text("floor\nS.\nfloor\nS.\n"),
+            Err(BuildingFileError::InvalidBuilding(BuildingError::Map(_)))
+        ));
+        assert!(matches!(
+            Building::from_text("floor\n.x\n"),
+            Err(BuildingFileError::InvalidFloor { floor: 0, .. })
+        ));
+        assert_eq!(vec![0..=1, 3..=5], consecutive_floors(&[0, 1, 3, 4, 5]));
+    }
+}
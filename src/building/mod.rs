@@ -0,0 +1,611 @@
+//! # Building
+//! Several floors of the same size stacked on each other, e.g. for the navigation in a building.
+//!
+//! Every floor is a [`Grid`]. Stairs and lifts are connectors placed on the same title
+//! of several floors, moving between the floors costs the connector cost per floor.
+//! The building is a [`SearchSpace`], so Bfs, Dijkstra, Greedy Bfs and A* search all floors at once.
+//! It is loaded from a text file with the ASCII map of every floor, see [`building_file`].
+pub mod building_file;
+
+use crate::{
+    algorithm::{
+        a_star::AStar,
+        search_space::SearchSpace,
+        solver::{search, SearchOutcome},
+        Algorithm, AlgorithmError,
+    },
+    map::{grid::Grid, MapError, Neighbourhood, SearchState, TitleCoords},
+};
+#[cfg(feature = "gui")]
+use piston_window::{ellipse, rectangle, Context, G2d};
+use std::ops::RangeInclusive;
+
+/// # FloorCoords
+/// Helper structure to store the location of title in the building (floor, x, y)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct FloorCoords {
+    pub floor: usize,
+    pub title: TitleCoords,
+}
+
+/// # BuildingError
+/// Errors reported when the building is edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildingError {
+    /// The floor is not in the building
+    UnknownFloor,
+    /// The floors have different sizes or there is no floor
+    InvalidFloors,
+    /// The connector does not link two floors or it is blocked by an obstacle
+    InvalidConnector,
+    Map(MapError),
+}
+
+impl From<MapError> for BuildingError {
+    fn from(error: MapError) -> Self {
+        BuildingError::Map(error)
+    }
+}
+
+/// # ConnectorKind
+/// Connector type. Every connector has its own cost to move one floor up or down
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ConnectorKind {
+    /// Stairs lead to the next floor only
+    Stairs = 0,
+    /// Lift goes directly to any floor of its shaft
+    Lift = 1,
+}
+
+impl ConnectorKind {
+    pub const ALL: [ConnectorKind; 2] = [ConnectorKind::Stairs, ConnectorKind::Lift];
+
+    /// # default_cost
+    /// The movement cost to move one floor up or down
+    pub fn default_cost(&self) -> f64 {
+        match self {
+            ConnectorKind::Stairs => 4.0,
+            ConnectorKind::Lift => 2.0,
+        }
+    }
+
+    /// # name
+    /// Connector name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectorKind::Stairs => "Stairs",
+            ConnectorKind::Lift => "Lift",
+        }
+    }
+}
+
+/// # Connector
+/// Stairs or lift on the title of the floors in the range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connector {
+    pub kind: ConnectorKind,
+    pub title: TitleCoords,
+    pub floors: RangeInclusive<usize>,
+}
+
+/// # Building
+/// Floors of the same size linked by the connectors
+#[derive(Clone)]
+pub struct Building {
+    floors: Vec<Grid>,
+    connectors: Vec<Connector>,
+    connector_costs: [f64; ConnectorKind::ALL.len()],
+    /// Floor shown on the screen
+    displayed_floor: usize,
+    start: Option<FloorCoords>,
+    goal: Option<FloorCoords>,
+}
+
+impl Building {
+    /// # new
+    /// Create the building with empty floors of columns x rows titles
+    pub fn new(columns: u32, rows: u32, floors: usize) -> Self {
+        Self {
+            floors: vec![Grid::new(0, 0, columns, rows, 1); floors.max(1)],
+            connectors: Vec::new(),
+            connector_costs: ConnectorKind::ALL.map(|kind| kind.default_cost()),
+            displayed_floor: 0,
+            start: None,
+            goal: None,
+        }
+    }
+
+    /// # from_floors
+    /// Create the building from the floors, the lowest one first.
+    ///
+    /// The floors must have the same size, the start and the goal may be placed on one floor only
+    pub fn from_floors(floors: Vec<Grid>) -> Result<Self, BuildingError> {
+        let Some(ground_floor) = floors.first() else {
+            return Err(BuildingError::InvalidFloors);
+        };
+        let size = (ground_floor.columns, ground_floor.rows);
+        if floors
+            .iter()
+            .any(|floor| (floor.columns, floor.rows) != size)
+        {
+            return Err(BuildingError::InvalidFloors);
+        }
+
+        let find = |title: fn(&Grid) -> Option<TitleCoords>| {
+            let mut found = floors
+                .iter()
+                .enumerate()
+                .filter_map(|(floor, grid)| title(grid).map(|title| FloorCoords { floor, title }));
+            match (found.next(), found.next()) {
+                (_, Some(_)) => Err(BuildingError::Map(MapError::TitleOccupied)),
+                (found, None) => Ok(found),
+            }
+        };
+        let start = find(|grid| grid.start_title)?;
+        let goal = find(|grid| grid.goal_title)?;
+
+        let mut building = Self::new(size.0, size.1, floors.len());
+        building.floors = floors;
+        building.start = start;
+        building.goal = goal;
+        Ok(building)
+    }
+
+    /// # floor
+    /// Get the floor. None if the building has no such floor
+    pub fn floor(&self, floor: usize) -> Option<&Grid> {
+        self.floors.get(floor)
+    }
+
+    /// # floor_mut
+    /// Get the floor to edit its obstacles and terrain.
+    ///
+    /// Use [`Building::set_start`] and [`Building::set_goal`] to place the start and the goal
+    pub fn floor_mut(&mut self, floor: usize) -> Option<&mut Grid> {
+        self.floors.get_mut(floor)
+    }
+
+    pub fn floor_count(&self) -> usize {
+        self.floors.len()
+    }
+
+    /// # add_connector
+    /// Place the stairs or the lift on the title of the floors in the range
+    pub fn add_connector(
+        &mut self,
+        kind: ConnectorKind,
+        title: TitleCoords,
+        floors: RangeInclusive<usize>,
+    ) -> Result<(), BuildingError> {
+        if *floors.end() >= self.floors.len() {
+            return Err(BuildingError::UnknownFloor);
+        }
+        if floors.start() >= floors.end() {
+            return Err(BuildingError::InvalidConnector);
+        }
+        if !self.floors[0].is_within_bounds(title) {
+            return Err(MapError::OutOfBounds.into());
+        }
+        if floors
+            .clone()
+            .any(|floor| self.floors[floor].is_obstacle(title))
+        {
+            return Err(BuildingError::InvalidConnector);
+        }
+        self.connectors.push(Connector {
+            kind,
+            title,
+            floors,
+        });
+        Ok(())
+    }
+
+    /// # connectors
+    /// All stairs and lifts of the building
+    pub fn connectors(&self) -> &[Connector] {
+        &self.connectors
+    }
+
+    /// # connector_at
+    /// Stairs or lift on the title of the floor
+    pub fn connector_at(&self, floor_coords: FloorCoords) -> Option<&Connector> {
+        self.connectors.iter().find(|connector| {
+            connector.title == floor_coords.title && connector.floors.contains(&floor_coords.floor)
+        })
+    }
+
+    /// # connector_cost
+    /// The movement cost to move one floor up or down by the connector
+    pub fn connector_cost(&self, kind: ConnectorKind) -> f64 {
+        self.connector_costs[kind as usize]
+    }
+
+    /// # set_connector_cost
+    /// Configure the movement cost of the connector per floor. The cost is at least 0
+    pub fn set_connector_cost(&mut self, kind: ConnectorKind, cost: f64) {
+        self.connector_costs[kind as usize] = cost.max(0.0);
+    }
+
+    /// # set_neighbourhood
+    /// Configure the movement on every floor
+    pub fn set_neighbourhood(&mut self, neighbourhood: Neighbourhood) {
+        for floor in self.floors.iter_mut() {
+            floor.set_neighbourhood(neighbourhood);
+        }
+    }
+
+    pub fn start(&self) -> Option<FloorCoords> {
+        self.start
+    }
+
+    pub fn goal(&self) -> Option<FloorCoords> {
+        self.goal
+    }
+
+    /// # set_start
+    /// Set the start title. The previous start title is cleared, also on another floor
+    pub fn set_start(&mut self, floor_coords: FloorCoords) -> Result<(), BuildingError> {
+        let floor = self
+            .floors
+            .get_mut(floor_coords.floor)
+            .ok_or(BuildingError::UnknownFloor)?;
+        floor.set_start(floor_coords.title)?;
+        if let Some(previous) = self.start.filter(|start| start.floor != floor_coords.floor) {
+            self.floors[previous.floor].clear_title(previous.title)?;
+        }
+        self.start = Some(floor_coords);
+        Ok(())
+    }
+
+    /// # set_goal
+    /// Set the goal title. The previous goal title is cleared, also on another floor
+    pub fn set_goal(&mut self, floor_coords: FloorCoords) -> Result<(), BuildingError> {
+        let floor = self
+            .floors
+            .get_mut(floor_coords.floor)
+            .ok_or(BuildingError::UnknownFloor)?;
+        floor.set_goal(floor_coords.title)?;
+        if let Some(previous) = self.goal.filter(|goal| goal.floor != floor_coords.floor) {
+            self.floors[previous.floor].clear_title(previous.title)?;
+        }
+        self.goal = Some(floor_coords);
+        Ok(())
+    }
+
+    /// # shortest_path
+    /// Find the cheapest path between the titles with A*
+    pub fn shortest_path(
+        &mut self,
+        start: FloorCoords,
+        goal: FloorCoords,
+    ) -> Result<SearchOutcome<FloorCoords>, AlgorithmError> {
+        self.query(start, goal, AStar::new())
+    }
+
+    /// # query
+    /// Search the path between the titles with the algorithm without a window.
+    ///
+    /// The start and the goal of the building are replaced. The algorithm should be a fresh instance
+    pub fn query(
+        &mut self,
+        start: FloorCoords,
+        goal: FloorCoords,
+        algorithm: impl Algorithm<Building>,
+    ) -> Result<SearchOutcome<FloorCoords>, AlgorithmError> {
+        self.set_start(start)
+            .and_then(|_| self.set_goal(goal))
+            .map_err(|_| AlgorithmError::InvalidInputData)?;
+        self.clear_marks();
+        search(self, algorithm)
+    }
+
+    /// # displayed_floor
+    /// Floor shown on the screen
+    pub fn displayed_floor(&self) -> usize {
+        self.displayed_floor
+    }
+
+    /// # show_floor
+    /// Select the floor shown on the screen. Ignored if the building has no such floor
+    pub fn show_floor(&mut self, floor: usize) {
+        if floor < self.floors.len() {
+            self.displayed_floor = floor;
+        }
+    }
+
+    /// # visited_per_floor
+    /// Number of the visited titles of every floor, e.g. `12 | 40 | 0`
+    pub fn visited_per_floor(&self) -> String {
+        self.floors
+            .iter()
+            .map(|floor| floor.overlay().count(SearchState::Visited).to_string())
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// # fit_into
+    /// Scale the title size, so the whole floor fits into the screen area (width x height)
+    pub fn fit_into(&mut self, width: u32, height: u32) {
+        for floor in self.floors.iter_mut() {
+            floor.fit_into(width, height);
+        }
+    }
+
+    /// # title_at
+    /// Title of the displayed floor under the mouse
+    pub fn title_at(&self, mouse_position: &[f64; 2]) -> Option<FloorCoords> {
+        let floor = self.displayed_floor;
+        self.floors[floor]
+            .screen_to_title_coords(mouse_position)
+            .map(|title| FloorCoords { floor, title })
+    }
+
+    /// # reset
+    /// Remove the start, the goal and the search marks. The floors and connectors are kept
+    pub fn reset(&mut self) {
+        for floor_coords in [self.start.take(), self.goal.take()].into_iter().flatten() {
+            let _ = self.floors[floor_coords.floor].clear_title(floor_coords.title);
+        }
+        self.clear_marks();
+    }
+
+    /// The lowest cost to move one floor up or down, 0 without connectors
+    fn cheapest_floor_change(&self) -> f64 {
+        self.connectors
+            .iter()
+            .map(|connector| self.connector_cost(connector.kind))
+            .reduce(f64::min)
+            .unwrap_or(0.0)
+    }
+}
+
+impl SearchSpace for Building {
+    type Node = FloorCoords;
+
+    fn start_node(&self) -> Option<FloorCoords> {
+        self.goal.and(self.start)
+    }
+
+    fn is_goal(&self, node: FloorCoords) -> bool {
+        self.goal == Some(node)
+    }
+
+    /// Neighbours on the same floor and the floors reachable by the connector on the title
+    fn successors(&self, node: FloorCoords) -> Vec<(FloorCoords, f64)> {
+        let floor = &self.floors[node.floor];
+        let mut successors: Vec<(FloorCoords, f64)> = floor
+            .successors(node.title)
+            .into_iter()
+            .map(|(title, cost)| {
+                let next = FloorCoords {
+                    floor: node.floor,
+                    title,
+                };
+                (next, cost)
+            })
+            .collect();
+
+        for connector in &self.connectors {
+            if connector.title != node.title || !connector.floors.contains(&node.floor) {
+                continue;
+            }
+            let cost_per_floor = self.connector_cost(connector.kind);
+            let reachable_floors = match connector.kind {
+                ConnectorKind::Stairs => (node.floor.saturating_sub(1)..=node.floor + 1)
+                    .filter(|floor| connector.floors.contains(floor))
+                    .collect(),
+                ConnectorKind::Lift => connector.floors.clone().collect::<Vec<_>>(),
+            };
+            for next_floor in reachable_floors {
+                if next_floor == node.floor || self.floors[next_floor].is_obstacle(node.title) {
+                    continue;
+                }
+                let next = FloorCoords {
+                    floor: next_floor,
+                    title: node.title,
+                };
+                successors.push((
+                    next,
+                    cost_per_floor * node.floor.abs_diff(next_floor) as f64,
+                ));
+            }
+        }
+        successors
+    }
+
+    /// The grid distance metric on the floor plus the cheapest way to reach the floor of the goal
+    fn estimate(&self, node: FloorCoords) -> f64 {
+        let Some(goal) = self.goal else {
+            return 0.0;
+        };
+        self.floors[node.floor].heuristic(node.title, goal.title)
+            + self.cheapest_floor_change() * node.floor.abs_diff(goal.floor) as f64
+    }
+
+    fn heuristic_name(&self) -> String {
+        format!(
+            "{} + {:.1} per floor",
+            self.floors[0].distance_metric().name(),
+            self.cheapest_floor_change()
+        )
+    }
+
+    /// The distance on the floor, moving between the floors is not counted
+    fn distance(&self, from: FloorCoords, to: FloorCoords) -> Option<f64> {
+        Some(self.floors[from.floor].centre_distance(from.title, to.title))
+    }
+
+    fn mark_visited(&mut self, node: FloorCoords) {
+        self.floors[node.floor].mark_visited(node.title);
+    }
+
+    fn mark_process(&mut self, node: FloorCoords) {
+        self.floors[node.floor].mark_process(node.title);
+    }
+
+    fn mark_path(&mut self, node: FloorCoords) {
+        self.floors[node.floor].set_trace_back_path(node.title);
+    }
+
+    fn clear_marks(&mut self) {
+        for floor in self.floors.iter_mut() {
+            floor.clear_overlay();
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl Building {
+    /// # render
+    /// Render the displayed floor with its stairs (squares) and lifts (circles)
+    pub fn render(&mut self, ctx: &Context, g: &mut G2d) {
+        let floor = self.displayed_floor;
+        self.floors[floor].render(ctx, g);
+
+        let size = self.floors[floor].title_size() as f64 / 2.0;
+        for connector in &self.connectors {
+            if !connector.floors.contains(&floor) {
+                continue;
+            }
+            let [x, y] = self.floors[floor].title_centre(connector.title);
+            let area = [x - size / 2.0, y - size / 2.0, size, size];
+            match connector.kind {
+                ConnectorKind::Stairs => rectangle([0.2, 0.2, 0.2, 1.0], area, ctx.transform, g),
+                ConnectorKind::Lift => ellipse([1.0, 1.0, 1.0, 1.0], area, ctx.transform, g),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::algorithm::{dijkstra::Dijkstra, AlgorithmKind};
+
+    /// Three floors linked by the stairs and the lift in opposite corners,
+    /// the ground floor is split by a wall
+    fn office() -> Building {
+        let mut building = Building::new(6, 4, 3);
+        for y in 0..4 {
+            building
+                .floor_mut(0)
+                .unwrap()
+                .set_obstacle(TitleCoords { x: 3, y })
+                .unwrap();
+        }
+        building
+            .add_connector(ConnectorKind::Stairs, TitleCoords { x: 0, y: 3 }, 0..=2)
+            .unwrap();
+        building
+            .add_connector(ConnectorKind::Lift, TitleCoords { x: 5, y: 0 }, 0..=2)
+            .unwrap();
+        building
+    }
+
+    #[test]
+    fn building_shortest_path() {
+        let mut building = office();
+        let start = FloorCoords {
+            floor: 0,
+            title: TitleCoords { x: 0, y: 0 },
+        };
+        let goal = FloorCoords {
+            floor: 0,
+            title: TitleCoords { x: 5, y: 3 },
+        };
+
+        // Up the stairs, along the first floor and down by the lift
+        let outcome = building.shortest_path(start, goal).unwrap();
+        assert_eq!(3.0 + 4.0 + 8.0 + 2.0 + 3.0, outcome.cost());
+        assert!(outcome.path.iter().any(|node| node.floor == 1));
+        assert_eq!(goal, *outcome.path.last().unwrap());
+        assert!(
+            building
+                .floor(1)
+                .unwrap()
+                .overlay()
+                .count(SearchState::Path)
+                > 0
+        );
+
+        let dijkstra = building.query(start, goal, Dijkstra::new()).unwrap();
+        assert_eq!(outcome.cost(), dijkstra.cost());
+        assert!(dijkstra.statistics.expanded_nodes >= outcome.statistics.expanded_nodes);
+
+        // The lift goes two floors at once, the stairs one by one
+        building.set_connector_cost(ConnectorKind::Stairs, 1.0);
+        let top = FloorCoords {
+            floor: 2,
+            title: TitleCoords { x: 0, y: 3 },
+        };
+        let outcome = building.query(start, top, AStar::new()).unwrap();
+        assert_eq!(3.0 + 2.0, outcome.cost());
+        assert_eq!("Manhattan + 1.0 per floor", building.heuristic_name());
+
+        assert!(AlgorithmKind::JumpPointSearch
+            .create_for::<Building>()
+            .is_err());
+    }
+
+    #[test]
+    fn building_edits() {
+        let mut building = office();
+        let start = FloorCoords {
+            floor: 1,
+            title: TitleCoords { x: 1, y: 1 },
+        };
+        building.set_start(start).unwrap();
+        building
+            .set_start(FloorCoords {
+                floor: 2,
+                title: TitleCoords { x: 1, y: 1 },
+            })
+            .unwrap();
+        assert!(building.floor(1).unwrap().start_title.is_none());
+        assert_eq!(Some(2), building.start().map(|start| start.floor));
+        assert_eq!(
+            Err(BuildingError::UnknownFloor),
+            building.set_goal(FloorCoords {
+                floor: 3,
+                title: TitleCoords { x: 1, y: 1 },
+            })
+        );
+        assert_eq!(
+            Err(BuildingError::InvalidConnector),
+            building.add_connector(ConnectorKind::Lift, TitleCoords { x: 3, y: 0 }, 0..=1)
+        );
+        assert_eq!(
+            Err(BuildingError::InvalidConnector),
+            building.add_connector(ConnectorKind::Lift, TitleCoords { x: 1, y: 0 }, 1..=1)
+        );
+        assert_eq!(
+            ConnectorKind::Lift,
+            building
+                .connector_at(FloorCoords {
+                    floor: 1,
+                    title: TitleCoords { x: 5, y: 0 },
+                })
+                .unwrap()
+                .kind
+        );
+
+        building.show_floor(2);
+        building.show_floor(5);
+        assert_eq!(2, building.displayed_floor());
+        assert_eq!(
+            Some(FloorCoords {
+                floor: 2,
+                title: TitleCoords { x: 4, y: 2 },
+            }),
+            building.title_at(&[4.5, 2.5])
+        );
+
+        building.reset();
+        assert!(building.start().is_none());
+        assert!(building.floor(2).unwrap().start_title.is_none());
+        assert!(matches!(
+            Building::from_floors(vec![Grid::new(0, 0, 2, 2, 1), Grid::new(0, 0, 3, 2, 1)]),
+            Err(BuildingError::InvalidFloors)
+        ));
+    }
+}
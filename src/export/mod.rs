@@ -0,0 +1,372 @@
+//! # Export
+//! Headless images of the grid: a PNG of its current state and an animated GIF of the whole search.
+//!
+//! The images are drawn by the software [`raster`], so no window or graphics device is needed,
+//! e.g. to generate the documentation or bug reports in CI.
+pub mod raster;
+
+use crate::algorithm::{timeline::Timeline, Algorithm, AlgorithmError, ONE_ITERATION_TIME_SEC};
+use crate::map::grid::Grid;
+use raster::Raster;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// Pause on the last frame of the animation in hundredths of a second
+const FINAL_FRAME_DELAY: u16 = 200;
+
+/// # ExportError
+/// Errors reported when the image is written
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+    /// The search could not be started
+    Algorithm(AlgorithmError),
+    /// The GIF is limited to 65535 x 65535 pixels
+    ImageTooLarge,
+}
+
+impl From<io::Error> for ExportError {
+    fn from(error: io::Error) -> Self {
+        ExportError::Io(error)
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(error: png::EncodingError) -> Self {
+        ExportError::Png(error)
+    }
+}
+
+impl From<gif::EncodingError> for ExportError {
+    fn from(error: gif::EncodingError) -> Self {
+        ExportError::Gif(error)
+    }
+}
+
+impl From<AlgorithmError> for ExportError {
+    fn from(error: AlgorithmError) -> Self {
+        ExportError::Algorithm(error)
+    }
+}
+
+/// # ImageExport
+/// Settings of the exported images.
+///
+/// ```no_run
+/// use r_path_finder::algorithm::a_star::AStar;
+/// use r_path_finder::export::ImageExport;
+/// use r_path_finder::map::{grid::Grid, TitleCoords};
+///
+/// let mut grid = Grid::builder(20, 20)
+///     .start(TitleCoords { x: 0, y: 0 })
+///     .goal(TitleCoords { x: 19, y: 19 })
+///     .build()
+///     .unwrap();
+/// let export = ImageExport::new().title_size(12).steps_per_frame(4);
+/// export.save_search_gif(&mut grid, &mut AStar::default(), "a_star.gif").unwrap();
+/// export.save_png(&grid, "a_star.png").unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageExport {
+    /// Size of the title in pixels
+    title_size: u32,
+    /// Delay between the frames in hundredths of a second
+    frame_delay: u16,
+    /// Algorithm steps shown by one frame
+    steps_per_frame: u32,
+}
+
+impl Default for ImageExport {
+    fn default() -> Self {
+        Self {
+            title_size: 20,
+            frame_delay: 4,
+            steps_per_frame: 1,
+        }
+    }
+}
+
+impl ImageExport {
+    /// # new
+    /// Titles of 20 pixels, one step per frame and 25 frames per second
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # title_size
+    /// Size of the title in pixels
+    pub fn title_size(mut self, title_size: u32) -> Self {
+        self.title_size = title_size.max(1);
+        self
+    }
+
+    /// # frame_delay
+    /// Delay between the frames of the animation in hundredths of a second
+    pub fn frame_delay(mut self, frame_delay: u16) -> Self {
+        self.frame_delay = frame_delay;
+        self
+    }
+
+    /// # steps_per_frame
+    /// Algorithm steps shown by one frame of the animation, so long searches stay short
+    pub fn steps_per_frame(mut self, steps_per_frame: u32) -> Self {
+        self.steps_per_frame = steps_per_frame.max(1);
+        self
+    }
+
+    /// # rasterize
+    /// Draw the grid with its search overlay
+    pub fn rasterize(&self, grid: &Grid) -> Raster {
+        Raster::grid(grid, self.title_size)
+    }
+
+    /// # write_png
+    /// Write the PNG of the grid with its search overlay
+    pub fn write_png(&self, grid: &Grid, writer: impl Write) -> Result<(), ExportError> {
+        let raster = self.rasterize(grid);
+        let mut encoder = png::Encoder::new(writer, raster.width(), raster.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(raster.pixels())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// # save_png
+    /// Save the PNG of the grid with its search overlay to the file
+    pub fn save_png(&self, grid: &Grid, path: impl AsRef<Path>) -> Result<(), ExportError> {
+        self.write_png(grid, BufWriter::new(File::create(path)?))
+    }
+
+    /// # write_search_gif
+    /// Run the algorithm on the grid and write the animated GIF of the search.
+    ///
+    /// The first frame shows the started search, the last one the final state, which is kept on the grid.
+    /// The algorithm should be a fresh instance
+    pub fn write_search_gif<A: Algorithm + ?Sized>(
+        &self,
+        grid: &mut Grid,
+        algorithm: &mut A,
+        writer: impl Write,
+    ) -> Result<(), ExportError> {
+        algorithm.start(grid)?;
+        let mut animation = GifAnimation::new(writer, &self.rasterize(grid))?;
+        while !algorithm.has_completed() {
+            let steps_taken = algorithm.steps_taken();
+            for _ in 0..self.steps_per_frame {
+                if algorithm.has_completed() {
+                    break;
+                }
+                // Every step is ready to execute when a whole iteration time has passed
+                algorithm.execute_step(grid, ONE_ITERATION_TIME_SEC);
+            }
+            // Nothing changes any more, e.g. the search was not started
+            if algorithm.steps_taken() == steps_taken && !algorithm.has_completed() {
+                break;
+            }
+            animation.frame(&self.rasterize(grid), self.frame_delay)?;
+        }
+        animation.finish(FINAL_FRAME_DELAY)
+    }
+
+    /// # save_search_gif
+    /// Run the algorithm on the grid and save the animated GIF of the search to the file
+    pub fn save_search_gif<A: Algorithm + ?Sized>(
+        &self,
+        grid: &mut Grid,
+        algorithm: &mut A,
+        path: impl AsRef<Path>,
+    ) -> Result<(), ExportError> {
+        self.write_search_gif(grid, algorithm, BufWriter::new(File::create(path)?))
+    }
+
+    /// # write_timeline_gif
+    /// Write the animated GIF of the recorded search replayed on the grid, e.g. the one from
+    /// [`Timeline::grid`]. The replay starts at the beginning and ends with all steps shown
+    pub fn write_timeline_gif(
+        &self,
+        timeline: &mut Timeline,
+        grid: &mut Grid,
+        writer: impl Write,
+    ) -> Result<(), ExportError> {
+        timeline.seek(grid, 0);
+        let mut animation = GifAnimation::new(writer, &self.rasterize(grid))?;
+        while !timeline.is_at_end() {
+            let position = timeline.position() + self.steps_per_frame as usize;
+            timeline.seek(grid, position);
+            animation.frame(&self.rasterize(grid), self.frame_delay)?;
+        }
+        animation.finish(FINAL_FRAME_DELAY)
+    }
+
+    /// # save_timeline_gif
+    /// Save the animated GIF of the recorded search replayed on the grid to the file
+    pub fn save_timeline_gif(
+        &self,
+        timeline: &mut Timeline,
+        grid: &mut Grid,
+        path: impl AsRef<Path>,
+    ) -> Result<(), ExportError> {
+        self.write_timeline_gif(timeline, grid, BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Frames of the GIF. Every frame is written when the next one arrives,
+/// so the last one can get a longer delay
+struct GifAnimation<W: Write> {
+    encoder: gif::Encoder<W>,
+    /// Palette indices of the pixels and the palette of the previous frame
+    pending: (Vec<u8>, Vec<u8>),
+    size: (u16, u16),
+}
+
+impl<W: Write> GifAnimation<W> {
+    fn new(writer: W, first_frame: &Raster) -> Result<Self, ExportError> {
+        let size = (
+            u16::try_from(first_frame.width()).map_err(|_| ExportError::ImageTooLarge)?,
+            u16::try_from(first_frame.height()).map_err(|_| ExportError::ImageTooLarge)?,
+        );
+        let mut encoder = gif::Encoder::new(writer, size.0, size.1, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        Ok(Self {
+            encoder,
+            pending: indexed_pixels(first_frame),
+            size,
+        })
+    }
+
+    fn frame(&mut self, raster: &Raster, delay: u16) -> Result<(), ExportError> {
+        let next = indexed_pixels(raster);
+        self.write_pending(delay)?;
+        self.pending = next;
+        Ok(())
+    }
+
+    fn finish(mut self, delay: u16) -> Result<(), ExportError> {
+        self.write_pending(delay)?;
+        self.encoder.into_inner()?.flush()?;
+        Ok(())
+    }
+
+    fn write_pending(&mut self, delay: u16) -> Result<(), ExportError> {
+        let (pixels, palette) = std::mem::take(&mut self.pending);
+        let mut frame =
+            gif::Frame::from_palette_pixels(self.size.0, self.size.1, pixels, palette, None);
+        frame.delay = delay;
+        self.encoder.write_frame(&frame)?;
+        Ok(())
+    }
+}
+
+/// Palette indices of the pixels and the RGB palette. The grid has few colors,
+/// when there are more than 256 of them the nearest one is taken
+fn indexed_pixels(raster: &Raster) -> (Vec<u8>, Vec<u8>) {
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut indices: HashMap<[u8; 3], u8> = HashMap::new();
+    let pixels = raster
+        .pixels()
+        .chunks_exact(4)
+        .map(|pixel| {
+            let color = [pixel[0], pixel[1], pixel[2]];
+            if let Some(index) = indices.get(&color) {
+                return *index;
+            }
+            let index = if palette.len() < 256 {
+                palette.push(color);
+                (palette.len() - 1) as u8
+            } else {
+                nearest_color(&palette, color)
+            };
+            indices.insert(color, index);
+            index
+        })
+        .collect();
+    (pixels, palette.concat())
+}
+
+fn nearest_color(palette: &[[u8; 3]], color: [u8; 3]) -> u8 {
+    let distance = |candidate: &[u8; 3]| -> u32 {
+        (0..3)
+            .map(|channel| (candidate[channel] as i32 - color[channel] as i32).pow(2) as u32)
+            .sum()
+    };
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, candidate)| distance(candidate))
+        .map_or(0, |(index, _)| index as u8)
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::algorithm::{bfs::Bfs, theta_star::ThetaStar};
+    use crate::map::{Neighbourhood, TitleCoords};
+
+    fn grid() -> Grid {
+        Grid::builder(6, 4)
+            .start(TitleCoords { x: 0, y: 0 })
+            .goal(TitleCoords { x: 5, y: 3 })
+            .obstacles((0..3).map(|y| TitleCoords { x: 3, y }))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn rasterized_grid() {
+        let mut grid = grid();
+        let export = ImageExport::new().title_size(10);
+        let raster = export.rasterize(&grid);
+        assert_eq!((60, 40), (raster.width(), raster.height()));
+        // Start, obstacle and the gap between the titles
+        assert_eq!(Some([255, 224, 0, 255]), raster.pixel(2, 2));
+        assert_eq!(Some([145, 120, 93, 255]), raster.pixel(35, 5));
+        assert_eq!(Some(raster::BACKGROUND), raster.pixel(9, 9));
+        assert_eq!(None, raster.pixel(60, 0));
+
+        // The any-angle path is drawn over the titles
+        crate::search(&mut grid, ThetaStar::default()).unwrap();
+        let searched = export.rasterize(&grid);
+        assert_ne!(raster, searched);
+        let mut png = Vec::new();
+        export.write_png(&grid, &mut png).unwrap();
+        assert_eq!(b"\x89PNG", &png[..4]);
+
+        grid.set_neighbourhood(Neighbourhood::Hex);
+        let hex = export.rasterize(&grid);
+        assert_eq!((65, 38), (hex.width(), hex.height()));
+    }
+
+    #[test]
+    fn search_animation() {
+        let mut grid = grid();
+        let mut gif = Vec::new();
+        ImageExport::new()
+            .title_size(4)
+            .write_search_gif(&mut grid, &mut Bfs::default(), &mut gif)
+            .unwrap();
+        assert_eq!(b"GIF89a", &gif[..6]);
+
+        // One frame per step besides the first one
+        let mut decoder = gif::DecodeOptions::new().read_info(gif.as_slice()).unwrap();
+        let mut frames = 0;
+        let mut last_delay = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames += 1;
+            last_delay = frame.delay;
+        }
+        let steps = crate::search(&mut self::grid(), Bfs::default())
+            .unwrap()
+            .statistics
+            .steps;
+        assert_eq!(steps as usize + 1, frames);
+        assert_eq!(FINAL_FRAME_DELAY, last_delay);
+    }
+}
@@ -0,0 +1,230 @@
+//! # Raster
+//! Software rasterizer of the grid, independent of the window.
+//!
+//! The titles, the gaps between them and the straight path segments are drawn like in the
+//! application, but the title size is chosen by the caller and the grid offset is ignored.
+use crate::map::{
+    grid::{Grid, WAYPOINT_COLOR},
+    hex::{self, AxialCoords},
+    Neighbourhood, TitleCoords,
+};
+
+/// Color of the gaps between the titles, the background of the application
+pub const BACKGROUND: [u8; 4] = [128, 128, 128, 255];
+
+/// # Raster
+/// RGBA image with 8 bits per channel, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raster {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    /// # new
+    /// Create the image filled with the color
+    pub fn new(width: u32, height: u32, color: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: color.repeat(width as usize * height as usize),
+        }
+    }
+
+    /// # grid
+    /// Draw the grid with its search overlay, every title takes `title_size` pixels
+    pub fn grid(grid: &Grid, title_size: u32) -> Self {
+        let title_size = title_size.max(1);
+        let size = title_size as f64;
+        // Small titles of big maps are drawn without the gap
+        let gap = if title_size > 4 { 2 } else { 0 };
+        let hex_grid = grid.neighbourhood() == Neighbourhood::Hex;
+        let (width, height) = if hex_grid {
+            (
+                ((grid.columns as f64 + 0.5) * size).ceil() as u32,
+                (((grid.rows.max(1) - 1) as f64 * hex::ROW_HEIGHT + 2.0 * hex::RADIUS) * size)
+                    .ceil() as u32,
+            )
+        } else {
+            (grid.columns * title_size, grid.rows * title_size)
+        };
+
+        let mut raster = Raster::new(width, height, BACKGROUND);
+        for x in 0..grid.columns as usize {
+            for y in 0..grid.rows as usize {
+                let title_coords = TitleCoords { x, y };
+                let color = rgba(grid.title_color(title_coords));
+                if hex_grid {
+                    let centre = title_centre(grid, title_coords, title_size);
+                    let radius = (title_size - gap) as f64 * hex::RADIUS;
+                    let corners: [[f64; 2]; 6] = std::array::from_fn(|corner| {
+                        let angle = (60.0 * corner as f64 - 30.0).to_radians();
+                        [
+                            centre[0] + radius * angle.cos(),
+                            centre[1] + radius * angle.sin(),
+                        ]
+                    });
+                    raster.fill_polygon(&corners, color);
+                    continue;
+                }
+                raster.fill_rect(
+                    x as u32 * title_size,
+                    y as u32 * title_size,
+                    title_size - gap,
+                    title_size - gap,
+                    color,
+                );
+            }
+        }
+
+        for segment in grid.overlay().waypoints().windows(2) {
+            raster.draw_line(
+                title_centre(grid, segment[0], title_size),
+                title_centre(grid, segment[1], title_size),
+                (size / 8.0).max(1.0),
+                rgba(WAYPOINT_COLOR),
+            );
+        }
+        raster
+    }
+
+    /// # width
+    /// Width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// # height
+    /// Height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// # pixels
+    /// RGBA bytes of all pixels, row by row
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// # pixel
+    /// Color of the pixel. None outside of the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let id = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[id..id + 4].try_into().ok()
+    }
+
+    /// # fill_rect
+    /// Fill the rectangle, the part outside of the image is skipped
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 4]) {
+        for pixel_y in y..(y + height).min(self.height) {
+            for pixel_x in x..(x + width).min(self.width) {
+                self.set_pixel(pixel_x, pixel_y, color);
+            }
+        }
+    }
+
+    /// # fill_polygon
+    /// Fill the convex polygon, every pixel whose centre lies inside is painted
+    pub fn fill_polygon(&mut self, corners: &[[f64; 2]], color: [u8; 4]) {
+        if corners.len() < 3 {
+            return;
+        }
+        let edges: Vec<([f64; 2], [f64; 2])> = corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|(from, to)| (*from, *to))
+            .collect();
+        let contains = |point: [f64; 2]| {
+            let sides: Vec<f64> = edges
+                .iter()
+                .map(|(from, to)| {
+                    (to[0] - from[0]) * (point[1] - from[1])
+                        - (to[1] - from[1]) * (point[0] - from[0])
+                })
+                .collect();
+            sides.iter().all(|side| *side >= 0.0) || sides.iter().all(|side| *side <= 0.0)
+        };
+        let [min_x, min_y, max_x, max_y] = self.bounds(corners.iter().copied(), 0.0);
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                if contains([x as f64 + 0.5, y as f64 + 0.5]) {
+                    self.set_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// # draw_line
+    /// Draw the straight line of the width, every pixel whose centre is close enough is painted
+    pub fn draw_line(&mut self, from: [f64; 2], to: [f64; 2], width: f64, color: [u8; 4]) {
+        let half_width = width / 2.0;
+        let direction = [to[0] - from[0], to[1] - from[1]];
+        let length_squared = direction[0] * direction[0] + direction[1] * direction[1];
+        let [min_x, min_y, max_x, max_y] = self.bounds([from, to].into_iter(), half_width);
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let point = [x as f64 + 0.5, y as f64 + 0.5];
+                // Closest point of the segment
+                let along = if length_squared > 0.0 {
+                    (((point[0] - from[0]) * direction[0] + (point[1] - from[1]) * direction[1])
+                        / length_squared)
+                        .clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let closest = [
+                    from[0] + along * direction[0],
+                    from[1] + along * direction[1],
+                ];
+                if (point[0] - closest[0]).hypot(point[1] - closest[1]) <= half_width {
+                    self.set_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let id = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[id..id + 4].copy_from_slice(&color);
+    }
+
+    /// Pixel range [min x, min y, max x, max y) covering the points and the margin, clipped to the image
+    fn bounds(&self, points: impl Iterator<Item = [f64; 2]>, margin: f64) -> [u32; 4] {
+        let (mut min, mut max) = ([f64::MAX; 2], [f64::MIN; 2]);
+        for point in points {
+            for axis in 0..2 {
+                min[axis] = min[axis].min(point[axis] - margin);
+                max[axis] = max[axis].max(point[axis] + margin);
+            }
+        }
+        let clip = |value: f64, limit: u32| value.clamp(0.0, limit as f64) as u32;
+        [
+            clip(min[0].floor(), self.width),
+            clip(min[1].floor(), self.height),
+            clip(max[0].ceil(), self.width),
+            clip(max[1].ceil(), self.height),
+        ]
+    }
+}
+
+/// Centre of the title in the image
+fn title_centre(grid: &Grid, title_coords: TitleCoords, title_size: u32) -> [f64; 2] {
+    let size = title_size as f64;
+    if grid.neighbourhood() == Neighbourhood::Hex {
+        let centre = AxialCoords::from(title_coords).centre();
+        return [(centre[0] + 0.5) * size, (centre[1] + hex::RADIUS) * size];
+    }
+    [
+        (title_coords.x as f64 + 0.5) * size,
+        (title_coords.y as f64 + 0.5) * size,
+    ]
+}
+
+/// 8-bit channels of the color
+fn rgba(color: [f32; 4]) -> [u8; 4] {
+    color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
@@ -0,0 +1,420 @@
+//! # Layout
+//! Open maps for testing the heuristics: random obstacles, caves, dungeons and terrain.
+//!
+//! The layouts are computed as the obstacle and terrain tables indexed by `[x][y]`
+//! and then copied into the grid.
+use crate::map::{grid::Grid, Terrain, TitleCoords};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::VecDeque;
+
+/// Smoothing passes of the cellular automaton
+const CAVE_ITERATIONS: usize = 5;
+/// Placement attempts of the dungeon rooms
+const ROOM_ATTEMPTS: usize = 40;
+/// Distance between the random values of the coarsest noise octave, in titles
+const NOISE_SCALE: f64 = 8.0;
+const NOISE_OCTAVES: usize = 3;
+
+/// # LayoutKind
+/// Kind of the generated map
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum LayoutKind {
+    /// Every title is an obstacle with the probability of the density
+    RandomObstacles,
+    /// Random obstacles smoothed by the cellular automaton. Only the largest cave is kept
+    Cave,
+    /// Rectangular rooms joined by corridors
+    Dungeon,
+    /// Terrain of the noise height map: water in the valleys, grass on the hills.
+    /// The highest titles (the density of them) are obstacles
+    Noise,
+}
+
+impl LayoutKind {
+    pub const ALL: [LayoutKind; 4] = [
+        LayoutKind::RandomObstacles,
+        LayoutKind::Cave,
+        LayoutKind::Dungeon,
+        LayoutKind::Noise,
+    ];
+
+    /// # default_density
+    /// Share of the obstacles used when no density is given
+    pub fn default_density(&self) -> f64 {
+        match self {
+            LayoutKind::RandomObstacles => 0.3,
+            LayoutKind::Cave => 0.45,
+            LayoutKind::Dungeon | LayoutKind::Noise => 0.0,
+        }
+    }
+
+    /// # name
+    /// Layout name
+    pub fn name(&self) -> &'static str {
+        match self {
+            LayoutKind::RandomObstacles => "Obstacles",
+            LayoutKind::Cave => "Cave",
+            LayoutKind::Dungeon => "Dungeon",
+            LayoutKind::Noise => "Noise terrain",
+        }
+    }
+}
+
+/// # LayoutGenerator
+/// Fills the grid with the generated map.
+///
+/// ```
+/// use r_path_finder::generator::layout::{LayoutGenerator, LayoutKind};
+///
+/// let grid = LayoutGenerator::new(LayoutKind::Cave).seed(3).generate(40, 30);
+/// assert_eq!(30, grid.rows);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutGenerator {
+    kind: LayoutKind,
+    seed: u64,
+    density: Option<f64>,
+}
+
+impl LayoutGenerator {
+    /// # new
+    /// Create the generator with the seed 0 and the default density of the layout
+    pub fn new(kind: LayoutKind) -> Self {
+        Self {
+            kind,
+            seed: 0,
+            density: None,
+        }
+    }
+
+    /// # seed
+    /// Set the seed of the random choices
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// # density
+    /// Set the share of the obstacles (0 to 1). The cave smooths the initial obstacles,
+    /// so it ends up with a different share. The dungeon ignores the density
+    pub fn density(mut self, density: f64) -> Self {
+        self.density = Some(density.clamp(0.0, 1.0));
+        self
+    }
+
+    /// # generate
+    /// Create the grid (columns x rows) with the layout
+    pub fn generate(&self, columns: u32, rows: u32) -> Grid {
+        let mut grid = Grid::builder(columns, rows)
+            .build()
+            .expect("Empty grid is always valid");
+        self.fill(&mut grid);
+        grid
+    }
+
+    /// # fill
+    /// Replace the map of the grid by the layout. The size and the movement of the grid are kept
+    pub fn fill(&self, grid: &mut Grid) {
+        let (columns, rows) = (grid.columns as usize, grid.rows as usize);
+        let density = self.density.unwrap_or(self.kind.default_density());
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut terrain = vec![vec![Terrain::Plain; rows]; columns];
+        let obstacles = match self.kind {
+            LayoutKind::RandomObstacles => random_obstacles(columns, rows, density, &mut rng),
+            LayoutKind::Cave => cave(columns, rows, density, &mut rng),
+            LayoutKind::Dungeon => dungeon(columns, rows, &mut rng),
+            LayoutKind::Noise => noise_terrain(columns, rows, density, &mut rng, &mut terrain),
+        };
+
+        grid.reset();
+        for x in 0..columns {
+            for y in 0..rows {
+                let title = TitleCoords { x, y };
+                if obstacles[x][y] {
+                    let _ = grid.set_obstacle(title);
+                } else if terrain[x][y] != Terrain::Plain {
+                    grid.set_terrain(title, terrain[x][y]);
+                }
+            }
+        }
+    }
+}
+
+fn random_obstacles(columns: usize, rows: usize, density: f64, rng: &mut StdRng) -> Vec<Vec<bool>> {
+    (0..columns)
+        .map(|_| (0..rows).map(|_| rng.random_bool(density)).collect())
+        .collect()
+}
+
+/// The title becomes a wall when at least 5 of its 8 neighbours are walls and stays a wall with 4.
+/// The titles outside the grid count as walls
+fn cave(columns: usize, rows: usize, density: f64, rng: &mut StdRng) -> Vec<Vec<bool>> {
+    let mut walls = random_obstacles(columns, rows, density, rng);
+    for _ in 0..CAVE_ITERATIONS {
+        walls = (0..columns)
+            .map(|x| {
+                (0..rows)
+                    .map(|y| {
+                        let neighbour_walls = (-1..=1)
+                            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+                            .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+                            .filter(|&(dx, dy)| {
+                                match (x.checked_add_signed(dx), y.checked_add_signed(dy)) {
+                                    (Some(x), Some(y)) if x < columns && y < rows => walls[x][y],
+                                    _ => true,
+                                }
+                            })
+                            .count();
+                        neighbour_walls >= 5 || (walls[x][y] && neighbour_walls == 4)
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    // The smaller caves are filled, so every free title can be reached
+    let mut region = vec![vec![None; rows]; columns];
+    let mut region_sizes = Vec::new();
+    for x in 0..columns {
+        for y in 0..rows {
+            if !walls[x][y] && region[x][y].is_none() {
+                let size = flood(&walls, &mut region, (x, y), region_sizes.len());
+                region_sizes.push(size);
+            }
+        }
+    }
+    let largest = (0..region_sizes.len()).max_by_key(|id| region_sizes[*id]);
+    for x in 0..columns {
+        for y in 0..rows {
+            if region[x][y].is_some() && region[x][y] != largest {
+                walls[x][y] = true;
+            }
+        }
+    }
+    walls
+}
+
+/// Mark the free titles 4-way connected to the first one by the region id. Returns their number
+fn flood(
+    walls: &[Vec<bool>],
+    region: &mut [Vec<Option<usize>>],
+    first: (usize, usize),
+    id: usize,
+) -> usize {
+    let (columns, rows) = (walls.len(), walls[0].len());
+    let mut size = 0;
+    let mut queue = VecDeque::from([first]);
+    region[first.0][first.1] = Some(id);
+    while let Some((x, y)) = queue.pop_front() {
+        size += 1;
+        let neighbours = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (x, y) in neighbours {
+            if x < columns && y < rows && !walls[x][y] && region[x][y].is_none() {
+                region[x][y] = Some(id);
+                queue.push_back((x, y));
+            }
+        }
+    }
+    size
+}
+
+/// Rooms do not touch each other, every room is joined with the previous one
+/// by the L-shaped corridor
+fn dungeon(columns: usize, rows: usize, rng: &mut StdRng) -> Vec<Vec<bool>> {
+    let mut walls = vec![vec![true; rows]; columns];
+    let max_room_size = (columns.min(rows) / 4).max(3);
+    // Rooms (x, y, width, height) keep one wall title to the border of the grid
+    let mut rooms: Vec<(usize, usize, usize, usize)> = Vec::new();
+
+    for _ in 0..ROOM_ATTEMPTS {
+        let width = rng.random_range(3..=max_room_size);
+        let height = rng.random_range(3..=max_room_size);
+        if width + 2 > columns || height + 2 > rows {
+            continue;
+        }
+        let x = rng.random_range(1..=columns - width - 1);
+        let y = rng.random_range(1..=rows - height - 1);
+        let overlaps = rooms
+            .iter()
+            .any(|&(other_x, other_y, other_width, other_height)| {
+                x <= other_x + other_width
+                    && other_x <= x + width
+                    && y <= other_y + other_height
+                    && other_y <= y + height
+            });
+        if overlaps {
+            continue;
+        }
+
+        for column in walls.iter_mut().skip(x).take(width) {
+            column[y..y + height].fill(false);
+        }
+        let centre = (x + width / 2, y + height / 2);
+        if let Some(&(other_x, other_y, other_width, other_height)) = rooms.last() {
+            let other_centre = (other_x + other_width / 2, other_y + other_height / 2);
+            let corner = if rng.random_bool(0.5) {
+                (centre.0, other_centre.1)
+            } else {
+                (other_centre.0, centre.1)
+            };
+            for (from, to) in [(other_centre, corner), (corner, centre)] {
+                for column in walls
+                    .iter_mut()
+                    .take(from.0.max(to.0) + 1)
+                    .skip(from.0.min(to.0))
+                {
+                    column[from.1.min(to.1)..=from.1.max(to.1)].fill(false);
+                }
+            }
+        }
+        rooms.push((x, y, width, height));
+    }
+    walls
+}
+
+/// Value noise: random values on the lattice smoothly interpolated between, summed over the octaves.
+/// The terrain is chosen by the rank of the height, so every map has the same share of the terrain
+fn noise_terrain(
+    columns: usize,
+    rows: usize,
+    density: f64,
+    rng: &mut StdRng,
+    terrain: &mut [Vec<Terrain>],
+) -> Vec<Vec<bool>> {
+    let mut heights = vec![vec![0.0; rows]; columns];
+    let mut scale = NOISE_SCALE;
+    let mut amplitude = 1.0;
+    for _ in 0..NOISE_OCTAVES {
+        let lattice_columns = (columns as f64 / scale) as usize + 2;
+        let lattice_rows = (rows as f64 / scale) as usize + 2;
+        let lattice: Vec<Vec<f64>> = (0..lattice_columns)
+            .map(|_| (0..lattice_rows).map(|_| rng.random::<f64>()).collect())
+            .collect();
+        for (x, column) in heights.iter_mut().enumerate() {
+            for (y, height) in column.iter_mut().enumerate() {
+                let (lattice_x, lattice_y) = (x as f64 / scale, y as f64 / scale);
+                let (i, j) = (lattice_x as usize, lattice_y as usize);
+                let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+                let (tx, ty) = (smooth(lattice_x.fract()), smooth(lattice_y.fract()));
+                let top = lattice[i][j] + (lattice[i + 1][j] - lattice[i][j]) * tx;
+                let bottom = lattice[i][j + 1] + (lattice[i + 1][j + 1] - lattice[i][j + 1]) * tx;
+                *height += amplitude * (top + (bottom - top) * ty);
+            }
+        }
+        scale /= 2.0;
+        amplitude /= 2.0;
+    }
+
+    let mut titles: Vec<(usize, usize)> = (0..columns)
+        .flat_map(|x| (0..rows).map(move |y| (x, y)))
+        .collect();
+    titles.sort_by(|a, b| heights[a.0][a.1].total_cmp(&heights[b.0][b.1]));
+    let mut obstacles = vec![vec![false; rows]; columns];
+    for (rank, &(x, y)) in titles.iter().enumerate() {
+        let rank = rank as f64 / titles.len() as f64;
+        terrain[x][y] = match rank {
+            rank if rank < 0.15 => Terrain::Water,
+            rank if rank < 0.3 => Terrain::Mud,
+            rank if rank < 0.65 => Terrain::Plain,
+            _ => Terrain::Grass,
+        };
+        obstacles[x][y] = rank >= 1.0 - density;
+    }
+    obstacles
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::{
+        algorithm::{bfs::Bfs, dijkstra::Dijkstra},
+        solve,
+    };
+
+    fn count(grid: &Grid, character: char) -> usize {
+        grid.to_ascii().chars().filter(|c| *c == character).count()
+    }
+
+    /// All free titles are reachable from the first one
+    fn is_connected(grid: &Grid) -> bool {
+        let ascii = grid.to_ascii();
+        let start = ascii
+            .lines()
+            .enumerate()
+            .find_map(|(y, row)| row.find('.').map(|x| TitleCoords { x, y }))
+            .unwrap();
+        let mut visited = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(title) = queue.pop_front() {
+            for neighbour in grid.get_neighbors(title) {
+                if !visited.contains(&neighbour) {
+                    visited.push(neighbour);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        visited.len() == count(grid, '.')
+    }
+
+    #[test]
+    fn random_obstacles_density() {
+        let generator = LayoutGenerator::new(LayoutKind::RandomObstacles).seed(5);
+        let grid = generator.generate(40, 40);
+        let share = count(&grid, '#') as f64 / 1600.0;
+        assert!((0.25..0.35).contains(&share));
+        assert_eq!(grid.to_ascii(), generator.generate(40, 40).to_ascii());
+        assert_ne!(
+            grid.to_ascii(),
+            generator.seed(6).generate(40, 40).to_ascii()
+        );
+
+        let dense = generator.density(0.7).generate(40, 40);
+        assert!(count(&dense, '#') > 1000);
+        assert_eq!(0, count(&generator.density(0.0).generate(40, 40), '#'));
+    }
+
+    #[test]
+    fn caves_and_dungeons() {
+        for kind in [LayoutKind::Cave, LayoutKind::Dungeon] {
+            for seed in 0..5 {
+                let grid = LayoutGenerator::new(kind).seed(seed).generate(40, 30);
+                assert!(count(&grid, '.') > 100, "{} {}", kind.name(), seed);
+                assert!(is_connected(&grid), "{} {}", kind.name(), seed);
+            }
+        }
+        let dungeon = LayoutGenerator::new(LayoutKind::Dungeon).generate(40, 30);
+        for x in 0..40 {
+            assert!(dungeon.is_obstacle(TitleCoords { x, y: 0 }));
+            assert!(dungeon.is_obstacle(TitleCoords { x, y: 29 }));
+        }
+        // Too small for a room
+        let tiny = LayoutGenerator::new(LayoutKind::Dungeon).generate(4, 4);
+        assert_eq!(16, count(&tiny, '#'));
+    }
+
+    #[test]
+    fn noise_terrain_costs() {
+        let generator = LayoutGenerator::new(LayoutKind::Noise).seed(9);
+        let grid = generator.generate(40, 40);
+        assert_eq!(0, count(&grid, '#'));
+        assert_eq!(240, count(&grid, 'w'));
+        assert_eq!(240, count(&grid, 'm'));
+        assert_eq!(560, count(&grid, 'g'));
+
+        let peaks = generator.density(0.1).generate(40, 40);
+        assert_eq!(160, count(&peaks, '#'));
+        assert_eq!(400, count(&peaks, 'g'));
+
+        // The cheapest path goes around the water, so it is longer than the shortest one
+        let (start, goal) = (TitleCoords { x: 0, y: 0 }, TitleCoords { x: 39, y: 39 });
+        let dijkstra = solve(&grid, start, goal, Dijkstra::default()).unwrap();
+        let bfs = solve(&grid, start, goal, Bfs::default()).unwrap();
+        assert!(dijkstra.cost < bfs.cost);
+        assert!(dijkstra.path.len() >= bfs.path.len());
+    }
+}
@@ -0,0 +1,410 @@
+//! # Maze
+//! Perfect mazes - every two free titles are joined by exactly one path of 4-way moves.
+//!
+//! The maze cells are the titles with odd coordinates, the titles between them are the walls.
+//! The generator starts with walls everywhere and carves the cells and the walls between
+//! the joined cells, so the carving can be animated title by title with [`MazeAnimation`].
+use crate::map::{grid::Grid, TitleCoords};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::collections::VecDeque;
+
+/// # MazeKind
+/// Algorithm which picks the joined cells
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MazeKind {
+    /// Random depth-first walk - long corridors with few dead ends
+    RecursiveBacktracker,
+    /// Randomized Prim - grows from the random frontier wall, many short dead ends
+    Prim,
+    /// Randomized Kruskal - joins the random walls of separated areas
+    Kruskal,
+    /// Loop-erased random walks - uniformly random maze
+    Wilson,
+}
+
+impl MazeKind {
+    pub const ALL: [MazeKind; 4] = [
+        MazeKind::RecursiveBacktracker,
+        MazeKind::Prim,
+        MazeKind::Kruskal,
+        MazeKind::Wilson,
+    ];
+
+    /// # name
+    /// Maze algorithm name
+    pub fn name(&self) -> &'static str {
+        match self {
+            MazeKind::RecursiveBacktracker => "Backtracker",
+            MazeKind::Prim => "Prim",
+            MazeKind::Kruskal => "Kruskal",
+            MazeKind::Wilson => "Wilson",
+        }
+    }
+}
+
+/// # MazeGenerator
+/// Fills the grid with the perfect maze.
+///
+/// ```
+/// use r_path_finder::generator::maze::{MazeGenerator, MazeKind};
+///
+/// let grid = MazeGenerator::new(MazeKind::Wilson).seed(7).generate(21, 15);
+/// assert_eq!(21, grid.columns);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MazeGenerator {
+    kind: MazeKind,
+    seed: u64,
+}
+
+impl MazeGenerator {
+    /// # new
+    /// Create the generator with the seed 0
+    pub fn new(kind: MazeKind) -> Self {
+        Self { kind, seed: 0 }
+    }
+
+    /// # seed
+    /// Set the seed of the random choices
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// # generate
+    /// Create the grid (columns x rows) with the maze
+    pub fn generate(&self, columns: u32, rows: u32) -> Grid {
+        let mut grid = Grid::builder(columns, rows)
+            .build()
+            .expect("Empty grid is always valid");
+        self.carve(&mut grid);
+        grid
+    }
+
+    /// # carve
+    /// Replace the map of the grid by the maze. The size and the movement of the grid are kept
+    pub fn carve(&self, grid: &mut Grid) {
+        self.animate(grid).finish(grid);
+    }
+
+    /// # animate
+    /// Fill the grid with walls and return the animation which carves the maze
+    pub fn animate(&self, grid: &mut Grid) -> MazeAnimation {
+        grid.reset();
+        for x in 0..grid.columns as usize {
+            for y in 0..grid.rows as usize {
+                let _ = grid.set_obstacle(TitleCoords { x, y });
+            }
+        }
+        let cells = Cells {
+            columns: grid.columns.saturating_sub(1) as usize / 2,
+            rows: grid.rows.saturating_sub(1) as usize / 2,
+        };
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let titles = if cells.count() == 0 {
+            Vec::new()
+        } else {
+            match self.kind {
+                MazeKind::RecursiveBacktracker => recursive_backtracker(&cells, &mut rng),
+                MazeKind::Prim => prim(&cells, &mut rng),
+                MazeKind::Kruskal => kruskal(&cells, &mut rng),
+                MazeKind::Wilson => wilson(&cells, &mut rng),
+            }
+        };
+        MazeAnimation {
+            titles: titles.into(),
+        }
+    }
+}
+
+/// # MazeAnimation
+/// Titles of the maze which are still to be carved, in the order of the algorithm
+pub struct MazeAnimation {
+    titles: VecDeque<TitleCoords>,
+}
+
+impl MazeAnimation {
+    /// # step
+    /// Carve the next titles, at most `titles` of them
+    pub fn step(&mut self, grid: &mut Grid, titles: usize) {
+        for _ in 0..titles {
+            // The titles which are already free can hold the start or the goal
+            let Some(title) = self.titles.pop_front() else {
+                return;
+            };
+            if grid.is_obstacle(title) {
+                let _ = grid.clear_title(title);
+            }
+        }
+    }
+
+    /// # finish
+    /// Carve all remaining titles
+    pub fn finish(mut self, grid: &mut Grid) {
+        let remaining = self.titles.len();
+        self.step(grid, remaining);
+    }
+
+    /// # is_finished
+    /// All titles are carved
+    pub fn is_finished(&self) -> bool {
+        self.titles.is_empty()
+    }
+}
+
+/// Maze cells (columns x rows), the cell (i, j) is the title (2i + 1, 2j + 1)
+struct Cells {
+    columns: usize,
+    rows: usize,
+}
+
+impl Cells {
+    fn count(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    fn title(&self, cell: usize) -> TitleCoords {
+        TitleCoords {
+            x: 2 * (cell % self.columns) + 1,
+            y: 2 * (cell / self.columns) + 1,
+        }
+    }
+
+    /// Title between the neighbouring cells
+    fn wall(&self, from: usize, to: usize) -> TitleCoords {
+        let (from, to) = (self.title(from), self.title(to));
+        TitleCoords {
+            x: (from.x + to.x) / 2,
+            y: (from.y + to.y) / 2,
+        }
+    }
+
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        let (i, j) = (cell % self.columns, cell / self.columns);
+        let mut neighbours = Vec::with_capacity(4);
+        if j > 0 {
+            neighbours.push(cell - self.columns);
+        }
+        if j + 1 < self.rows {
+            neighbours.push(cell + self.columns);
+        }
+        if i > 0 {
+            neighbours.push(cell - 1);
+        }
+        if i + 1 < self.columns {
+            neighbours.push(cell + 1);
+        }
+        neighbours
+    }
+}
+
+fn recursive_backtracker(cells: &Cells, rng: &mut StdRng) -> Vec<TitleCoords> {
+    let mut visited = vec![false; cells.count()];
+    let start = rng.random_range(0..cells.count());
+    visited[start] = true;
+    let mut titles = vec![cells.title(start)];
+    let mut stack = vec![start];
+
+    while let Some(&cell) = stack.last() {
+        let unvisited: Vec<usize> = cells
+            .neighbours(cell)
+            .into_iter()
+            .filter(|neighbour| !visited[*neighbour])
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = unvisited[rng.random_range(0..unvisited.len())];
+        visited[next] = true;
+        titles.extend([cells.wall(cell, next), cells.title(next)]);
+        stack.push(next);
+    }
+    titles
+}
+
+fn prim(cells: &Cells, rng: &mut StdRng) -> Vec<TitleCoords> {
+    let mut visited = vec![false; cells.count()];
+    let start = rng.random_range(0..cells.count());
+    visited[start] = true;
+    let mut titles = vec![cells.title(start)];
+    let mut frontier: Vec<(usize, usize)> = cells
+        .neighbours(start)
+        .into_iter()
+        .map(|neighbour| (start, neighbour))
+        .collect();
+
+    while !frontier.is_empty() {
+        let (cell, next) = frontier.swap_remove(rng.random_range(0..frontier.len()));
+        if visited[next] {
+            continue;
+        }
+        visited[next] = true;
+        titles.extend([cells.wall(cell, next), cells.title(next)]);
+        frontier.extend(
+            cells
+                .neighbours(next)
+                .into_iter()
+                .filter(|neighbour| !visited[*neighbour])
+                .map(|neighbour| (next, neighbour)),
+        );
+    }
+    titles
+}
+
+fn kruskal(cells: &Cells, rng: &mut StdRng) -> Vec<TitleCoords> {
+    let mut walls: Vec<(usize, usize)> = (0..cells.count())
+        .flat_map(|cell| {
+            cells
+                .neighbours(cell)
+                .into_iter()
+                .filter(move |neighbour| *neighbour > cell)
+                .map(move |neighbour| (cell, neighbour))
+        })
+        .collect();
+    walls.shuffle(rng);
+
+    // Union-find of the joined areas
+    let mut parents: Vec<usize> = (0..cells.count()).collect();
+    fn root(parents: &mut [usize], mut cell: usize) -> usize {
+        while parents[cell] != cell {
+            parents[cell] = parents[parents[cell]];
+            cell = parents[cell];
+        }
+        cell
+    }
+
+    let mut titles = Vec::new();
+    for (cell, next) in walls {
+        let (cell_root, next_root) = (root(&mut parents, cell), root(&mut parents, next));
+        if cell_root != next_root {
+            parents[cell_root] = next_root;
+            titles.extend([cells.title(cell), cells.wall(cell, next), cells.title(next)]);
+        }
+    }
+    if titles.is_empty() {
+        // The only cell has no walls to join
+        titles.push(cells.title(0));
+    }
+    titles
+}
+
+fn wilson(cells: &Cells, rng: &mut StdRng) -> Vec<TitleCoords> {
+    let mut in_maze = vec![false; cells.count()];
+    let first = rng.random_range(0..cells.count());
+    in_maze[first] = true;
+    let mut titles = vec![cells.title(first)];
+
+    let mut order: Vec<usize> = (0..cells.count()).collect();
+    order.shuffle(rng);
+    // Position of the cell in the walk, the walk is cut back when it crosses itself
+    let mut walk_position: Vec<Option<usize>> = vec![None; cells.count()];
+
+    for cell in order {
+        if in_maze[cell] {
+            continue;
+        }
+        let mut walk = vec![cell];
+        walk_position[cell] = Some(0);
+        let mut current = cell;
+        while !in_maze[current] {
+            let neighbours = cells.neighbours(current);
+            let next = neighbours[rng.random_range(0..neighbours.len())];
+            match walk_position[next] {
+                Some(position) => {
+                    for erased in walk.drain(position + 1..) {
+                        walk_position[erased] = None;
+                    }
+                }
+                None => {
+                    walk_position[next] = Some(walk.len());
+                    walk.push(next);
+                }
+            }
+            current = next;
+        }
+
+        for step in walk.windows(2) {
+            in_maze[step[0]] = true;
+            titles.extend([cells.title(step[0]), cells.wall(step[0], step[1])]);
+        }
+        for cell in walk {
+            walk_position[cell] = None;
+        }
+    }
+    titles
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    /// Free titles reachable from the first cell
+    fn reachable(grid: &Grid) -> usize {
+        let start = TitleCoords { x: 1, y: 1 };
+        let mut visited = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(title) = queue.pop_front() {
+            for neighbour in grid.get_neighbors(title) {
+                if !visited.contains(&neighbour) {
+                    visited.push(neighbour);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        visited.len()
+    }
+
+    #[test]
+    fn perfect_mazes() {
+        let cells = 10 * 7;
+        for kind in MazeKind::ALL {
+            let grid = MazeGenerator::new(kind).seed(7).generate(21, 15);
+            let free_titles = grid
+                .to_ascii()
+                .chars()
+                .filter(|character| *character == '.')
+                .count();
+            // Cells joined by a spanning tree: one wall less than the cells
+            assert_eq!(2 * cells - 1, free_titles, "{}", kind.name());
+            assert_eq!(free_titles, reachable(&grid), "{}", kind.name());
+
+            let same_seed = MazeGenerator::new(kind).seed(7).generate(21, 15);
+            let other_seed = MazeGenerator::new(kind).seed(8).generate(21, 15);
+            assert_eq!(grid.to_ascii(), same_seed.to_ascii());
+            assert_ne!(grid.to_ascii(), other_seed.to_ascii());
+        }
+
+        let tiny = MazeGenerator::new(MazeKind::Kruskal).generate(3, 3);
+        assert!(!tiny.is_obstacle(TitleCoords { x: 1, y: 1 }));
+        assert!(tiny.is_obstacle(TitleCoords { x: 0, y: 1 }));
+    }
+
+    #[test]
+    fn maze_animation() {
+        let generator = MazeGenerator::new(MazeKind::RecursiveBacktracker).seed(3);
+        let mut grid = Grid::builder(9, 9).build().unwrap();
+        let mut animation = generator.animate(&mut grid);
+        assert!(grid.is_obstacle(TitleCoords { x: 1, y: 1 }));
+
+        animation.step(&mut grid, 1);
+        let first_cell = grid.to_ascii().chars().filter(|c| *c == '.').count();
+        assert_eq!(1, first_cell);
+
+        // The start placed on the carved title is kept
+        let start = grid
+            .to_ascii()
+            .lines()
+            .enumerate()
+            .find_map(|(y, row)| row.find('.').map(|x| TitleCoords { x, y }))
+            .unwrap();
+        grid.set_start(start).unwrap();
+        while !animation.is_finished() {
+            animation.step(&mut grid, 2);
+        }
+        assert_eq!(Some(start), grid.start_title);
+
+        grid.clear_title(start).unwrap();
+        assert_eq!(generator.generate(9, 9).to_ascii(), grid.to_ascii());
+    }
+}
@@ -0,0 +1,6 @@
+//! # Generator
+//! Procedural generation of the grid layouts.
+//!
+//! Every generator takes a seed, so the same seed always gives the same layout.
+pub mod layout;
+pub mod maze;
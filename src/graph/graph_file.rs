@@ -0,0 +1,305 @@
+//! # Graph files
+//! Import of the weighted graph.
+//!
+//! Two text formats are supported:
+//! - [DIMACS](http://www.diag.uniroma1.it/challenge9/format.shtml) shortest path format.
+//!   The `.gr` file holds the problem line `p sp <nodes> <arcs>` and the directed arcs
+//!   `a <from> <to> <cost>`. Nodes are numbered from 1 and labelled by their number.
+//!   The optional `.co` file holds the node positions `v <node> <x> <y>`. Lines starting with `c` are comments
+//! - CSV edge list. Every line is either a node `node,<label>,<x>,<y>` or an undirected edge
+//!   `edge,<from>,<to>,<cost>`. Nodes of the edges need not be listed. Lines starting with `#` are comments
+use super::{GraphError, WeightedGraph};
+use std::{fs, io, path::Path};
+
+/// # GraphFormat
+/// Supported graph file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dimacs,
+    Csv,
+}
+
+impl GraphFormat {
+    /// # from_path
+    /// `.gr` files are DIMACS graphs, everything else is a CSV edge list
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("gr") => GraphFormat::Dimacs,
+            _ => GraphFormat::Csv,
+        }
+    }
+}
+
+/// # GraphFileError
+/// Errors reported when the graph file is loaded
+#[derive(Debug)]
+pub enum GraphFileError {
+    Io(io::Error),
+    /// The DIMACS problem line is missing or malformed
+    InvalidHeader(String),
+    /// Malformed line (counted from 1)
+    InvalidLine(usize),
+    /// The node or the cost in the line (counted from 1) is not valid
+    InvalidGraph {
+        line: usize,
+        error: GraphError,
+    },
+    /// Some nodes have no position, so the graph can not be drawn
+    MissingPositions,
+}
+
+impl From<io::Error> for GraphFileError {
+    fn from(error: io::Error) -> Self {
+        GraphFileError::Io(error)
+    }
+}
+
+impl WeightedGraph {
+    /// # load
+    /// Load the graph from the file. The format is chosen by the file extension.
+    ///
+    /// The positions of the DIMACS graph are loaded from the `.co` file next to the `.gr` file, if it exists
+    pub fn load(path: impl AsRef<Path>) -> Result<WeightedGraph, GraphFileError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        match GraphFormat::from_path(path) {
+            GraphFormat::Dimacs => {
+                let mut graph = WeightedGraph::from_dimacs(&content)?;
+                let coordinates_path = path.with_extension("co");
+                if coordinates_path.exists() {
+                    graph.load_coordinates(coordinates_path)?;
+                }
+                Ok(graph)
+            }
+            GraphFormat::Csv => WeightedGraph::from_csv(&content),
+        }
+    }
+
+    /// # load_coordinates
+    /// Load the node positions from the DIMACS `.co` file
+    pub fn load_coordinates(&mut self, path: impl AsRef<Path>) -> Result<(), GraphFileError> {
+        self.add_dimacs_coordinates(&fs::read_to_string(path)?)
+    }
+
+    /// # from_dimacs
+    /// Parse the graph from DIMACS `.gr` content
+    pub fn from_dimacs(content: &str) -> Result<WeightedGraph, GraphFileError> {
+        let mut graph = WeightedGraph::new();
+        let mut has_problem_line = false;
+
+        for (line_id, line) in dimacs_lines(content) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["p", "sp", nodes, _arcs] if !has_problem_line => {
+                    let nodes: usize = nodes
+                        .parse()
+                        .map_err(|_| GraphFileError::InvalidHeader(line.to_string()))?;
+                    for node in 1..=nodes {
+                        graph.add_node(&node.to_string(), None);
+                    }
+                    has_problem_line = true;
+                }
+                ["p", ..] => return Err(GraphFileError::InvalidHeader(line.to_string())),
+                ["a", from, to, cost] if has_problem_line => {
+                    let from = parse_dimacs_node(&graph, from, line_id)?;
+                    let to = parse_dimacs_node(&graph, to, line_id)?;
+                    let cost = parse_number(cost, line_id)?;
+                    graph.add_edge(from, to, cost).map_err(|error| {
+                        GraphFileError::InvalidGraph {
+                            line: line_id,
+                            error,
+                        }
+                    })?;
+                }
+                ["a", ..] if !has_problem_line => {
+                    return Err(GraphFileError::InvalidHeader(
+                        "missing 'p sp' line".to_string(),
+                    ))
+                }
+                _ => return Err(GraphFileError::InvalidLine(line_id)),
+            }
+        }
+
+        if !has_problem_line {
+            return Err(GraphFileError::InvalidHeader(
+                "missing 'p sp' line".to_string(),
+            ));
+        }
+        Ok(graph)
+    }
+
+    /// # add_dimacs_coordinates
+    /// Parse the node positions from DIMACS `.co` content
+    pub fn add_dimacs_coordinates(&mut self, content: &str) -> Result<(), GraphFileError> {
+        for (line_id, line) in dimacs_lines(content) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["p", ..] => {}
+                ["v", node, x, y] => {
+                    let node = parse_dimacs_node(self, node, line_id)?;
+                    let position = [parse_number(x, line_id)?, parse_number(y, line_id)?];
+                    self.set_position(node, position).map_err(|error| {
+                        GraphFileError::InvalidGraph {
+                            line: line_id,
+                            error,
+                        }
+                    })?;
+                }
+                _ => return Err(GraphFileError::InvalidLine(line_id)),
+            }
+        }
+        Ok(())
+    }
+
+    /// # from_csv
+    /// Parse the graph from the CSV edge list
+    pub fn from_csv(content: &str) -> Result<WeightedGraph, GraphFileError> {
+        let mut graph = WeightedGraph::new();
+
+        for (line_id, line) in content.lines().enumerate() {
+            let line_id = line_id + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            match fields.as_slice() {
+                ["node", label, x, y] if !label.is_empty() => {
+                    let position = [parse_number(x, line_id)?, parse_number(y, line_id)?];
+                    graph.add_node(label, Some(position));
+                }
+                ["edge", from, to, cost] if !from.is_empty() && !to.is_empty() => {
+                    let cost = parse_number(cost, line_id)?;
+                    let from = graph.add_node(from, None);
+                    let to = graph.add_node(to, None);
+                    graph.add_undirected_edge(from, to, cost).map_err(|error| {
+                        GraphFileError::InvalidGraph {
+                            line: line_id,
+                            error,
+                        }
+                    })?;
+                }
+                _ => return Err(GraphFileError::InvalidLine(line_id)),
+            }
+        }
+        Ok(graph)
+    }
+}
+
+/// Lines which are not empty nor comments, with their numbers counted from 1
+fn dimacs_lines(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(line_id, line)| (line_id + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('c'))
+}
+
+fn parse_dimacs_node(
+    graph: &WeightedGraph,
+    node: &str,
+    line_id: usize,
+) -> Result<usize, GraphFileError> {
+    graph.node(node).ok_or(GraphFileError::InvalidGraph {
+        line: line_id,
+        error: GraphError::UnknownNode,
+    })
+}
+
+fn parse_number(value: &str, line_id: usize) -> Result<f64, GraphFileError> {
+    value
+        .parse()
+        .map_err(|_| GraphFileError::InvalidLine(line_id))
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::algorithm::search_space::SearchSpace;
+
+    const GRAPH: &str = "c Tiny road network\n\
+        p sp 4 5\n\
+        a 1 2 31\n\
+        a 2 1 31\n\
+        a 1 3 10\n\
+        a 3 4 10\n\
+        a 4 2 10\n";
+
+    const COORDINATES: &str = "c Positions\n\
+        p aux sp co 4\n\
+        v 1 0 0\n\
+        v 2 10 0\n\
+        v 3 0 10\n\
+        v 4 10 10\n";
+
+    #[test]
+    fn load_dimacs() {
+        let mut graph = WeightedGraph::from_dimacs(GRAPH).unwrap();
+        assert_eq!(4, graph.node_count());
+        assert_eq!(5, graph.edge_count());
+        assert_eq!(0.0, graph.heuristic_scale());
+
+        graph.add_dimacs_coordinates(COORDINATES).unwrap();
+        assert!(graph.has_positions());
+        assert_eq!(Some([10.0, 10.0]), graph.position(graph.node("4").unwrap()));
+        assert_eq!(1.0, graph.heuristic_scale());
+
+        let (start, goal) = (graph.node("1").unwrap(), graph.node("2").unwrap());
+        let outcome = graph.shortest_path(start, goal).unwrap();
+        assert_eq!(30.0, outcome.cost());
+        assert_eq!(4, outcome.path.len());
+        assert_eq!(31.0, graph.cost(graph.node("2").unwrap(), start));
+
+        assert!(matches!(
+            WeightedGraph::from_dimacs("a 1 2 3\n"),
+            Err(GraphFileError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            WeightedGraph::from_dimacs("p sp 2 1\na 1 3 1\n"),
+            Err(GraphFileError::InvalidGraph {
+                line: 2,
+                error: GraphError::UnknownNode
+            })
+        ));
+        assert!(matches!(
+            WeightedGraph::from_dimacs("p sp 2 1\na 1 2 x\n"),
+            Err(GraphFileError::InvalidLine(2))
+        ));
+        assert!(matches!(
+            graph.add_dimacs_coordinates("v 9 0 0\n"),
+            Err(GraphFileError::InvalidGraph { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn load_csv() {
+        let content = "# Cities\n\
+            node,Oslo,10.7,59.9\n\
+            node,Bergen,5.3,60.4\n\
+            edge,Oslo,Bergen,463\n\
+            edge,Bergen,Stavanger,209\n";
+        let mut graph = WeightedGraph::from_csv(content).unwrap();
+        assert_eq!(3, graph.node_count());
+        assert_eq!(4, graph.edge_count());
+        assert_eq!(None, graph.position(graph.node("Stavanger").unwrap()));
+
+        let (stavanger, oslo) = (
+            graph.node("Stavanger").unwrap(),
+            graph.node("Oslo").unwrap(),
+        );
+        let outcome = graph.shortest_path(stavanger, oslo).unwrap();
+        assert_eq!(672.0, outcome.cost());
+
+        assert!(matches!(
+            WeightedGraph::from_csv("edge,a,b,-1\n"),
+            Err(GraphFileError::InvalidGraph {
+                line: 1,
+                error: GraphError::InvalidCost
+            })
+        ));
+        assert!(matches!(
+            WeightedGraph::from_csv("node,a,1\n"),
+            Err(GraphFileError::InvalidLine(1))
+        ));
+    }
+}
@@ -0,0 +1,536 @@
+//! # Graph
+//! Weighted graph of nodes connected by edges, e.g. a road network.
+//!
+//! The graph is a [`SearchSpace`], so Bfs, Dijkstra, Greedy Bfs and A* search it the same way
+//! as the grid. It is loaded from DIMACS `.gr`/`.co` files or from a CSV edge list, see [`graph_file`].
+//!
+//! Node positions use the map convention: x grows to the east (right), y to the north (up).
+pub mod graph_file;
+
+use crate::{
+    algorithm::{
+        a_star::AStar,
+        search_space::SearchSpace,
+        solver::{search, SearchOutcome},
+        Algorithm, AlgorithmError,
+    },
+    map::SearchState,
+};
+#[cfg(feature = "gui")]
+use piston_window::{ellipse, line_from_to, types::Color, Context, G2d};
+use std::{cell::OnceCell, collections::HashMap};
+
+/// # NodeId
+/// Index of the node in the graph
+pub type NodeId = usize;
+
+/// Radius of the node drawn on the screen, also the distance to pick the node with the mouse
+const NODE_RADIUS: f64 = 4.0;
+
+/// # GraphError
+/// Errors reported when the graph is edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphError {
+    /// The node id is not in the graph
+    UnknownNode,
+    /// The edge cost is negative or not a number
+    InvalidCost,
+}
+
+/// # GraphView
+/// Scaling of the node positions into the screen area
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GraphView {
+    /// The lowest x and the highest y of the node positions
+    origin: [f64; 2],
+    scale: f64,
+    /// Top left corner of the screen area
+    offset: [f64; 2],
+}
+
+impl Default for GraphView {
+    fn default() -> Self {
+        Self {
+            origin: [0.0, 0.0],
+            scale: 1.0,
+            offset: [0.0, 0.0],
+        }
+    }
+}
+
+/// # WeightedGraph
+/// Directed graph with non-negative edge costs and optional node positions
+#[derive(Debug, Clone, Default)]
+pub struct WeightedGraph {
+    labels: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    positions: Vec<Option<[f64; 2]>>,
+    edges: Vec<Vec<(NodeId, f64)>>,
+    edge_count: usize,
+    /// The lowest ratio of the edge cost to its straight line length, see `heuristic_scale`
+    heuristic_scale: OnceCell<f64>,
+    states: Vec<SearchState>,
+    /// Nodes of the found path from the goal back to the start
+    path: Vec<NodeId>,
+    view: GraphView,
+    pub start: Option<NodeId>,
+    pub goal: Option<NodeId>,
+}
+
+impl WeightedGraph {
+    /// # new
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # add_node
+    /// Add the node with the label, e.g. its id in the file. The node which already has
+    /// the label is reused and only its position is updated
+    pub fn add_node(&mut self, label: &str, position: Option<[f64; 2]>) -> NodeId {
+        let node = match self.ids.get(label) {
+            Some(node) => *node,
+            None => {
+                let node = self.labels.len();
+                self.labels.push(label.to_string());
+                self.ids.insert(label.to_string(), node);
+                self.positions.push(None);
+                self.edges.push(Vec::new());
+                self.states.push(SearchState::Unvisited);
+                node
+            }
+        };
+        if position.is_some() {
+            self.positions[node] = position;
+            self.heuristic_scale = OnceCell::new();
+        }
+        node
+    }
+
+    /// # set_position
+    /// Set the position of the node
+    pub fn set_position(&mut self, node: NodeId, position: [f64; 2]) -> Result<(), GraphError> {
+        let node_position = self
+            .positions
+            .get_mut(node)
+            .ok_or(GraphError::UnknownNode)?;
+        *node_position = Some(position);
+        self.heuristic_scale = OnceCell::new();
+        Ok(())
+    }
+
+    /// # add_edge
+    /// Add the directed edge with the movement cost
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, cost: f64) -> Result<(), GraphError> {
+        if from >= self.node_count() || to >= self.node_count() {
+            return Err(GraphError::UnknownNode);
+        }
+        if cost.is_nan() || cost < 0.0 {
+            return Err(GraphError::InvalidCost);
+        }
+        self.edges[from].push((to, cost));
+        self.edge_count += 1;
+        self.heuristic_scale = OnceCell::new();
+        Ok(())
+    }
+
+    /// # add_undirected_edge
+    /// Add the edges in both directions with the same movement cost
+    pub fn add_undirected_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        cost: f64,
+    ) -> Result<(), GraphError> {
+        self.add_edge(from, to, cost)?;
+        self.add_edge(to, from, cost)
+    }
+
+    /// # node
+    /// Find the node by its label
+    pub fn node(&self, label: &str) -> Option<NodeId> {
+        self.ids.get(label).copied()
+    }
+
+    /// # label
+    /// Label of the node. None for unknown nodes
+    pub fn label(&self, node: NodeId) -> Option<&str> {
+        self.labels.get(node).map(String::as_str)
+    }
+
+    /// # position
+    /// Position of the node. None for unknown nodes or nodes without position
+    pub fn position(&self, node: NodeId) -> Option<[f64; 2]> {
+        self.positions.get(node).copied().flatten()
+    }
+
+    /// # has_positions
+    /// Check if every node has its position, e.g. to draw the graph
+    pub fn has_positions(&self) -> bool {
+        self.positions.iter().all(Option::is_some)
+    }
+
+    /// # edges
+    /// Outgoing edges of the node with their costs
+    pub fn edges(&self, node: NodeId) -> &[(NodeId, f64)] {
+        self.edges.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// # heuristic_scale
+    /// The lowest ratio of the edge cost to the straight line distance between its nodes.
+    ///
+    /// No edge is cheaper than the scaled distance, so the scaled distance to the goal
+    /// never overestimates and A* stays optimal. 0 if some node has no position
+    pub fn heuristic_scale(&self) -> f64 {
+        *self.heuristic_scale.get_or_init(|| {
+            if !self.has_positions() {
+                return 0.0;
+            }
+            let scale = (0..self.node_count())
+                .flat_map(|from| self.edges[from].iter().map(move |edge| (from, *edge)))
+                .filter_map(|(from, (to, cost))| {
+                    let distance = self.straight_distance(from, to)?;
+                    (distance > 0.0).then_some(cost / distance)
+                })
+                .fold(f64::INFINITY, f64::min);
+            if scale.is_finite() {
+                scale
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// # state
+    /// Search state of the node
+    pub fn state(&self, node: NodeId) -> SearchState {
+        self.states
+            .get(node)
+            .copied()
+            .unwrap_or(SearchState::Unvisited)
+    }
+
+    /// # shortest_path
+    /// Find the cheapest path between the nodes with A*
+    pub fn shortest_path(
+        &mut self,
+        start: NodeId,
+        goal: NodeId,
+    ) -> Result<SearchOutcome<NodeId>, AlgorithmError> {
+        self.query(start, goal, AStar::new())
+    }
+
+    /// # query
+    /// Search the path between the nodes with the algorithm without a window.
+    ///
+    /// The start and the goal of the graph are replaced. The algorithm should be a fresh instance
+    pub fn query(
+        &mut self,
+        start: NodeId,
+        goal: NodeId,
+        algorithm: impl Algorithm<WeightedGraph>,
+    ) -> Result<SearchOutcome<NodeId>, AlgorithmError> {
+        if start >= self.node_count() || goal >= self.node_count() {
+            return Err(AlgorithmError::InvalidInputData);
+        }
+        self.start = Some(start);
+        self.goal = Some(goal);
+        self.clear_marks();
+        search(self, algorithm)
+    }
+
+    /// # fit_into
+    /// Scale the node positions, so the whole graph fits into the screen area (x, y, width, height)
+    pub fn fit_into(&mut self, area: [f64; 4]) {
+        let positions: Vec<[f64; 2]> = self.positions.iter().flatten().copied().collect();
+        if positions.is_empty() {
+            return;
+        }
+        let min_x = positions.iter().map(|p| p[0]).fold(f64::INFINITY, f64::min);
+        let max_x = positions
+            .iter()
+            .map(|p| p[0])
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_y = positions.iter().map(|p| p[1]).fold(f64::INFINITY, f64::min);
+        let max_y = positions
+            .iter()
+            .map(|p| p[1])
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        // Keep the nodes on the border fully visible
+        let width = (area[2] - 2.0 * NODE_RADIUS).max(1.0);
+        let height = (area[3] - 2.0 * NODE_RADIUS).max(1.0);
+        let scale = match (max_x - min_x, max_y - min_y) {
+            (0.0, 0.0) => 1.0,
+            (dx, dy) => (width / dx).min(height / dy),
+        };
+        self.view = GraphView {
+            origin: [min_x, max_y],
+            scale,
+            offset: [area[0] + NODE_RADIUS, area[1] + NODE_RADIUS],
+        };
+    }
+
+    /// # screen_position
+    /// Position of the node on the screen. None for nodes without position
+    pub fn screen_position(&self, node: NodeId) -> Option<[f64; 2]> {
+        let [x, y] = self.position(node)?;
+        Some([
+            self.view.offset[0] + (x - self.view.origin[0]) * self.view.scale,
+            self.view.offset[1] + (self.view.origin[1] - y) * self.view.scale,
+        ])
+    }
+
+    /// # node_at
+    /// The nearest node to the mouse screen position, if it is close enough to be picked
+    pub fn node_at(&self, mouse_position: &[f64; 2]) -> Option<NodeId> {
+        (0..self.node_count())
+            .filter_map(|node| {
+                let [x, y] = self.screen_position(node)?;
+                let distance = (x - mouse_position[0]).hypot(y - mouse_position[1]);
+                Some((node, distance))
+            })
+            .filter(|(_, distance)| *distance <= 2.0 * NODE_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(node, _)| node)
+    }
+
+    /// # reset
+    /// Remove the start, the goal and the search marks. The nodes and edges are kept
+    pub fn reset(&mut self) {
+        self.start = None;
+        self.goal = None;
+        self.clear_marks();
+    }
+
+    fn straight_distance(&self, from: NodeId, to: NodeId) -> Option<f64> {
+        let (from, to) = (self.position(from)?, self.position(to)?);
+        Some((to[0] - from[0]).hypot(to[1] - from[1]))
+    }
+
+    fn mark(&mut self, node: NodeId, state: SearchState) {
+        if let Some(node_state) = self.states.get_mut(node) {
+            *node_state = state;
+        }
+    }
+}
+
+impl SearchSpace for WeightedGraph {
+    type Node = NodeId;
+
+    fn start_node(&self) -> Option<NodeId> {
+        self.goal.and(self.start)
+    }
+
+    fn is_goal(&self, node: NodeId) -> bool {
+        self.goal == Some(node)
+    }
+
+    fn successors(&self, node: NodeId) -> Vec<(NodeId, f64)> {
+        self.edges(node).to_vec()
+    }
+
+    /// The straight line distance to the goal scaled by the heuristic scale
+    fn estimate(&self, node: NodeId) -> f64 {
+        self.goal
+            .and_then(|goal| self.straight_distance(node, goal))
+            .map_or(0.0, |distance| self.heuristic_scale() * distance)
+    }
+
+    fn heuristic_name(&self) -> String {
+        match self.heuristic_scale() {
+            0.0 => "None (no positions)".to_string(),
+            scale => format!("Euclidean x {:.4}", scale),
+        }
+    }
+
+    /// The cheapest of the parallel edges
+    fn cost(&self, from: NodeId, to: NodeId) -> f64 {
+        self.edges(from)
+            .iter()
+            .filter(|(node, _)| *node == to)
+            .map(|(_, cost)| *cost)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn distance(&self, from: NodeId, to: NodeId) -> Option<f64> {
+        self.straight_distance(from, to)
+    }
+
+    fn mark_visited(&mut self, node: NodeId) {
+        self.mark(node, SearchState::Visited);
+    }
+
+    fn mark_process(&mut self, node: NodeId) {
+        self.mark(node, SearchState::Process);
+    }
+
+    fn mark_path(&mut self, node: NodeId) {
+        self.mark(node, SearchState::Path);
+        self.path.push(node);
+    }
+
+    fn clear_marks(&mut self) {
+        self.states.fill(SearchState::Unvisited);
+        self.path.clear();
+    }
+}
+
+#[cfg(feature = "gui")]
+impl WeightedGraph {
+    /// # render
+    /// Render the edges and the nodes scaled into the screen area, see `fit_into`
+    pub fn render(&self, ctx: &Context, g: &mut G2d) {
+        for from in 0..self.node_count() {
+            let Some(from_position) = self.screen_position(from) else {
+                continue;
+            };
+            for (to, _) in self.edges(from) {
+                if let Some(to_position) = self.screen_position(*to) {
+                    line_from_to(
+                        [0.3, 0.3, 0.3, 1.0],
+                        0.5,
+                        from_position,
+                        to_position,
+                        ctx.transform,
+                        g,
+                    );
+                }
+            }
+        }
+
+        for step in self.path.windows(2) {
+            if let (Some(from), Some(to)) =
+                (self.screen_position(step[0]), self.screen_position(step[1]))
+            {
+                line_from_to([0.0, 0.0, 1.0, 1.0], 2.0, from, to, ctx.transform, g);
+            }
+        }
+
+        for node in 0..self.node_count() {
+            let (color, radius) = match self.node_color(node) {
+                Some(color) => (color, NODE_RADIUS),
+                None => continue,
+            };
+            if let Some([x, y]) = self.screen_position(node) {
+                ellipse(
+                    color,
+                    [x - radius, y - radius, 2.0 * radius, 2.0 * radius],
+                    ctx.transform,
+                    g,
+                );
+            }
+        }
+    }
+
+    /// Unvisited nodes are not drawn, so the edges of big graphs stay visible
+    fn node_color(&self, node: NodeId) -> Option<Color> {
+        if self.start == Some(node) {
+            return Some([1.0, 0.878, 0.0, 1.0]);
+        }
+        if self.goal == Some(node) {
+            return Some([0.255, 0.706, 0.949, 1.0]);
+        }
+        match self.state(node) {
+            SearchState::Unvisited => None,
+            SearchState::Visited | SearchState::BackwardVisited => Some([0.0, 1.0, 0.0, 1.0]),
+            SearchState::Path => Some([0.0, 0.0, 1.0, 1.0]),
+            SearchState::Process | SearchState::BackwardProcess | SearchState::JumpPoint => {
+                Some([0.45, 0.984, 0.84, 1.0])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::algorithm::{dijkstra::Dijkstra, AlgorithmKind};
+
+    /// Square with a cheap detour:
+    /// a(0,0) -> b(10,0) costs 30, a -> c(0,10) -> d(10,10) -> b costs 10 + 10 + 10
+    fn square() -> WeightedGraph {
+        let mut graph = WeightedGraph::new();
+        let a = graph.add_node("a", Some([0.0, 0.0]));
+        let b = graph.add_node("b", Some([10.0, 0.0]));
+        let c = graph.add_node("c", Some([0.0, 10.0]));
+        let d = graph.add_node("d", Some([10.0, 10.0]));
+        graph.add_undirected_edge(a, b, 31.0).unwrap();
+        graph.add_undirected_edge(a, c, 10.0).unwrap();
+        graph.add_undirected_edge(c, d, 10.0).unwrap();
+        graph.add_edge(d, b, 10.0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn graph_shortest_path() {
+        let mut graph = square();
+        let (a, b) = (graph.node("a").unwrap(), graph.node("b").unwrap());
+        assert_eq!(1.0, graph.heuristic_scale());
+
+        let a_star = graph.shortest_path(a, b).unwrap();
+        assert_eq!(10.0, graph.estimate(a));
+        assert_eq!(vec!["a", "c", "d", "b"], labels(&graph, &a_star.path));
+        assert_eq!(30.0, a_star.cost());
+        assert_eq!(SearchState::Path, graph.state(graph.node("c").unwrap()));
+
+        let dijkstra = graph.query(a, b, Dijkstra::new()).unwrap();
+        assert_eq!(a_star.path, dijkstra.path);
+        assert!(dijkstra.statistics.expanded_nodes >= a_star.statistics.expanded_nodes);
+
+        // Bfs takes the fewest edges
+        let bfs = graph
+            .query(a, b, AlgorithmKind::Bfs.create_for().unwrap())
+            .unwrap();
+        assert_eq!(vec!["a", "b"], labels(&graph, &bfs.path));
+        assert_eq!(31.0, bfs.cost());
+
+        // The edge d -> b is one way
+        let back = graph.shortest_path(b, graph.node("d").unwrap()).unwrap();
+        assert_eq!(vec!["b", "a", "c", "d"], labels(&graph, &back.path));
+
+        let isolated = graph.add_node("e", Some([5.0, 5.0]));
+        assert!(!graph
+            .shortest_path(a, isolated)
+            .unwrap()
+            .is_goal_reachable());
+        assert!(graph.shortest_path(a, 42).is_err());
+        assert_eq!(Err(GraphError::InvalidCost), graph.add_edge(a, b, -1.0));
+        assert_eq!(Err(GraphError::UnknownNode), graph.add_edge(a, 42, 1.0));
+        assert!(AlgorithmKind::JumpPointSearch
+            .create_for::<WeightedGraph>()
+            .is_err());
+    }
+
+    #[test]
+    fn graph_screen_positions() {
+        let mut graph = square();
+        graph.fit_into([0.0, 0.0, 108.0, 208.0]);
+        let c = graph.node("c").unwrap();
+        let b = graph.node("b").unwrap();
+
+        // The north is up on the screen
+        assert_eq!(Some([4.0, 4.0]), graph.screen_position(c));
+        assert_eq!(Some([104.0, 104.0]), graph.screen_position(b));
+        assert_eq!(Some(b), graph.node_at(&[100.0, 101.0]));
+        assert_eq!(None, graph.node_at(&[50.0, 50.0]));
+
+        graph.add_node("no position", None);
+        assert!(!graph.has_positions());
+        assert_eq!(0.0, graph.heuristic_scale());
+    }
+
+    fn labels<'a>(graph: &'a WeightedGraph, path: &[NodeId]) -> Vec<&'a str> {
+        path.iter()
+            .map(|node| graph.label(*node).unwrap())
+            .collect()
+    }
+}
@@ -1,14 +1,53 @@
 pub mod algorithm;
-mod map;
+pub mod benchmark;
+pub mod building;
+pub mod export;
+pub mod generator;
+pub mod graph;
+pub mod map;
+#[cfg(feature = "gui")]
 mod render_utils;
 
-use algorithm::{
-    a_star::AStar, bfs::Bfs, dijkstra::Dijkstra, greedy_bfs::GreedyBfs, Algorithm, AlgorithmError,
+pub use algorithm::{
+    search_space::SearchSpace,
+    solver::{search, solve, SearchOutcome, SearchResult},
 };
+pub use map::{grid::Grid, Terrain, TitleCoords};
 
-use map::grid::Grid;
-use map::Title;
+#[cfg(feature = "gui")]
+use algorithm::{
+    a_star::{AStar, TieBreaking},
+    bfs::Bfs,
+    bidirectional::BidirectionalSearch,
+    d_star_lite::DStarLite,
+    dfs::Dfs,
+    dijkstra::Dijkstra,
+    greedy_bfs::GreedyBfs,
+    iterative_deepening::{IdaStar, Iddfs},
+    jump_point_search::JumpPointSearch,
+    race::Race,
+    stepping::StepControl,
+    theta_star::ThetaStar,
+    timeline::{Timeline, TimelineError},
+    Algorithm, AlgorithmError, AlgorithmKind,
+};
+#[cfg(feature = "gui")]
+use building::{building_file::BuildingFileError, Building};
+#[cfg(feature = "gui")]
+use generator::{
+    layout::LayoutGenerator,
+    maze::{MazeAnimation, MazeGenerator},
+};
+#[cfg(feature = "gui")]
+use graph::{graph_file::GraphFileError, WeightedGraph};
+#[cfg(feature = "gui")]
+use map::{map_file::MapFileError, DistanceMetric, Neighbourhood, Title};
+#[cfg(feature = "gui")]
 use piston_window::*;
+#[cfg(feature = "gui")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "gui")]
 mod fsm {
     #[derive(Clone, Copy, PartialEq, Debug)]
     pub enum MouseActionState {
@@ -33,6 +72,15 @@ mod fsm {
         pub fn reset(self) -> Self {
             Self::SetStartPoint
         }
+
+        /// Continue from the first point which is not set on the kept map
+        pub fn resume(has_start: bool, has_goal: bool) -> Self {
+            match (has_start, has_goal) {
+                (true, true) => Self::StartSimulation,
+                (true, false) => Self::SetEndPoint,
+                _ => Self::SetStartPoint,
+            }
+        }
     }
 
     #[derive(Clone, Copy, PartialEq, Debug)]
@@ -41,41 +89,56 @@ mod fsm {
         Dijkstra = 1,
         GreedyBfs = 2,
         AStar = 3,
+        JumpPointSearch = 4,
+        JumpPointSearchPlus = 5,
+        BidirectionalBfs = 6,
+        BidirectionalDijkstra = 7,
+        BidirectionalAStar = 8,
+        DStarLite = 9,
+        ThetaStar = 10,
+        LazyThetaStar = 11,
+        Dfs = 12,
+        Iddfs = 13,
+        IdaStar = 14,
     }
 
     impl MenuSelectionState {
+        /// Number of menu items
+        const COUNT: isize = 15;
+
         pub fn new() -> Self {
             Self::Bfs
         }
 
         pub fn next(self) -> Self {
-            match self {
-                Self::Bfs => Self::Dijkstra,
-                Self::Dijkstra => Self::GreedyBfs,
-                Self::GreedyBfs => Self::AStar,
-                Self::AStar => Self::Bfs,
-            }
+            Self::from_index((self as isize + 1) % Self::COUNT).unwrap()
         }
 
         pub fn prev(self) -> Self {
-            match self {
-                Self::Bfs => Self::AStar,
-                Self::AStar => Self::GreedyBfs,
-                Self::GreedyBfs => Self::Dijkstra,
-                Self::Dijkstra => Self::Bfs,
-            }
+            Self::from_index((self as isize + Self::COUNT - 1) % Self::COUNT).unwrap()
         }
 
         pub fn selected_algorithm_id(&self) -> usize {
             *self as usize
         }
 
-        pub fn from_index(self, id: isize) -> Option<Self> {
+        pub fn from_index(id: isize) -> Option<Self> {
             match id {
                 0 => Some(Self::Bfs),
                 1 => Some(Self::Dijkstra),
                 2 => Some(Self::GreedyBfs),
                 3 => Some(Self::AStar),
+                4 => Some(Self::JumpPointSearch),
+                5 => Some(Self::JumpPointSearchPlus),
+                6 => Some(Self::BidirectionalBfs),
+                7 => Some(Self::BidirectionalDijkstra),
+                8 => Some(Self::BidirectionalAStar),
+                9 => Some(Self::DStarLite),
+                10 => Some(Self::ThetaStar),
+                11 => Some(Self::LazyThetaStar),
+                12 => Some(Self::Dfs),
+                13 => Some(Self::Iddfs),
+                14 => Some(Self::IdaStar),
                 _ => None,
             }
         }
@@ -86,31 +149,166 @@ mod fsm {
     }
 }
 
+#[cfg(feature = "gui")]
 mod application {
     pub mod message {
-        pub const WELCOME: &str = "..::R-PATH-FINDER::..\n\n - 1-click left mouse button sets start\n\n - 2-click left mouse button sets goal\n\n - right mouse button sets obstacle\n\n - 3-click left mouse button starts\n  the simulation\n\n - Esc - restart simulation";
+        pub const WELCOME: &str = "..::R-PATH-FINDER::..\n\n - 1-click left mouse button sets start\n - 2-click left mouse button sets goal\n - 3-click starts the simulation\n - right mouse button paints the brush\n\n Keys:\n - 1-7 - brush: obstacle, plain,\n  grass, mud, water, eraser, road\n - N - 4/8-way/hex movement\n - H/T - heuristic/A* tie-breaking\n - +/- - A* weight\n - G/M - generator/new map\n - C - race Bfs, Dijkstra, Greedy Bfs, A*\n - Space/Enter - pause/single step\n - [/] - speed, F - finish\n - Left/Right - step back/forward\n - S/L, W/R - save/load map, trace\n - Backspace - clear the map\n - Esc - back to menu, keeps the map";
         pub const SIMULATION_STARTS: &str = "Simulation Starts...";
         pub const APP_TITLE: &str = "R-PathFinder - Menu";
         pub const DONE: &str = "Done";
-        pub const ALGORITHM_MENU_ITEMS: [&str; 4] = ["Bfs", "Dijkstra", "Greedy Bfs", "A*"];
+        pub const MAP_SAVED: &str = "Map saved";
+        pub const MAP_LOADED: &str = "Map loaded";
+        pub const MAP_NOT_SAVED: &str = "The map was not saved,\n check the map path";
+        pub const MAP_NOT_LOADED: &str = "The map was not loaded,\n check the map file";
+        pub const ENDPOINTS_MISSING: &str = "Set the start and the goal\n before the simulation";
+        pub const RACE_TITLE: &str = "R-PathFinder - Race";
+        pub const RACE_WELCOME: &str =
+            "..::RACE::..\n - Space/Enter, [/], F - steps\n - Esc - back to the map";
+        pub const RACE_NEEDS_ENDPOINTS: &str = "Set the start and the goal\n before the race";
+        pub const TRACE_SAVED: &str = "Trace saved";
+        pub const TRACE_NOT_SAVED: &str = "The trace was not saved,\n check the trace path";
+        pub const TRACE_NOT_LOADED: &str = "The trace was not loaded,\n check the trace file";
+        pub const TRACE_LOADED: &str =
+            "Trace loaded\n - Left/Right - step back/forward\n - Space/Enter - pause/single step\n - [/] - speed, F - finish\n - Esc - back to menu, keeps the map";
+        pub const MAZE_STARTS: &str = "Carving the maze...\n M - finish it";
+        pub const GRAPH_WELCOME: &str = "..::R-PATH-FINDER::..\n\n - 1-click left mouse button on the node\n  sets start\n - 2-click sets goal\n - 3-click starts the simulation\n\n Keys:\n - +/- - A* weight\n - T - A* tie-breaking\n - Space/Enter - pause/single step\n - [/] - speed, F - finish\n - Backspace - clear start and goal\n - Esc - back to menu, keeps the graph";
+        pub const GRAPH_UNSUPPORTED: &str =
+            "The graph can be searched by\n Bfs, Dijkstra, Greedy Bfs and A* only";
+        pub const BUILDING_WELCOME: &str = "..::R-PATH-FINDER::..\n\n - 1-click left mouse button sets start\n - 2-click left mouse button sets goal\n  (also on another floor)\n - 3-click left mouse button starts\n  the simulation\n - right mouse button paints the brush\n\n Keys:\n - Up/Down - show the floor above/below\n - 1-7 - brush: obstacle, plain,\n  grass, mud, water, eraser, road\n - +/- - A* weight\n - T - A* tie-breaking\n - Space/Enter - pause/single step\n - [/] - speed, F - finish\n - Backspace - clear start and goal\n - Esc - back to menu, keeps the building";
+        pub const GRID_UNSUPPORTED: &str =
+            "JPS needs 8-way movement without\n corner cutting or hex movement\n and titles of the same cost\n - N - change the movement";
+        pub const BUILDING_UNSUPPORTED: &str =
+            "The building can be searched by\n Bfs, Dijkstra, Greedy Bfs and A* only";
+        pub const ALGORITHM_MISSING: &str =
+            "The selected algorithm does not exist,\n Esc - choose another one";
+        pub const SEARCH_STALLED: &str = "The search stopped making progress";
+        pub const SEARCH_SPACE_UNSUPPORTED: &str =
+            "The algorithm cannot search\n this search space,\n Esc - choose another one";
+        pub const ALGORITHM_MENU_ITEMS: [&str; 15] = [
+            "Bfs",
+            "Dijkstra",
+            "Greedy Bfs",
+            "A*",
+            "JPS",
+            "JPS+",
+            "Bi-Bfs",
+            "Bi-Dijkstra",
+            "Bi-A*",
+            "D* Lite",
+            "Theta*",
+            "Lazy Theta*",
+            "Dfs",
+            "Iddfs",
+            "IDA*",
+        ];
+    }
+    /// Number of menu items in one column
+    pub const MENU_ROWS: usize = 8;
+    /// Screen area (width x height) of the grid
+    pub const GRID_AREA: (u32, u32) = (400, 400);
+    /// Step and upper limit of the A* heuristic weight
+    pub const WEIGHT_STEP: f64 = 0.5;
+    pub const MAX_WEIGHT: f64 = 5.0;
+    /// Titles carved by the maze animation in one update
+    pub const MAZE_TITLES_PER_UPDATE: usize = 2;
+    /// Map file used by the save/load shortcuts if no map was given
+    pub const DEFAULT_MAP_PATH: &str = "r_path_finder_map.txt";
+    /// Trace file used by the write/replay shortcuts if no trace was given
+    pub const DEFAULT_TRACE_PATH: &str = "r_path_finder_trace.jsonl";
+
+    /// # Generator
+    /// What the new map shortcut generates
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub enum Generator {
+        /// Carved in the render loop
+        Maze(crate::generator::maze::MazeKind),
+        Layout(crate::generator::layout::LayoutKind),
     }
+
+    impl Generator {
+        pub fn all() -> Vec<Self> {
+            use crate::generator::{layout::LayoutKind, maze::MazeKind};
+            let mazes = MazeKind::ALL.map(Self::Maze);
+            let layouts = LayoutKind::ALL.map(Self::Layout);
+            mazes.into_iter().chain(layouts).collect()
+        }
+
+        pub fn name(&self) -> String {
+            match self {
+                Self::Maze(kind) => format!("{} maze", kind.name()),
+                Self::Layout(kind) => kind.name().to_string(),
+            }
+        }
+    }
+
     #[derive(Debug, PartialEq)]
     pub enum Scene {
         Menu,
         Algorithm,
+        /// The algorithm searches the loaded graph instead of the grid
+        Graph,
+        /// The algorithm searches all floors of the loaded building instead of the grid
+        Building,
+        /// Several algorithms search copies of the grid side by side
+        Race,
+    }
+
+    /// # Brush
+    /// What the right mouse button paints on the grid
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub enum Brush {
+        Obstacle,
+        Terrain(crate::map::Terrain),
+        /// Removes obstacles
+        Eraser,
+    }
+
+    impl Brush {
+        pub fn name(&self) -> &'static str {
+            match self {
+                Self::Obstacle => "Obstacle",
+                Self::Terrain(terrain) => terrain.name(),
+                Self::Eraser => "Eraser",
+            }
+        }
     }
 }
 
+#[cfg(feature = "gui")]
 pub struct App<'a> {
     window: PistonWindow,
-    algorithms: [Box<dyn Algorithm>; 4],
+    algorithms: [Box<dyn Algorithm>; application::message::ALGORITHM_MENU_ITEMS.len()],
     grid: Grid,
+    /// Road-like graph searched instead of the grid, when it is loaded
+    graph: Option<WeightedGraph>,
+    graph_algorithm: Option<Box<dyn Algorithm<WeightedGraph>>>,
+    /// Floors linked by stairs and lifts searched instead of the grid, when it is loaded
+    building: Option<Building>,
+    building_algorithm: Option<Box<dyn Algorithm<Building>>>,
+    /// Algorithms searching copies of the grid in lockstep, when they race
+    race: Option<Race>,
     mouse_action_fsm: fsm::MouseActionState,
     menu_fsm: fsm::MenuSelectionState,
     scene: application::Scene,
+    brush: application::Brush,
+    heuristic: Option<DistanceMetric>,
+    a_star_weight: f64,
+    tie_breaking: TieBreaking,
+    /// Pause, single step and speed of the search
+    step_control: StepControl,
+    generator: application::Generator,
+    /// Seed of the last generated map, every new map takes the next one
+    generator_seed: u64,
+    /// Maze which is being carved in the render loop
+    maze_animation: Option<MazeAnimation>,
+    /// Recorded steps of the grid search, which can be scrubbed
+    timeline: Option<Timeline>,
+    map_path: PathBuf,
+    trace_path: PathBuf,
     output_log: &'a str,
 }
 
+#[cfg(feature = "gui")]
 impl Default for App<'_> {
     /// # default
     /// Create a new instance of application.
@@ -122,27 +320,79 @@ impl Default for App<'_> {
                 .build()
                 .unwrap();
 
-        let grid = Grid::new(0, 0, 400, 400, 20);
+        let grid = Grid::new(0, 0, application::GRID_AREA.0, application::GRID_AREA.1, 20);
 
         let algorithms: [Box<dyn Algorithm>; application::message::ALGORITHM_MENU_ITEMS.len()] = [
             Box::new(Bfs::default()),
             Box::new(Dijkstra::default()),
             Box::new(GreedyBfs::default()),
             Box::new(AStar::default()),
+            Box::new(JumpPointSearch::default()),
+            Box::new(JumpPointSearch::plus()),
+            Box::new(BidirectionalSearch::bfs()),
+            Box::new(BidirectionalSearch::dijkstra()),
+            Box::new(BidirectionalSearch::a_star()),
+            Box::new(DStarLite::default()),
+            Box::new(ThetaStar::default()),
+            Box::new(ThetaStar::lazy()),
+            Box::new(Dfs::default()),
+            Box::new(Iddfs::default()),
+            Box::new(IdaStar::default()),
         ];
 
         Self {
             window,
             algorithms,
             grid,
+            graph: None,
+            graph_algorithm: None,
+            building: None,
+            building_algorithm: None,
+            race: None,
             mouse_action_fsm: fsm::MouseActionState::new(),
             menu_fsm: fsm::MenuSelectionState::new(),
             scene: application::Scene::Menu,
+            brush: application::Brush::Obstacle,
+            heuristic: None,
+            a_star_weight: 1.0,
+            tie_breaking: TieBreaking::default(),
+            step_control: StepControl::default(),
+            generator: application::Generator::all()[0],
+            generator_seed: 0,
+            maze_animation: None,
+            timeline: None,
+            map_path: PathBuf::from(application::DEFAULT_MAP_PATH),
+            trace_path: PathBuf::from(application::DEFAULT_TRACE_PATH),
             output_log: application::message::WELCOME,
         }
     }
 }
 
+/// Statistics of the started algorithm
+#[cfg(feature = "gui")]
+fn live_statistics<S: SearchSpace>(algorithm: &dyn Algorithm<S>) -> Option<String> {
+    (algorithm.has_completed() || algorithm.steps_taken() > 0)
+        .then(|| algorithm.output_statistics())
+}
+
+/// Algorithm for the graph or the building, A* takes the selected weight and tie-breaking
+#[cfg(feature = "gui")]
+fn search_space_algorithm<S: SearchSpace + 'static>(
+    kind: AlgorithmKind,
+    a_star_weight: f64,
+    tie_breaking: TieBreaking,
+) -> Result<Box<dyn Algorithm<S>>, AlgorithmError> {
+    match kind {
+        AlgorithmKind::AStar => Ok(Box::new(
+            AStar::new()
+                .weighted(a_star_weight)
+                .tie_breaking(tie_breaking),
+        )),
+        kind => kind.create_for(),
+    }
+}
+
+#[cfg(feature = "gui")]
 impl App<'_> {
     /// # run
     /// Run application/simulation
@@ -159,6 +409,15 @@ impl App<'_> {
                     &mut is_drawing_locked,
                     &e,
                 ),
+                application::Scene::Graph => {
+                    self.graph_scene_input_handling(&mut mouse_screen_position, &e)
+                }
+                application::Scene::Building => self.building_scene_input_handling(
+                    &mut mouse_screen_position,
+                    &mut is_drawing_locked,
+                    &e,
+                ),
+                application::Scene::Race => self.race_scene_input_handling(&e),
             }
             e.update(|args: &UpdateArgs| {
                 self.update_simulation_state(args);
@@ -175,12 +434,14 @@ impl App<'_> {
                         let mut size = 32;
 
                         if algorithm_id == self.menu_fsm.selected_algorithm_id() {
-                            size = 48;
+                            size = 44;
                         }
+                        let column = (algorithm_id / application::MENU_ROWS) as f64;
+                        let row = (algorithm_id % application::MENU_ROWS) as f64;
 
                         render_utils::draw_text(
                             menu_item,
-                            [270.0, 150.0 + 50.0 * (algorithm_id as f64)],
+                            [120.0 + 280.0 * column, 60.0 + 50.0 * row],
                             size,
                             render_utils::color::BLACK,
                             &mut glyph,
@@ -199,10 +460,58 @@ impl App<'_> {
                         g,
                     );
 
-                    if self.algorithms[self.menu_fsm.selected_algorithm_id()].has_completed() {
+                    let settings = match (&self.scene, &self.building) {
+                        (application::Scene::Building, Some(building)) => format!(
+                            "Floor: {}/{}\nVisited: {}\nA* weight: {:.1}\nTie-breaking: {}\nBrush: {}\nSpeed: {}",
+                            building.displayed_floor() + 1,
+                            building.floor_count(),
+                            building.visited_per_floor(),
+                            self.a_star_weight,
+                            self.tie_breaking.name(),
+                            self.brush.name(),
+                            self.step_control.status()
+                        ),
+                        _ => format!(
+                            "Moves: {}\nHeuristic: {}\nA* weight: {:.1}\nTie-breaking: {}\nBrush: {}\nMap: {}, seed {}\nSpeed: {}{}",
+                            self.grid.neighbourhood().name(),
+                            self.heuristic.map_or("Grid metric", |metric| metric.name()),
+                            self.a_star_weight,
+                            self.tie_breaking.name(),
+                            self.brush.name(),
+                            self.generator.name(),
+                            self.generator_seed,
+                            self.step_control.status(),
+                            self.timeline.as_ref().map_or(String::new(), |timeline| {
+                                format!(", step {}/{}", timeline.position(), timeline.len())
+                            })
+                        ),
+                    };
+                    render_utils::draw_text(
+                        &settings,
+                        [410.0, 375.0],
+                        16,
+                        render_utils::color::BLACK,
+                        &mut glyph,
+                        &c,
+                        g,
+                    );
+
+                    // Live statistics show e.g. the depth limit of the running iteration
+                    let statistics = match self.scene {
+                        application::Scene::Graph => {
+                            self.graph_algorithm.as_deref().and_then(live_statistics)
+                        }
+                        application::Scene::Building => {
+                            self.building_algorithm.as_deref().and_then(live_statistics)
+                        }
+                        application::Scene::Race => self.race.as_ref().map(Race::standings),
+                        _ => live_statistics(
+                            self.algorithms[self.menu_fsm.selected_algorithm_id()].as_ref(),
+                        ),
+                    };
+                    if let Some(statistics) = statistics {
                         render_utils::draw_text(
-                            &self.algorithms[self.menu_fsm.selected_algorithm_id()]
-                                .output_statistics(),
+                            &statistics,
                             [410.0, 100.0],
                             16,
                             render_utils::color::BLACK,
@@ -212,7 +521,28 @@ impl App<'_> {
                         );
                     }
 
-                    self.grid.render(&c, g);
+                    match (&self.scene, &self.graph, &mut self.building, &mut self.race) {
+                        (application::Scene::Graph, Some(graph), _, _) => graph.render(&c, g),
+                        (application::Scene::Building, _, Some(building), _) => {
+                            building.render(&c, g)
+                        }
+                        (application::Scene::Race, _, _, Some(race)) => {
+                            for lane in race.lanes_mut() {
+                                let (x, y) = lane.grid().offset();
+                                render_utils::draw_text(
+                                    &lane.algorithm().name(),
+                                    [x as f64, y as f64 - 4.0],
+                                    14,
+                                    render_utils::color::BLACK,
+                                    &mut glyph,
+                                    &c,
+                                    g,
+                                );
+                                lane.grid_mut().render(&c, g);
+                            }
+                        }
+                        _ => self.grid.render(&c, g),
+                    }
                 }
 
                 glyph.factory.encoder.flush(device);
@@ -223,7 +553,7 @@ impl App<'_> {
     /// # skip_menu_and_run_algorithm
     /// Skip menu and just run current algorithm
     pub fn skip_menu_and_run_algorithm(&mut self, id: isize) -> Result<(), AlgorithmError> {
-        let alg = self.menu_fsm.from_index(id);
+        let alg = fsm::MenuSelectionState::from_index(id);
         if alg.is_none() {
             return Err(AlgorithmError::AlgorithmDoesNotExist);
         }
@@ -235,6 +565,83 @@ impl App<'_> {
         Ok(())
     }
 
+    /// # load_map
+    /// Load the map file (MovingAI `.map` or ASCII) into the grid.
+    ///
+    /// The file is also used by the save/load shortcuts
+    pub fn load_map(&mut self, path: impl AsRef<Path>) -> Result<(), MapFileError> {
+        let mut grid = Grid::load(path.as_ref())?;
+        grid.fit_into(application::GRID_AREA.0, application::GRID_AREA.1);
+
+        self.reset_grid_search();
+        self.grid = grid;
+        self.maze_animation = None;
+        self.map_path = path.as_ref().to_path_buf();
+        self.mouse_action_fsm = fsm::MouseActionState::resume(
+            self.grid.start_title.is_some(),
+            self.grid.goal_title.is_some(),
+        );
+        Ok(())
+    }
+
+    /// # load_trace
+    /// Load the recorded search with its map, which is replayed without running the algorithm.
+    ///
+    /// The file is also used by the trace shortcuts
+    pub fn load_trace(&mut self, path: impl AsRef<Path>) -> Result<(), TimelineError> {
+        let timeline = Timeline::load(path.as_ref())?;
+        let mut grid = timeline.grid()?;
+        grid.fit_into(application::GRID_AREA.0, application::GRID_AREA.1);
+
+        self.reset_grid_search();
+        self.window.set_title(timeline.header().algorithm.clone());
+        self.grid = grid;
+        self.timeline = Some(timeline);
+        self.maze_animation = None;
+        self.trace_path = path.as_ref().to_path_buf();
+        self.mouse_action_fsm = fsm::MouseActionState::EndSimulation;
+        self.output_log = application::message::TRACE_LOADED;
+        Ok(())
+    }
+
+    /// # load_graph
+    /// Load the DIMACS `.gr` or CSV graph, which is searched instead of the grid.
+    ///
+    /// Every node needs its position to be drawn, the DIMACS positions are loaded
+    /// from the `.co` file next to the `.gr` file
+    pub fn load_graph(&mut self, path: impl AsRef<Path>) -> Result<(), GraphFileError> {
+        let mut graph = WeightedGraph::load(path)?;
+        if !graph.has_positions() {
+            return Err(GraphFileError::MissingPositions);
+        }
+        graph.fit_into([
+            0.0,
+            0.0,
+            application::GRID_AREA.0 as f64,
+            application::GRID_AREA.1 as f64,
+        ]);
+        self.graph = Some(graph);
+        self.graph_algorithm = None;
+        self.building = None;
+        self.building_algorithm = None;
+        self.mouse_action_fsm = fsm::MouseActionState::new();
+        Ok(())
+    }
+
+    /// # load_building
+    /// Load the building with its floors, stairs and lifts, which is searched instead of the grid
+    pub fn load_building(&mut self, path: impl AsRef<Path>) -> Result<(), BuildingFileError> {
+        let mut building = Building::load(path)?;
+        building.fit_into(application::GRID_AREA.0, application::GRID_AREA.1);
+        self.mouse_action_fsm =
+            fsm::MouseActionState::resume(building.start().is_some(), building.goal().is_some());
+        self.building = Some(building);
+        self.building_algorithm = None;
+        self.graph = None;
+        self.graph_algorithm = None;
+        Ok(())
+    }
+
     fn menu_scene_input_handling(&mut self, e: &Event) {
         if let Some(Button::Keyboard(Key::Up)) = e.press_args() {
             self.menu_fsm = self.menu_fsm.prev();
@@ -245,7 +652,17 @@ impl App<'_> {
         }
 
         if let Some(Button::Keyboard(Key::Return)) = e.press_args() {
-            self.scene = application::Scene::Algorithm;
+            self.scene = match (&self.graph, &self.building) {
+                (Some(_), _) => {
+                    self.output_log = application::message::GRAPH_WELCOME;
+                    application::Scene::Graph
+                }
+                (_, Some(_)) => {
+                    self.output_log = application::message::BUILDING_WELCOME;
+                    application::Scene::Building
+                }
+                _ => application::Scene::Algorithm,
+            };
             self.window.set_title(
                 application::message::ALGORITHM_MENU_ITEMS[self.menu_fsm.selected_algorithm_id()]
                     .to_string(),
@@ -259,34 +676,182 @@ impl App<'_> {
         is_drawing_locked: &mut bool,
         e: &Event,
     ) {
+        self.brush_input_handling(mouse_screen_position, is_drawing_locked, e);
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            self.handle_mouse_action(*mouse_screen_position);
+        }
+
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            match key {
+                Key::Escape => self.reset_simulation(),
+                Key::Backspace => self.clear_map(),
+                Key::S => self.save_map(),
+                Key::L => self.reload_map(),
+                Key::N => self.switch_neighbourhood(),
+                Key::H => self.switch_heuristic(),
+                Key::Equals | Key::NumPadPlus => {
+                    self.change_a_star_weight(application::WEIGHT_STEP)
+                }
+                Key::Minus | Key::NumPadMinus => {
+                    self.change_a_star_weight(-application::WEIGHT_STEP)
+                }
+                Key::T => self.switch_tie_breaking(),
+                Key::G => self.switch_generator(),
+                Key::M => self.generate_map(),
+                Key::C => self.start_race(),
+                Key::W => self.save_trace(),
+                Key::R => self.reload_trace(),
+                Key::Left => self.step_timeline_back(),
+                Key::Right => self.step_timeline_forward(),
+                key => self.handle_step_control_key(key),
+            }
+        }
+    }
+
+    fn race_scene_input_handling(&mut self, e: &Event) {
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            match key {
+                Key::Escape => self.leave_race(),
+                key => self.handle_step_control_key(key),
+            }
+        }
+    }
+
+    fn graph_scene_input_handling(&mut self, mouse_screen_position: &mut [f64; 2], e: &Event) {
         if let Some(pos) = e.mouse_cursor_args() {
             *mouse_screen_position = pos;
-            if *is_drawing_locked {
-                self.grid
-                    .on_mouse_clicked(mouse_screen_position, Title::Obstacle);
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            self.handle_graph_mouse_action(*mouse_screen_position);
+        }
+
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            match key {
+                Key::Escape => self.reset_simulation(),
+                Key::Backspace => self.clear_map(),
+                Key::Equals | Key::NumPadPlus => {
+                    self.change_a_star_weight(application::WEIGHT_STEP)
+                }
+                Key::Minus | Key::NumPadMinus => {
+                    self.change_a_star_weight(-application::WEIGHT_STEP)
+                }
+                Key::T => self.switch_tie_breaking(),
+                key => self.handle_step_control_key(key),
             }
         }
+    }
+
+    fn building_scene_input_handling(
+        &mut self,
+        mouse_screen_position: &mut [f64; 2],
+        is_drawing_locked: &mut bool,
+        e: &Event,
+    ) {
+        self.brush_input_handling(mouse_screen_position, is_drawing_locked, e);
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            self.handle_building_mouse_action(*mouse_screen_position);
+        }
 
-        if let Some(Button::Mouse(button)) = e.press_args() {
-            match button {
-                MouseButton::Left => self.handle_mouse_action(*mouse_screen_position),
-                MouseButton::Right => {
-                    *is_drawing_locked = true;
-                    self.grid
-                        .on_mouse_clicked(mouse_screen_position, Title::Obstacle);
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            match key {
+                Key::Escape => self.reset_simulation(),
+                Key::Backspace => self.clear_map(),
+                Key::Up => self.show_next_floor(1),
+                Key::Down => self.show_next_floor(-1),
+                Key::Equals | Key::NumPadPlus => {
+                    self.change_a_star_weight(application::WEIGHT_STEP)
                 }
-                _ => (),
+                Key::Minus | Key::NumPadMinus => {
+                    self.change_a_star_weight(-application::WEIGHT_STEP)
+                }
+                Key::T => self.switch_tie_breaking(),
+                key => self.handle_step_control_key(key),
             }
         }
+    }
+
+    /// Painting shared by the scenes of the grid and the building.
+    /// The right mouse button paints with the brush, the number keys select it
+    fn brush_input_handling(
+        &mut self,
+        mouse_screen_position: &mut [f64; 2],
+        is_drawing_locked: &mut bool,
+        e: &Event,
+    ) {
+        if let Some(pos) = e.mouse_cursor_args() {
+            *mouse_screen_position = pos;
+            if *is_drawing_locked {
+                self.paint_with_brush(mouse_screen_position);
+            }
+        }
+
+        if let Some(Button::Mouse(MouseButton::Right)) = e.press_args() {
+            *is_drawing_locked = true;
+            self.paint_with_brush(mouse_screen_position);
+        }
+
+        if let Some(Button::Mouse(MouseButton::Right)) = e.release_args() {
+            *is_drawing_locked = false;
+        }
+
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            self.brush = match key {
+                Key::D1 => application::Brush::Obstacle,
+                Key::D2 => application::Brush::Terrain(Terrain::Plain),
+                Key::D3 => application::Brush::Terrain(Terrain::Grass),
+                Key::D4 => application::Brush::Terrain(Terrain::Mud),
+                Key::D5 => application::Brush::Terrain(Terrain::Water),
+                Key::D6 => application::Brush::Eraser,
+                Key::D7 => application::Brush::Terrain(Terrain::Road),
+                _ => return,
+            };
+        }
+    }
+
+    /// Step controls shared by the scenes
+    fn handle_step_control_key(&mut self, key: Key) {
+        match key {
+            Key::Space => self.step_control.toggle_pause(),
+            Key::Return => self.step_control.step_once(),
+            Key::RightBracket => self.step_control.change_speed(1),
+            Key::LeftBracket => self.step_control.change_speed(-1),
+            Key::F => self.step_control.finish(),
+            _ => (),
+        }
+    }
 
-        if let Some(Button::Mouse(button)) = e.release_args() {
-            if button == MouseButton::Right {
-                *is_drawing_locked = false;
+    /// The brush paints the grid or the displayed floor of the building
+    fn paint_with_brush(&mut self, mouse_screen_position: &[f64; 2]) {
+        let grid = match (&self.scene, &mut self.building) {
+            (application::Scene::Building, Some(building)) => {
+                let floor = building.displayed_floor();
+                match building.floor_mut(floor) {
+                    Some(floor) => floor,
+                    None => return,
+                }
+            }
+            _ => &mut self.grid,
+        };
+        match self.brush {
+            application::Brush::Obstacle => {
+                grid.on_mouse_clicked(mouse_screen_position, Title::Obstacle)
             }
+            application::Brush::Terrain(terrain) => {
+                grid.on_mouse_paint_terrain(mouse_screen_position, terrain)
+            }
+            application::Brush::Eraser => grid.on_mouse_erase_obstacle(mouse_screen_position),
         }
+    }
 
-        if let Some(Button::Keyboard(Key::Escape)) = e.press_args() {
-            self.reset_simulation();
+    /// Show the floor above (1) or below (-1) the displayed one
+    fn show_next_floor(&mut self, step: isize) {
+        if let Some(building) = &mut self.building {
+            if let Some(floor) = building.displayed_floor().checked_add_signed(step) {
+                building.show_floor(floor);
+            }
         }
     }
 
@@ -297,16 +862,71 @@ impl App<'_> {
         self.window.load_font(font_source_path).unwrap()
     }
 
+    /// The steps follow the step control. Finished algorithms keep stepping, so incremental ones can repair the path
+    /// when the map is edited
     fn update_simulation_state(&mut self, args: &UpdateArgs) {
-        if self.algorithms[self.menu_fsm.selected_algorithm_id()].has_completed() {
-            self.output_log = application::message::DONE;
+        if let (application::Scene::Race, Some(race)) = (&self.scene, &mut self.race) {
+            self.step_control.run(args.dt, || race.step());
+            return;
+        }
+        if let (Some(graph), Some(algorithm)) = (&mut self.graph, &mut self.graph_algorithm) {
+            self.step_control.update(algorithm, graph, args.dt);
+            if algorithm.has_completed() {
+                self.output_log = application::message::DONE;
+            }
             return;
         }
-        self.algorithms[self.menu_fsm.selected_algorithm_id()]
-            .execute_step(&mut self.grid, args.dt);
+        if let (Some(building), Some(algorithm)) =
+            (&mut self.building, &mut self.building_algorithm)
+        {
+            self.step_control.update(algorithm, building, args.dt);
+            if algorithm.has_completed() {
+                self.output_log = application::message::DONE;
+            }
+            return;
+        }
+        if let Some(animation) = &mut self.maze_animation {
+            animation.step(&mut self.grid, application::MAZE_TITLES_PER_UPDATE);
+            if animation.is_finished() {
+                self.maze_animation = None;
+                self.output_log = application::message::WELCOME;
+            }
+            return;
+        }
+        let algorithm = &mut self.algorithms[self.menu_fsm.selected_algorithm_id()];
+        match &mut self.timeline {
+            // The recorded steps are replayed before the new ones are executed
+            Some(timeline) if !timeline.is_at_end() => {
+                for _ in 0..self.step_control.due_steps(args.dt) {
+                    if !timeline.step_forward(&mut self.grid) {
+                        break;
+                    }
+                }
+                return;
+            }
+            Some(timeline) => {
+                self.step_control.update_with(
+                    algorithm,
+                    &mut self.grid,
+                    args.dt,
+                    |algorithm, grid| {
+                        timeline.record(algorithm, grid);
+                    },
+                );
+            }
+            None => {
+                self.step_control.update(algorithm, &mut self.grid, args.dt);
+            }
+        }
+        if algorithm.has_completed() {
+            self.output_log = application::message::DONE;
+        }
     }
 
     fn handle_mouse_action(&mut self, mouse_pos: [f64; 2]) {
+        if self.maze_animation.is_some() {
+            return;
+        }
         match self.mouse_action_fsm {
             fsm::MouseActionState::SetStartPoint => {
                 self.grid.on_mouse_clicked(&mouse_pos, Title::Start);
@@ -322,19 +942,119 @@ impl App<'_> {
                 }
             }
             fsm::MouseActionState::StartSimulation => {
-                let s =
-                    self.algorithms[self.menu_fsm.selected_algorithm_id()].start(&mut self.grid);
-                self.handle_algorithm_error(s);
+                let algorithm = &mut self.algorithms[self.menu_fsm.selected_algorithm_id()];
+                let status = Timeline::start(algorithm, &mut self.grid)
+                    .map(|timeline| self.timeline = Some(timeline));
+                // A rejected grid can be changed and the simulation started again
+                if status.is_ok() {
+                    self.mouse_action_fsm = self.mouse_action_fsm.next();
+                }
+                self.handle_algorithm_error(status);
+            }
+            fsm::MouseActionState::EndSimulation => {}
+        }
+    }
+
+    /// The graph is searched by the selected algorithm, A* takes the selected weight and tie-breaking
+    fn handle_graph_mouse_action(&mut self, mouse_pos: [f64; 2]) {
+        let Some(graph) = &mut self.graph else {
+            return;
+        };
+        let node = graph.node_at(&mouse_pos);
+        match self.mouse_action_fsm {
+            fsm::MouseActionState::SetStartPoint => {
+                if node.is_some() {
+                    graph.start = node;
+                    self.mouse_action_fsm = self.mouse_action_fsm.next();
+                }
+            }
+            fsm::MouseActionState::SetEndPoint => {
+                if node.is_some() && node != graph.start {
+                    graph.goal = node;
+                    self.mouse_action_fsm = self.mouse_action_fsm.next();
+                }
+            }
+            fsm::MouseActionState::StartSimulation => {
+                match search_space_algorithm(
+                    AlgorithmKind::ALL[self.menu_fsm.selected_algorithm_id()],
+                    self.a_star_weight,
+                    self.tie_breaking,
+                ) {
+                    Ok(mut algorithm) => {
+                        let status = algorithm.start(graph);
+                        self.graph_algorithm = Some(algorithm);
+                        self.handle_algorithm_error(status);
+                    }
+                    Err(_) => self.output_log = application::message::GRAPH_UNSUPPORTED,
+                }
+                self.mouse_action_fsm = self.mouse_action_fsm.next();
+            }
+            fsm::MouseActionState::EndSimulation => {}
+        }
+    }
+
+    /// The start and the goal are set on the displayed floor
+    fn handle_building_mouse_action(&mut self, mouse_pos: [f64; 2]) {
+        let Some(building) = &mut self.building else {
+            return;
+        };
+        let title = building.title_at(&mouse_pos);
+        match self.mouse_action_fsm {
+            fsm::MouseActionState::SetStartPoint => {
+                if title.is_some_and(|title| building.set_start(title).is_ok()) {
+                    self.mouse_action_fsm = self.mouse_action_fsm.next();
+                }
+            }
+            fsm::MouseActionState::SetEndPoint => {
+                if title.is_some_and(|title| building.set_goal(title).is_ok()) {
+                    self.mouse_action_fsm = self.mouse_action_fsm.next();
+                }
+            }
+            fsm::MouseActionState::StartSimulation => {
+                match search_space_algorithm(
+                    AlgorithmKind::ALL[self.menu_fsm.selected_algorithm_id()],
+                    self.a_star_weight,
+                    self.tie_breaking,
+                ) {
+                    Ok(mut algorithm) => {
+                        let status = algorithm.start(building);
+                        self.building_algorithm = Some(algorithm);
+                        self.handle_algorithm_error(status);
+                    }
+                    Err(_) => self.output_log = application::message::BUILDING_UNSUPPORTED,
+                }
                 self.mouse_action_fsm = self.mouse_action_fsm.next();
             }
             fsm::MouseActionState::EndSimulation => {}
         }
     }
 
+    /// Go back to the menu. The map is kept, so it can be searched by another algorithm
     fn reset_simulation(&mut self) {
+        if let Some(animation) = self.maze_animation.take() {
+            animation.finish(&mut self.grid);
+        }
         self.output_log = application::message::WELCOME;
-        self.mouse_action_fsm = self.mouse_action_fsm.reset();
-        self.algorithms[self.menu_fsm.selected_algorithm_id()].reset(&mut self.grid);
+        self.brush = application::Brush::Obstacle;
+        self.reset_grid_search();
+        self.mouse_action_fsm = fsm::MouseActionState::resume(
+            self.grid.start_title.is_some(),
+            self.grid.goal_title.is_some(),
+        );
+        if let Some(graph) = &mut self.graph {
+            graph.clear_marks();
+            self.graph_algorithm = None;
+            self.mouse_action_fsm =
+                fsm::MouseActionState::resume(graph.start.is_some(), graph.goal.is_some());
+        }
+        if let Some(building) = &mut self.building {
+            building.clear_marks();
+            self.building_algorithm = None;
+            self.mouse_action_fsm = fsm::MouseActionState::resume(
+                building.start().is_some(),
+                building.goal().is_some(),
+            );
+        }
 
         self.menu_fsm = self.menu_fsm.reset();
         self.scene = application::Scene::Menu;
@@ -342,12 +1062,273 @@ impl App<'_> {
             .set_title(application::message::APP_TITLE.to_string());
     }
 
+    /// Recreate the heuristic algorithms with the next heuristic.
+    /// The running search is stopped
+    fn switch_heuristic(&mut self) {
+        self.heuristic = match self.heuristic {
+            None => Some(DistanceMetric::ALL[0]),
+            Some(metric) => DistanceMetric::ALL
+                .iter()
+                .position(|candidate| *candidate == metric)
+                .and_then(|id| DistanceMetric::ALL.get(id + 1))
+                .copied(),
+        };
+        self.rebuild_heuristic_algorithms();
+    }
+
+    fn change_a_star_weight(&mut self, step: f64) {
+        self.a_star_weight = (self.a_star_weight + step).clamp(1.0, application::MAX_WEIGHT);
+        self.rebuild_heuristic_algorithms();
+    }
+
+    fn switch_tie_breaking(&mut self) {
+        let current = TieBreaking::ALL
+            .iter()
+            .position(|tie_breaking| *tie_breaking == self.tie_breaking)
+            .unwrap_or_default();
+        self.tie_breaking = TieBreaking::ALL[(current + 1) % TieBreaking::ALL.len()];
+        self.rebuild_heuristic_algorithms();
+    }
+
+    /// Recreate Greedy Bfs and A* with the selected heuristic, weight and tie-breaking.
+    /// The running search is stopped
+    fn rebuild_heuristic_algorithms(&mut self) {
+        self.reset_grid_search();
+        self.mouse_action_fsm = fsm::MouseActionState::resume(
+            self.grid.start_title.is_some(),
+            self.grid.goal_title.is_some(),
+        );
+        self.output_log = application::message::WELCOME;
+        if let (application::Scene::Graph, Some(graph)) = (&self.scene, &mut self.graph) {
+            graph.clear_marks();
+            self.graph_algorithm = None;
+            self.mouse_action_fsm =
+                fsm::MouseActionState::resume(graph.start.is_some(), graph.goal.is_some());
+            self.output_log = application::message::GRAPH_WELCOME;
+        }
+        if let (application::Scene::Building, Some(building)) = (&self.scene, &mut self.building) {
+            building.clear_marks();
+            self.building_algorithm = None;
+            self.mouse_action_fsm = fsm::MouseActionState::resume(
+                building.start().is_some(),
+                building.goal().is_some(),
+            );
+            self.output_log = application::message::BUILDING_WELCOME;
+        }
+
+        let greedy_bfs_id = fsm::MenuSelectionState::GreedyBfs.selected_algorithm_id();
+        let a_star_id = fsm::MenuSelectionState::AStar.selected_algorithm_id();
+        let (greedy_bfs, a_star) = self.heuristic_algorithms();
+        self.algorithms[greedy_bfs_id] = Box::new(greedy_bfs);
+        self.algorithms[a_star_id] = Box::new(a_star);
+    }
+
+    /// Greedy Bfs and A* with the selected heuristic, weight and tie-breaking
+    fn heuristic_algorithms(&self) -> (GreedyBfs, AStar) {
+        let (greedy_bfs, a_star) = match self.heuristic {
+            Some(metric) => (
+                GreedyBfs::with_heuristic(metric),
+                AStar::with_heuristic(metric),
+            ),
+            None => (GreedyBfs::default(), AStar::default()),
+        };
+        (
+            greedy_bfs,
+            a_star
+                .weighted(self.a_star_weight)
+                .tie_breaking(self.tie_breaking),
+        )
+    }
+
+    /// Race Bfs, Dijkstra, Greedy Bfs and A* on copies of the grid.
+    /// The heuristic algorithms take the selected settings
+    fn start_race(&mut self) {
+        if let Some(animation) = self.maze_animation.take() {
+            animation.finish(&mut self.grid);
+        }
+        let (greedy_bfs, a_star) = self.heuristic_algorithms();
+        let algorithms: Vec<Box<dyn Algorithm>> = vec![
+            Box::new(Bfs::default()),
+            Box::new(Dijkstra::default()),
+            Box::new(greedy_bfs),
+            Box::new(a_star),
+        ];
+        match Race::new(&self.grid, algorithms) {
+            Ok(mut race) => {
+                race.arrange(0, 0, application::GRID_AREA.0, application::GRID_AREA.1);
+                self.race = Some(race);
+                self.scene = application::Scene::Race;
+                self.output_log = application::message::RACE_WELCOME;
+                self.window
+                    .set_title(application::message::RACE_TITLE.to_string());
+            }
+            Err(_) => self.output_log = application::message::RACE_NEEDS_ENDPOINTS,
+        }
+    }
+
+    /// Back to the grid of the selected algorithm, the race is dropped
+    fn leave_race(&mut self) {
+        self.race = None;
+        self.scene = application::Scene::Algorithm;
+        self.output_log = application::message::WELCOME;
+        self.window.set_title(
+            application::message::ALGORITHM_MENU_ITEMS[self.menu_fsm.selected_algorithm_id()]
+                .to_string(),
+        );
+    }
+
+    /// Change the movement of the grid. The running search is stopped,
+    /// its values belong to the previous movement
+    fn switch_neighbourhood(&mut self) {
+        self.reset_grid_search();
+        self.mouse_action_fsm = fsm::MouseActionState::resume(
+            self.grid.start_title.is_some(),
+            self.grid.goal_title.is_some(),
+        );
+        self.output_log = application::message::WELCOME;
+        let current = Neighbourhood::ALL
+            .iter()
+            .position(|neighbourhood| *neighbourhood == self.grid.neighbourhood())
+            .unwrap_or_default();
+        let next = Neighbourhood::ALL[(current + 1) % Neighbourhood::ALL.len()];
+        self.grid.set_neighbourhood(next);
+        // Hexagons take a different area than squares
+        self.grid
+            .fit_into(application::GRID_AREA.0, application::GRID_AREA.1);
+    }
+
+    fn switch_generator(&mut self) {
+        let generators = application::Generator::all();
+        let current = generators
+            .iter()
+            .position(|generator| *generator == self.generator)
+            .unwrap_or_default();
+        self.generator = generators[(current + 1) % generators.len()];
+    }
+
+    /// Replace the map by the generated one with the next seed. The maze is carved
+    /// in the render loop, the maze which is being carved is finished instead
+    fn generate_map(&mut self) {
+        if let Some(animation) = self.maze_animation.take() {
+            animation.finish(&mut self.grid);
+            self.output_log = application::message::WELCOME;
+            return;
+        }
+        self.reset_grid_search();
+        self.generator_seed += 1;
+        self.mouse_action_fsm = self.mouse_action_fsm.reset();
+        match self.generator {
+            application::Generator::Maze(kind) => {
+                let animation = MazeGenerator::new(kind)
+                    .seed(self.generator_seed)
+                    .animate(&mut self.grid);
+                self.maze_animation = Some(animation);
+                self.output_log = application::message::MAZE_STARTS;
+            }
+            application::Generator::Layout(kind) => {
+                LayoutGenerator::new(kind)
+                    .seed(self.generator_seed)
+                    .fill(&mut self.grid);
+                self.output_log = application::message::WELCOME;
+            }
+        }
+    }
+
+    fn save_map(&mut self) {
+        match self.grid.save(&self.map_path) {
+            Ok(_) => self.output_log = application::message::MAP_SAVED,
+            Err(_) => self.output_log = application::message::MAP_NOT_SAVED,
+        }
+    }
+
+    fn reload_map(&mut self) {
+        let map_path = self.map_path.clone();
+        self.output_log = match self.load_map(&map_path) {
+            Ok(_) => application::message::MAP_LOADED,
+            Err(_) => application::message::MAP_NOT_LOADED,
+        }
+    }
+
+    /// Stop the grid search, its timeline is dropped
+    fn reset_grid_search(&mut self) {
+        self.algorithms[self.menu_fsm.selected_algorithm_id()].reset(&mut self.grid);
+        self.grid.record_overlay_changes(false);
+        self.timeline = None;
+    }
+
+    /// Undo the last shown step of the search, the search is paused
+    fn step_timeline_back(&mut self) {
+        if let Some(timeline) = &mut self.timeline {
+            self.step_control.pause();
+            timeline.step_back(&mut self.grid);
+        }
+    }
+
+    /// Show the next recorded step, or execute the new one at the end of the timeline
+    fn step_timeline_forward(&mut self) {
+        match &mut self.timeline {
+            Some(timeline) if !timeline.is_at_end() => {
+                self.step_control.pause();
+                timeline.step_forward(&mut self.grid);
+            }
+            _ => self.step_control.step_once(),
+        }
+    }
+
+    fn save_trace(&mut self) {
+        let Some(timeline) = &self.timeline else {
+            return;
+        };
+        match timeline.save(&self.trace_path) {
+            Ok(_) => self.output_log = application::message::TRACE_SAVED,
+            Err(_) => self.output_log = application::message::TRACE_NOT_SAVED,
+        }
+    }
+
+    fn reload_trace(&mut self) {
+        let trace_path = self.trace_path.clone();
+        if self.load_trace(&trace_path).is_err() {
+            self.output_log = application::message::TRACE_NOT_LOADED;
+        }
+    }
+
+    fn clear_map(&mut self) {
+        if let Some(graph) = &mut self.graph {
+            graph.reset();
+            self.graph_algorithm = None;
+            self.output_log = application::message::GRAPH_WELCOME;
+            self.mouse_action_fsm = self.mouse_action_fsm.reset();
+            return;
+        }
+        if let Some(building) = &mut self.building {
+            building.reset();
+            self.building_algorithm = None;
+            self.output_log = application::message::BUILDING_WELCOME;
+            self.mouse_action_fsm = self.mouse_action_fsm.reset();
+            return;
+        }
+        self.output_log = application::message::WELCOME;
+        self.maze_animation = None;
+        self.reset_grid_search();
+        self.grid.reset();
+        self.mouse_action_fsm = self.mouse_action_fsm.reset();
+    }
+
     fn handle_algorithm_error(&mut self, status: Result<(), AlgorithmError>) {
         match status {
             Err(AlgorithmError::InvalidInputData) => {
-                println!("User did not set the start or end point")
+                self.output_log = application::message::ENDPOINTS_MISSING
+            }
+            Err(AlgorithmError::UnsupportedGrid) => {
+                self.output_log = application::message::GRID_UNSUPPORTED
+            }
+            Err(AlgorithmError::AlgorithmDoesNotExist) => {
+                self.output_log = application::message::ALGORITHM_MISSING
+            }
+            Err(AlgorithmError::UnsupportedSearchSpace) => {
+                self.output_log = application::message::SEARCH_SPACE_UNSUPPORTED
             }
-            Err(_) => todo!(),
+            Err(AlgorithmError::Stalled) => self.output_log = application::message::SEARCH_STALLED,
             Ok(_) => {
                 self.output_log = application::message::SIMULATION_STARTS;
             }
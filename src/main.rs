@@ -1,6 +1,79 @@
 use r_path_finder::App;
 
+const USAGE: &str =
+    "Usage: r_path_finder [--map <path>] [--graph <path>] [--building <path>] [--trace <path>]";
+
+/// File loaded at the start, given by the flag and the path after it
+enum StartupFile {
+    Map(String),
+    Graph(String),
+    Building(String),
+    Trace(String),
+}
+
+impl StartupFile {
+    /// Read the path after the flag. None for an unknown flag
+    fn parse(flag: &str, args: &mut impl Iterator<Item = String>) -> Option<Self> {
+        let file: fn(String) -> Self = match flag {
+            "--map" => StartupFile::Map,
+            "--graph" => StartupFile::Graph,
+            "--building" => StartupFile::Building,
+            "--trace" => StartupFile::Trace,
+            _ => return None,
+        };
+        match args.next() {
+            Some(path) => Some(file(path)),
+            None => exit_with(&format!("Missing path after {}", flag)),
+        }
+    }
+
+    /// Load the file into the application, exit if it was not loaded
+    fn load(&self, app: &mut App) {
+        let (kind, path, status) = match self {
+            StartupFile::Map(path) => ("Map", path, app.load_map(path).map_err(describe)),
+            StartupFile::Graph(path) => ("Graph", path, app.load_graph(path).map_err(describe)),
+            StartupFile::Building(path) => {
+                ("Building", path, app.load_building(path).map_err(describe))
+            }
+            StartupFile::Trace(path) => ("Trace", path, app.load_trace(path).map_err(describe)),
+        };
+        if let Err(error) = status {
+            exit_with(&format!("{} {} was not loaded: {}", kind, path, error));
+        }
+    }
+}
+
+fn describe(error: impl std::fmt::Debug) -> String {
+    format!("{:?}", error)
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+/// # Usage
+/// `r_path_finder [--map <path>] [--graph <path>] [--building <path>] [--trace <path>]`
+///
+/// `--map` starts the application with the map file (MovingAI `.map` or ASCII).
+/// `--graph` searches the DIMACS `.gr` or CSV graph instead of the grid.
+/// `--building` searches the floors of the building instead of the grid.
+/// `--trace` replays the recorded search (JSON lines) on its map.
+///
+/// All arguments are checked before the window is opened
 fn main() {
+    let mut files = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match StartupFile::parse(&arg, &mut args) {
+            Some(file) => files.push(file),
+            None => exit_with(&format!("Unknown argument {}\n{}", arg, USAGE)),
+        }
+    }
+
     let mut app = App::default();
+    for file in &files {
+        file.load(&mut app);
+    }
     app.run();
 }
@@ -0,0 +1,162 @@
+use super::{grid::Grid, MapError, Terrain, TitleCoords};
+
+/// Title size in pixels used when the grid is rendered
+const DEFAULT_TITLE_SIZE: u32 = 20;
+
+/// # GridBuilder
+/// Builds a grid by the number of columns and rows.
+///
+/// ```
+/// use r_path_finder::map::{grid::Grid, TitleCoords};
+///
+/// let grid = Grid::builder(10, 5)
+///     .start(TitleCoords { x: 0, y: 0 })
+///     .goal(TitleCoords { x: 9, y: 4 })
+///     .obstacle(TitleCoords { x: 5, y: 2 })
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(10, grid.columns);
+/// assert!(grid.is_obstacle(TitleCoords { x: 5, y: 2 }));
+/// ```
+pub struct GridBuilder {
+    columns: u32,
+    rows: u32,
+    title_size: u32,
+    offset: (u32, u32),
+    start: Option<TitleCoords>,
+    goal: Option<TitleCoords>,
+    obstacles: Vec<TitleCoords>,
+    terrain: Vec<(TitleCoords, Terrain)>,
+}
+
+impl GridBuilder {
+    /// # new
+    /// Create a builder of the grid with given number of columns and rows
+    pub fn new(columns: u32, rows: u32) -> Self {
+        Self {
+            columns,
+            rows,
+            title_size: DEFAULT_TITLE_SIZE,
+            offset: (0, 0),
+            start: None,
+            goal: None,
+            obstacles: Vec::new(),
+            terrain: Vec::new(),
+        }
+    }
+
+    /// # title_size
+    /// Size of the title in pixels when the grid is rendered
+    pub fn title_size(mut self, title_size: u32) -> Self {
+        self.title_size = title_size.max(1);
+        self
+    }
+
+    /// # offset
+    /// Screen offset(x,y) of the grid when it is rendered
+    pub fn offset(mut self, x: u32, y: u32) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    /// # start
+    /// Set the start title
+    pub fn start(mut self, title_coords: TitleCoords) -> Self {
+        self.start = Some(title_coords);
+        self
+    }
+
+    /// # goal
+    /// Set the goal title
+    pub fn goal(mut self, title_coords: TitleCoords) -> Self {
+        self.goal = Some(title_coords);
+        self
+    }
+
+    /// # obstacle
+    /// Place an obstacle on the title
+    pub fn obstacle(mut self, title_coords: TitleCoords) -> Self {
+        self.obstacles.push(title_coords);
+        self
+    }
+
+    /// # obstacles
+    /// Place obstacles on all given titles
+    pub fn obstacles(mut self, titles: impl IntoIterator<Item = TitleCoords>) -> Self {
+        self.obstacles.extend(titles);
+        self
+    }
+
+    /// # terrain
+    /// Set terrain of the title
+    pub fn terrain(mut self, title_coords: TitleCoords, terrain: Terrain) -> Self {
+        self.terrain.push((title_coords, terrain));
+        self
+    }
+
+    /// # build
+    /// Create the grid. Fails if any title is out of the grid or placed twice
+    pub fn build(self) -> Result<Grid, MapError> {
+        let mut grid = Grid::new(
+            self.offset.0,
+            self.offset.1,
+            self.columns * self.title_size,
+            self.rows * self.title_size,
+            self.title_size,
+        );
+
+        if let Some(start) = self.start {
+            grid.set_start(start)?;
+        }
+        if let Some(goal) = self.goal {
+            grid.set_goal(goal)?;
+        }
+        for obstacle in self.obstacles {
+            grid.set_obstacle(obstacle)?;
+        }
+        for (title_coords, terrain) in self.terrain {
+            if !grid.is_within_bounds(title_coords) {
+                return Err(MapError::OutOfBounds);
+            }
+            grid.set_terrain(title_coords, terrain);
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn build_grid() {
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 7, y: 2 };
+        let mud = TitleCoords { x: 3, y: 1 };
+
+        let grid = GridBuilder::new(8, 3)
+            .title_size(10)
+            .start(start)
+            .goal(goal)
+            .obstacles([TitleCoords { x: 1, y: 0 }, TitleCoords { x: 1, y: 1 }])
+            .terrain(mud, Terrain::Mud)
+            .build()
+            .unwrap();
+
+        assert_eq!(8, grid.columns);
+        assert_eq!(3, grid.rows);
+        assert_eq!(Some(start), grid.start_title);
+        assert_eq!(Some(goal), grid.goal_title);
+        assert!(grid.is_obstacle(TitleCoords { x: 1, y: 1 }));
+        assert_eq!(Terrain::Mud, grid.terrain(mud));
+
+        let occupied = GridBuilder::new(8, 3).start(start).obstacle(start).build();
+        assert_eq!(Some(MapError::TitleOccupied), occupied.err());
+
+        let out_of_bounds = GridBuilder::new(8, 3)
+            .goal(TitleCoords { x: 8, y: 0 })
+            .build();
+        assert_eq!(Some(MapError::OutOfBounds), out_of_bounds.err());
+    }
+}
@@ -1,6 +1,14 @@
-use super::{Title, TitleCoords};
-use piston_window::types::Color;
-use piston_window::{rectangle, Context, G2d};
+use std::collections::HashSet;
+
+use super::{
+    builder::GridBuilder,
+    hex::{self, AxialCoords},
+    line_titles,
+    overlay::{SearchOverlay, TitleChange},
+    DistanceMetric, MapError, Neighbourhood, SearchState, Terrain, Title, TitleCoords,
+};
+#[cfg(feature = "gui")]
+use piston_window::{line_from_to, polygon, rectangle, Context, G2d};
 
 /// FYI the coordinate system is
 ///
@@ -9,15 +17,31 @@ use piston_window::{rectangle, Context, G2d};
 /// |               <br>
 /// V y             <br>
 pub const POSSIBLE_DIRECTIONS: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)]; // Up, Down, Left, Right
+pub const DIAGONAL_DIRECTIONS: [(isize, isize); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)]; // Up-Left, Up-Right, Down-Left, Down-Right
+
+/// Color of the straight path segments between the waypoints
+pub const WAYPOINT_COLOR: [f32; 4] = [0.0, 0.0, 0.5, 1.0];
+/// Color of the unvisited road titles
+pub const ROAD_COLOR: [f32; 4] = [0.87, 0.72, 0.53, 1.0];
 
 /// # Grid
 /// Grid of titles used for path-finding algorithms
+#[derive(Clone)]
 pub struct Grid {
     pub rows: u32,
     pub columns: u32,
     title_size: u32,
     offset: (u32, u32),
     titles: Vec<Vec<Title>>,
+    terrain: Vec<Vec<Terrain>>,
+    terrain_costs: [f64; Terrain::ALL.len()],
+    neighbourhood: Neighbourhood,
+    distance_metric: DistanceMetric,
+    overlay: SearchOverlay,
+    /// Titles whose obstacle or terrain changed, e.g. for incremental replanning
+    changed_titles: HashSet<TitleCoords>,
+    /// Search state changes of the overlay, when they are recorded
+    overlay_changes: Option<Vec<TitleChange>>,
     pub start_title: Option<TitleCoords>,
     pub goal_title: Option<TitleCoords>,
 }
@@ -30,14 +54,17 @@ impl Grid {
         let columns = width / title_size;
         let rows = height / title_size;
 
-        for _ in 0..rows {
-            let mut row_titles = Vec::new();
-            for _ in 0..columns {
-                row_titles.push(Title::Normal { was_visited: false });
+        // Titles are indexed by [x][y], so the outer vector holds columns
+        for _ in 0..columns {
+            let mut column_titles = Vec::new();
+            for _ in 0..rows {
+                column_titles.push(Title::Normal);
             }
-            titles.push(row_titles);
+            titles.push(column_titles);
         }
 
+        let terrain = vec![vec![Terrain::Plain; rows as usize]; columns as usize];
+        let terrain_costs = Terrain::ALL.map(|terrain| terrain.default_cost());
         let offset = (x, y);
 
         Self {
@@ -46,68 +73,304 @@ impl Grid {
             title_size,
             offset,
             titles,
+            terrain,
+            terrain_costs,
+            neighbourhood: Neighbourhood::Four,
+            distance_metric: DistanceMetric::Manhattan,
+            overlay: SearchOverlay::new(columns, rows),
+            changed_titles: HashSet::new(),
+            overlay_changes: None,
             start_title: None,
             goal_title: None,
         }
     }
 
+    /// # builder
+    /// Start building a grid with given number of columns and rows
+    pub fn builder(columns: u32, rows: u32) -> GridBuilder {
+        GridBuilder::new(columns, rows)
+    }
+
+    /// # fit_into
+    /// Scale the title size, so the whole grid fits into the screen area (width x height).
+    ///
+    /// The rows of the hex grid are shifted and overlap, see [`hex`]
+    pub fn fit_into(&mut self, width: u32, height: u32) {
+        let columns = self.columns.max(1) as f64;
+        let rows = self.rows.max(1) as f64;
+        let (grid_width, grid_height) = match self.neighbourhood {
+            Neighbourhood::Hex => (
+                columns + 0.5,
+                (rows - 1.0) * hex::ROW_HEIGHT + 2.0 * hex::RADIUS,
+            ),
+            _ => (columns, rows),
+        };
+        let title_size = (width as f64 / grid_width).min(height as f64 / grid_height);
+        self.title_size = (title_size as u32).max(1);
+    }
+
+    /// # title_size
+    /// Size of the title on the screen
+    pub fn title_size(&self) -> u32 {
+        self.title_size
+    }
+
+    /// # offset
+    /// Screen position (x, y) of the top left corner of the grid
+    pub fn offset(&self) -> (u32, u32) {
+        self.offset
+    }
+
+    /// # set_offset
+    /// Move the grid on the screen, e.g. into one of several viewports
+    pub fn set_offset(&mut self, x: u32, y: u32) {
+        self.offset = (x, y);
+    }
+
+    /// # title
+    /// Get the title. None if the coordinates are out of the grid
+    pub fn title(&self, title_coords: TitleCoords) -> Option<&Title> {
+        if !self.is_within_bounds(title_coords) {
+            return None;
+        }
+        Some(&self.titles[title_coords.x][title_coords.y])
+    }
+
+    /// # set_start
+    /// Set the start title. The previous start title is cleared
+    pub fn set_start(&mut self, title_coords: TitleCoords) -> Result<(), MapError> {
+        self.check_title_is_free(title_coords, self.start_title)?;
+        if let Some(previous_start) = self.start_title.take() {
+            self.titles[previous_start.x][previous_start.y] = Title::Normal;
+        }
+        self.titles[title_coords.x][title_coords.y] = Title::Start;
+        self.start_title = Some(title_coords);
+        Ok(())
+    }
+
+    /// # set_goal
+    /// Set the goal title. The previous goal title is cleared
+    pub fn set_goal(&mut self, title_coords: TitleCoords) -> Result<(), MapError> {
+        self.check_title_is_free(title_coords, self.goal_title)?;
+        if let Some(previous_goal) = self.goal_title.take() {
+            self.titles[previous_goal.x][previous_goal.y] = Title::Normal;
+        }
+        self.titles[title_coords.x][title_coords.y] = Title::End;
+        self.goal_title = Some(title_coords);
+        Ok(())
+    }
+
+    /// # set_obstacle
+    /// Place an obstacle on the title
+    pub fn set_obstacle(&mut self, title_coords: TitleCoords) -> Result<(), MapError> {
+        self.check_title_is_free(title_coords, None)?;
+        self.titles[title_coords.x][title_coords.y] = Title::Obstacle;
+        self.changed_titles.insert(title_coords);
+        Ok(())
+    }
+
+    /// # clear_title
+    /// Turn the title back into a normal one. Clears the start or goal if it was placed there
+    pub fn clear_title(&mut self, title_coords: TitleCoords) -> Result<(), MapError> {
+        if !self.is_within_bounds(title_coords) {
+            return Err(MapError::OutOfBounds);
+        }
+        if self.start_title == Some(title_coords) {
+            self.start_title = None;
+        }
+        if self.goal_title == Some(title_coords) {
+            self.goal_title = None;
+        }
+        if self.titles[title_coords.x][title_coords.y] == Title::Obstacle {
+            self.changed_titles.insert(title_coords);
+        }
+        self.titles[title_coords.x][title_coords.y] = Title::Normal;
+        Ok(())
+    }
+
     /// # on_mouse_clicked
     /// Mouse click event to setup start,end and additional obstacle title
     pub fn on_mouse_clicked(&mut self, mouse_positon: &[f64; 2], title: Title) {
-        if mouse_positon[0] >= self.offset.0 as f64
-            && mouse_positon[0] < (self.offset.0 + self.columns * self.title_size) as f64
-            && mouse_positon[1] >= self.offset.1 as f64
-            && mouse_positon[1] < (self.offset.1 + self.rows * self.title_size) as f64
-        {
-            let x = ((mouse_positon[0] - self.offset.0 as f64) / self.title_size as f64) as usize;
-            let y = ((mouse_positon[1] - self.offset.1 as f64) / self.title_size as f64) as usize;
-
+        if let Some(TitleCoords { x, y }) = self.screen_to_title_coords(mouse_positon) {
             if self.is_not_set(TitleCoords { x, y }) {
                 match title {
                     Title::Start => self.start_title = Some(TitleCoords { x, y }),
                     Title::End => self.goal_title = Some(TitleCoords { x, y }),
-                    _ => {}
+                    Title::Obstacle => {
+                        self.changed_titles.insert(TitleCoords { x, y });
+                    }
+                    Title::Normal => {}
                 }
                 self.titles[x][y] = title;
             }
         }
     }
 
+    /// # on_mouse_erase_obstacle
+    /// Mouse event to remove the obstacle under the cursor
+    pub fn on_mouse_erase_obstacle(&mut self, mouse_positon: &[f64; 2]) {
+        if let Some(title_coords) = self.screen_to_title_coords(mouse_positon) {
+            if self.is_obstacle(title_coords) {
+                let _ = self.clear_title(title_coords);
+            }
+        }
+    }
+
+    /// # on_mouse_paint_terrain
+    /// Mouse event to paint the terrain under the cursor
+    pub fn on_mouse_paint_terrain(&mut self, mouse_positon: &[f64; 2], terrain: Terrain) {
+        if let Some(title_coords) = self.screen_to_title_coords(mouse_positon) {
+            self.set_terrain(title_coords, terrain);
+        }
+    }
+
     /// # get_neighbors
-    /// Get title neighbors for current title
-    pub fn get_neighbors(&mut self, current_coord: TitleCoords) -> Vec<TitleCoords> {
-        POSSIBLE_DIRECTIONS
+    /// Get title neighbors for current title.
+    ///
+    /// Straight neighbors come first, followed by the diagonal ones for 8-way movement.
+    /// The hex grid has six neighbours depending on the row, see [`hex::directions`]
+    pub fn get_neighbors(&self, current_coord: TitleCoords) -> Vec<TitleCoords> {
+        if self.neighbourhood == Neighbourhood::Hex {
+            return hex::directions(current_coord)
+                .into_iter()
+                .filter_map(|step_direction| self.passable_step(current_coord, step_direction))
+                .collect();
+        }
+
+        let mut neighbors: Vec<TitleCoords> = POSSIBLE_DIRECTIONS
             .into_iter()
-            .filter_map(|step_direction| {
-                let coord_x = current_coord.x.checked_add_signed(step_direction.0)?;
-                let coord_y = current_coord.y.checked_add_signed(step_direction.1)?;
+            .filter_map(|step_direction| self.passable_step(current_coord, step_direction))
+            .collect();
 
-                let neighbor_coordinates = TitleCoords {
-                    x: coord_x,
-                    y: coord_y,
-                };
+        if self.neighbourhood == Neighbourhood::Four {
+            return neighbors;
+        }
 
-                if !self.is_within_bounds(neighbor_coordinates)
-                    || self.is_obstacle(neighbor_coordinates)
-                {
-                    return None;
+        for step_direction in DIAGONAL_DIRECTIONS {
+            let Some(neighbor_coordinates) = self.passable_step(current_coord, step_direction)
+            else {
+                continue;
+            };
+            if self.neighbourhood == Neighbourhood::EightNoCornerCutting {
+                let horizontal = self.passable_step(current_coord, (step_direction.0, 0));
+                let vertical = self.passable_step(current_coord, (0, step_direction.1));
+                if horizontal.is_none() || vertical.is_none() {
+                    continue;
                 }
-                Some(neighbor_coordinates)
-            })
-            .collect()
+            }
+            neighbors.push(neighbor_coordinates);
+        }
+        neighbors
     }
+
     /// # cost
-    /// The movement cost - Is 1 for normal grid
-    pub fn cost(&self, _current: TitleCoords, _next: TitleCoords) -> i32 {
-        1
+    /// The movement cost - Cost of the terrain of the next title.
+    ///
+    /// Diagonal moves are √2 times more expensive. All six moves of the hex grid cost the same
+    pub fn cost(&self, current: TitleCoords, next: TitleCoords) -> f64 {
+        let terrain_cost = self.terrain_cost(self.terrain(next));
+        if self.neighbourhood != Neighbourhood::Hex && current.x != next.x && current.y != next.y {
+            return terrain_cost * std::f64::consts::SQRT_2;
+        }
+        terrain_cost
+    }
+
+    /// # neighbourhood
+    /// Titles reachable in one move
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// # set_neighbourhood
+    /// Configure the movement. The heuristic is switched to the matching distance metric.
+    ///
+    /// [`Neighbourhood::Hex`] turns the titles into hexagons
+    pub fn set_neighbourhood(&mut self, neighbourhood: Neighbourhood) {
+        self.neighbourhood = neighbourhood;
+        self.distance_metric = neighbourhood.matching_metric();
+    }
+
+    /// # distance_metric
+    /// Distance metric used by the heuristic
+    pub fn distance_metric(&self) -> DistanceMetric {
+        self.distance_metric
+    }
+
+    /// # set_distance_metric
+    /// Select the distance metric used by the heuristic
+    pub fn set_distance_metric(&mut self, distance_metric: DistanceMetric) {
+        self.distance_metric = distance_metric;
+    }
+
+    /// # terrain
+    /// Terrain of the title. Titles outside the grid are plain
+    pub fn terrain(&self, title_coords: TitleCoords) -> Terrain {
+        if !self.is_within_bounds(title_coords) {
+            return Terrain::Plain;
+        }
+        self.terrain[title_coords.x][title_coords.y]
+    }
+
+    /// # set_terrain
+    /// Set terrain of the title
+    pub fn set_terrain(&mut self, title_coords: TitleCoords, terrain: Terrain) {
+        if self.is_within_bounds(title_coords)
+            && self.terrain[title_coords.x][title_coords.y] != terrain
+        {
+            self.terrain[title_coords.x][title_coords.y] = terrain;
+            self.changed_titles.insert(title_coords);
+        }
+    }
+
+    /// # terrain_cost
+    /// The movement cost to enter the title with given terrain
+    pub fn terrain_cost(&self, terrain: Terrain) -> f64 {
+        self.terrain_costs[terrain as usize]
+    }
+
+    /// # set_terrain_cost
+    /// Configure the movement cost of the terrain. Costs which are not positive are ignored
+    pub fn set_terrain_cost(&mut self, terrain: Terrain, cost: f64) {
+        if cost > 0.0 && cost.is_finite() {
+            self.terrain_costs[terrain as usize] = cost;
+        }
+    }
+
+    /// # heuristic_scale
+    /// The cheapest configured terrain cost.
+    ///
+    /// No move is cheaper than the scaled distance, so the scaled distance to the goal
+    /// never overestimates and A* stays optimal
+    pub fn heuristic_scale(&self) -> f64 {
+        self.terrain_costs
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min)
     }
 
     /// # heuristic
-    /// Manhattan distance on a square grid
-    pub fn heuristic(&self, current: TitleCoords, next: TitleCoords) -> i32 {
-        let x_distance = (next.x as i32) - (current.x as i32);
-        let y_distance = (next.y as i32) - (current.y as i32);
-        x_distance.abs() + y_distance.abs()
+    /// Distance between titles measured with the selected distance metric,
+    /// scaled by the heuristic scale
+    pub fn heuristic(&self, current: TitleCoords, next: TitleCoords) -> f64 {
+        self.heuristic_scale() * self.distance_metric.distance(current, next)
+    }
+
+    /// # centre_distance
+    /// Straight line distance between the centres of the titles, measured in titles
+    pub fn centre_distance(&self, from: TitleCoords, to: TitleCoords) -> f64 {
+        match self.neighbourhood {
+            Neighbourhood::Hex => hex::centre_distance(from, to),
+            _ => DistanceMetric::Euclidean.distance(from, to),
+        }
+    }
+
+    /// # line_titles
+    /// Titles crossed by the straight line between the centres of two titles, both included
+    pub fn line_titles(&self, from: TitleCoords, to: TitleCoords) -> Vec<TitleCoords> {
+        match self.neighbourhood {
+            Neighbourhood::Hex => hex::line_titles(from, to),
+            _ => line_titles(from, to),
+        }
     }
 
     /// # is_within_bounds
@@ -117,31 +380,157 @@ impl Grid {
     }
 
     /// # mark_visited
-    /// Mark title visited
+    /// Mark title visited in the search overlay
     pub fn mark_visited(&mut self, title_coords: TitleCoords) {
         if !self.is_within_bounds(title_coords) || self.is_title_start_or_end(title_coords) {
             return;
         }
-        self.titles[title_coords.x][title_coords.y] = Title::Normal { was_visited: true };
+        self.set_search_state(title_coords, SearchState::Visited);
+    }
+
+    /// # mark_backward_visited
+    /// Mark title visited by the backward search in the search overlay
+    pub fn mark_backward_visited(&mut self, title_coords: TitleCoords) {
+        if !self.is_within_bounds(title_coords) || self.is_title_start_or_end(title_coords) {
+            return;
+        }
+        self.set_search_state(title_coords, SearchState::BackwardVisited);
+    }
+
+    /// # mark_backward_process
+    /// Mark title in processing state of the backward search in the search overlay
+    pub fn mark_backward_process(&mut self, title_coords: TitleCoords) {
+        if !self.is_within_bounds(title_coords) || self.is_title_start_or_end(title_coords) {
+            return;
+        }
+        self.set_search_state(title_coords, SearchState::BackwardProcess);
+    }
+
+    /// # mark_jump_point
+    /// Mark title as a jump point in the search overlay
+    pub fn mark_jump_point(&mut self, title_coords: TitleCoords) {
+        if !self.is_within_bounds(title_coords) || self.is_title_start_or_end(title_coords) {
+            return;
+        }
+        self.set_search_state(title_coords, SearchState::JumpPoint);
     }
 
     /// # mark_process
-    /// Mark title in processing state
+    /// Mark title in processing state in the search overlay
     pub fn mark_process(&mut self, title_coords: TitleCoords) {
         if !self.is_within_bounds(title_coords) || self.is_title_start_or_end(title_coords) {
             return;
         }
-        self.titles[title_coords.x][title_coords.y] = Title::Process;
+        self.set_search_state(title_coords, SearchState::Process);
     }
 
     /// # set_trace_back_path
-    /// Set title to be a title path
+    /// Set title to be a title path in the search overlay
     pub fn set_trace_back_path(&mut self, title_coords: TitleCoords) {
-        if self.is_within_bounds(title_coords)
-            && self.titles[title_coords.x][title_coords.y] == (Title::Normal { was_visited: true })
-        {
-            self.titles[title_coords.x][title_coords.y] = Title::Path;
+        if !self.is_within_bounds(title_coords) || self.is_title_start_or_end(title_coords) {
+            return;
+        }
+        self.set_search_state(title_coords, SearchState::Path);
+    }
+
+    /// # overlay
+    /// Search overlay drawn over the map
+    pub fn overlay(&self) -> &SearchOverlay {
+        &self.overlay
+    }
+
+    /// # take_overlay
+    /// Take the search overlay out of the grid leaving an empty one.
+    ///
+    /// Useful to keep the result of one algorithm and compare it with the next run
+    pub fn take_overlay(&mut self) -> SearchOverlay {
+        let empty_overlay = SearchOverlay::new(self.columns, self.rows);
+        std::mem::replace(&mut self.overlay, empty_overlay)
+    }
+
+    /// # set_overlay
+    /// Show previously taken search overlay. Ignored if it does not fit the grid
+    pub fn set_overlay(&mut self, overlay: SearchOverlay) {
+        if overlay.columns() == self.columns && overlay.rows() == self.rows {
+            self.overlay = overlay;
+        }
+    }
+
+    /// # clear_overlay
+    /// Clear the search overlay. The map is left untouched
+    pub fn clear_overlay(&mut self) {
+        if self.overlay_changes.is_some() {
+            for x in 0..self.columns as usize {
+                for y in 0..self.rows as usize {
+                    self.set_search_state(TitleCoords { x, y }, SearchState::Unvisited);
+                }
+            }
+        }
+        self.overlay.clear();
+    }
+
+    /// # set_search_state
+    /// Set the search state of the title in the overlay, e.g. to replay the recorded search
+    pub fn set_search_state(&mut self, title_coords: TitleCoords, state: SearchState) {
+        let before = self.overlay.get(title_coords);
+        if before == state || !self.is_within_bounds(title_coords) {
+            return;
+        }
+        if let Some(changes) = &mut self.overlay_changes {
+            changes.push(TitleChange {
+                title: title_coords,
+                before,
+                after: state,
+            });
+        }
+        self.overlay.set(title_coords, state);
+    }
+
+    /// # record_overlay_changes
+    /// Start or stop recording the search state changes of the overlay
+    pub fn record_overlay_changes(&mut self, record: bool) {
+        self.overlay_changes = record.then(Vec::new);
+    }
+
+    /// # take_overlay_changes
+    /// Search state changes recorded since the last call
+    pub fn take_overlay_changes(&mut self) -> Vec<TitleChange> {
+        self.overlay_changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// # has_line_of_sight
+    /// Check if the straight line between the centres of two titles crosses no obstacle.
+    ///
+    /// Unless the 8-way movement cuts corners, the line may not pass through the corner of an obstacle
+    pub fn has_line_of_sight(&self, from: TitleCoords, to: TitleCoords) -> bool {
+        if self.neighbourhood == Neighbourhood::Hex {
+            return hex::line_titles(from, to)
+                .into_iter()
+                .all(|title| !self.is_obstacle(title));
         }
+        line_titles(from, to).windows(2).all(|step| {
+            let (current, next) = (step[0], step[1]);
+            let is_corner_free = current.x == next.x
+                || current.y == next.y
+                || self.neighbourhood == Neighbourhood::Eight
+                || (!self.is_obstacle(TitleCoords {
+                    x: next.x,
+                    y: current.y,
+                }) && !self.is_obstacle(TitleCoords {
+                    x: current.x,
+                    y: next.y,
+                }));
+            is_corner_free && !self.is_obstacle(next)
+        })
+    }
+
+    /// # set_path_waypoints
+    /// Show the path as straight segments between the waypoints, e.g. for any-angle paths
+    pub fn set_path_waypoints(&mut self, waypoints: &[TitleCoords]) {
+        self.overlay.set_waypoints(waypoints.to_vec());
     }
 
     /// # is_obstacle
@@ -153,64 +542,219 @@ impl Grid {
         self.titles[title_coords.x][title_coords.y] == Title::Obstacle
     }
     /// # reset
-    /// Clear grid
+    /// Clear grid - the map and the search overlay
     pub fn reset(&mut self) {
+        self.overlay.clear();
         for row in self.titles.iter_mut() {
             for title in row.iter_mut() {
-                *title = Title::Normal { was_visited: false };
+                *title = Title::Normal;
+            }
+        }
+        for row in self.terrain.iter_mut() {
+            for terrain in row.iter_mut() {
+                *terrain = Terrain::Plain;
             }
         }
         self.start_title = None;
         self.goal_title = None;
+        self.changed_titles.clear();
+    }
+
+    /// # take_changed_titles
+    /// Titles whose obstacle or terrain changed since the last call
+    pub fn take_changed_titles(&mut self) -> Vec<TitleCoords> {
+        self.changed_titles.drain().collect()
+    }
+
+    /// The title can be taken if it is inside the grid and it is not an obstacle,
+    /// start or goal. The `replaced` title is the one which is going to be moved
+    fn check_title_is_free(
+        &self,
+        title_coords: TitleCoords,
+        replaced: Option<TitleCoords>,
+    ) -> Result<(), MapError> {
+        if !self.is_within_bounds(title_coords) {
+            return Err(MapError::OutOfBounds);
+        }
+        if replaced == Some(title_coords) {
+            return Ok(());
+        }
+        match self.titles[title_coords.x][title_coords.y] {
+            Title::Start | Title::End | Title::Obstacle => Err(MapError::TitleOccupied),
+            _ => Ok(()),
+        }
+    }
+
+    fn passable_step(
+        &self,
+        current_coord: TitleCoords,
+        step_direction: (isize, isize),
+    ) -> Option<TitleCoords> {
+        let neighbor_coordinates = TitleCoords {
+            x: current_coord.x.checked_add_signed(step_direction.0)?,
+            y: current_coord.y.checked_add_signed(step_direction.1)?,
+        };
+        if !self.is_within_bounds(neighbor_coordinates) || self.is_obstacle(neighbor_coordinates) {
+            return None;
+        }
+        Some(neighbor_coordinates)
+    }
+
+    /// # screen_to_title_coords
+    /// Title under the mouse. None outside the grid
+    pub fn screen_to_title_coords(&self, mouse_positon: &[f64; 2]) -> Option<TitleCoords> {
+        if self.neighbourhood == Neighbourhood::Hex {
+            let title_size = self.title_size as f64;
+            // Relative to the centre of the first title, measured in titles
+            let point = [
+                (mouse_positon[0] - self.offset.0 as f64) / title_size - 0.5,
+                (mouse_positon[1] - self.offset.1 as f64) / title_size - hex::RADIUS,
+            ];
+            return AxialCoords::from_point(point)
+                .to_title_coords()
+                .filter(|title_coords| self.is_within_bounds(*title_coords));
+        }
+        if mouse_positon[0] >= self.offset.0 as f64
+            && mouse_positon[0] < (self.offset.0 + self.columns * self.title_size) as f64
+            && mouse_positon[1] >= self.offset.1 as f64
+            && mouse_positon[1] < (self.offset.1 + self.rows * self.title_size) as f64
+        {
+            let x = ((mouse_positon[0] - self.offset.0 as f64) / self.title_size as f64) as usize;
+            let y = ((mouse_positon[1] - self.offset.1 as f64) / self.title_size as f64) as usize;
+            return Some(TitleCoords { x, y });
+        }
+        None
+    }
+
+    fn is_title_start_or_end(&self, title_coords: TitleCoords) -> bool {
+        if self.titles[title_coords.x][title_coords.y] == Title::Start
+            || self.titles[title_coords.x][title_coords.y] == Title::End
+        {
+            return true;
+        }
+        false
+    }
+    fn is_not_set(&self, title_coords: TitleCoords) -> bool {
+        if self.titles[title_coords.x][title_coords.y] == (Title::Normal) {
+            return true;
+        }
+        false
     }
 
+    /// # title_color
+    /// RGBA color of the title on the screen and in the exported images,
+    /// darkened by the movement cost of its terrain
+    pub fn title_color(&self, title_coords: TitleCoords) -> [f32; 4] {
+        let color = match self.titles[title_coords.x][title_coords.y] {
+            Title::Start => return [1.0, 0.878, 0.0, 1.0],
+            Title::End => return [0.255, 0.706, 0.949, 1.0],
+            Title::Obstacle => return [0.569, 0.471, 0.365, 1.0],
+            Title::Normal => match self.overlay.get(title_coords) {
+                SearchState::Unvisited if self.terrain(title_coords) == Terrain::Road => ROAD_COLOR,
+                SearchState::Unvisited => [1.0, 0.0, 0.0, 1.0],
+                SearchState::Visited => [0.0, 1.0, 0.0, 1.0],
+                SearchState::Path => [0.0, 0.0, 1.0, 1.0],
+                SearchState::Process => [0.45, 0.984, 0.84, 1.0],
+                SearchState::JumpPoint => [1.0, 0.0, 1.0, 1.0],
+                SearchState::BackwardVisited => [0.58, 0.44, 0.86, 1.0],
+                SearchState::BackwardProcess => [0.85, 0.75, 1.0, 1.0],
+            },
+        };
+        self.shade_by_cost(color, title_coords)
+    }
+
+    /// Darken the color proportionally to the movement cost of the title
+    fn shade_by_cost(&self, color: [f32; 4], title_coords: TitleCoords) -> [f32; 4] {
+        let min_cost = self.terrain_costs.iter().copied().fold(f64::MAX, f64::min);
+        let max_cost = self.terrain_costs.iter().copied().fold(f64::MIN, f64::max);
+        if max_cost <= min_cost {
+            return color;
+        }
+        let cost = self.terrain_cost(self.terrain(title_coords));
+        let ratio = ((cost - min_cost) / (max_cost - min_cost)) as f32;
+        let brightness = 1.0 - 0.75 * ratio;
+        [
+            color[0] * brightness,
+            color[1] * brightness,
+            color[2] * brightness,
+            color[3],
+        ]
+    }
+}
+
+#[cfg(feature = "gui")]
+impl Grid {
     /// # render
     /// Render current grid with titles
     pub fn render(&mut self, ctx: &Context, g: &mut G2d) {
-        for x in 0..self.rows {
-            for y in 0..self.columns {
-                let title = &self.titles[x as usize][y as usize];
-                let color = self.get_color_for_title(title);
+        // Small titles of big maps are drawn without the gap
+        let gap = if self.title_size > 4 { 2 } else { 0 };
+        for x in 0..self.columns {
+            for y in 0..self.rows {
+                let title_coords = TitleCoords {
+                    x: x as usize,
+                    y: y as usize,
+                };
+                let color = self.title_color(title_coords);
+                if self.neighbourhood == Neighbourhood::Hex {
+                    polygon(color, &self.hexagon(title_coords, gap), ctx.transform, g);
+                    continue;
+                }
                 rectangle(
                     color,
                     [
                         ((x * self.title_size) + self.offset.0) as f64,
                         ((y * self.title_size) + self.offset.1) as f64,
-                        (self.title_size - 2) as f64,
-                        (self.title_size - 2) as f64,
+                        (self.title_size - gap) as f64,
+                        (self.title_size - gap) as f64,
                     ],
                     ctx.transform,
                     g,
                 );
             }
         }
-    }
 
-    fn get_color_for_title(&self, title: &Title) -> Color {
-        match *title {
-            Title::Normal { was_visited: false } => [1.0, 0.0, 0.0, 1.0],
-            Title::Normal { was_visited: true } => [0.0, 1.0, 0.0, 1.0],
-            Title::Start => [1.0, 0.878, 0.0, 1.0],
-            Title::End => [0.255, 0.706, 0.949, 1.0],
-            Title::Obstacle => [0.569, 0.471, 0.365, 1.0],
-            Title::Path => [0.0, 0.0, 1.0, 1.0],
-            Title::Process => [0.45, 0.984, 0.84, 1.0],
+        for segment in self.overlay.waypoints().windows(2) {
+            line_from_to(
+                WAYPOINT_COLOR,
+                (self.title_size as f64 / 8.0).max(1.0),
+                self.title_centre(segment[0]),
+                self.title_centre(segment[1]),
+                ctx.transform,
+                g,
+            );
         }
     }
 
-    fn is_title_start_or_end(&self, title_coords: TitleCoords) -> bool {
-        if self.titles[title_coords.x][title_coords.y] == Title::Start
-            || self.titles[title_coords.x][title_coords.y] == Title::End
-        {
-            return true;
+    /// # title_centre
+    /// Centre of the title on the screen
+    pub fn title_centre(&self, title_coords: TitleCoords) -> [f64; 2] {
+        if self.neighbourhood == Neighbourhood::Hex {
+            let title_size = self.title_size as f64;
+            let centre = AxialCoords::from(title_coords).centre();
+            return [
+                self.offset.0 as f64 + (centre[0] + 0.5) * title_size,
+                self.offset.1 as f64 + (centre[1] + hex::RADIUS) * title_size,
+            ];
         }
-        false
+        let half_size = self.title_size as f64 / 2.0;
+        [
+            (title_coords.x as u32 * self.title_size + self.offset.0) as f64 + half_size,
+            (title_coords.y as u32 * self.title_size + self.offset.1) as f64 + half_size,
+        ]
     }
-    fn is_not_set(&self, title_coords: TitleCoords) -> bool {
-        if self.titles[title_coords.x][title_coords.y] == (Title::Normal { was_visited: false }) {
-            return true;
-        }
-        false
+
+    /// Corners of the pointy-top hexagon, shrunk by the gap between the titles
+    fn hexagon(&self, title_coords: TitleCoords, gap: u32) -> [[f64; 2]; 6] {
+        let centre = self.title_centre(title_coords);
+        let radius = (self.title_size - gap) as f64 * hex::RADIUS;
+        std::array::from_fn(|corner| {
+            let angle = (60.0 * corner as f64 - 30.0).to_radians();
+            [
+                centre[0] + radius * angle.cos(),
+                centre[1] + radius * angle.sin(),
+            ]
+        })
     }
 }
 
@@ -250,17 +794,15 @@ mod unit_test {
         grid.mark_visited(normal_title_coord);
         assert!(grid.is_within_bounds(normal_title_coord));
         assert_eq!(
-            Title::Normal { was_visited: true },
+            Title::Normal,
             grid.titles[normal_title_coord.x][normal_title_coord.y]
         );
+        assert_eq!(SearchState::Visited, grid.overlay().get(normal_title_coord));
 
         assert!(!grid.is_within_bounds(out_of_bounds_title_coord));
 
         grid.set_trace_back_path(normal_title_coord);
-        assert_eq!(
-            Title::Path,
-            grid.titles[normal_title_coord.x][normal_title_coord.y]
-        );
+        assert_eq!(SearchState::Path, grid.overlay().get(normal_title_coord));
     }
     #[test]
     fn grid_reset() {
@@ -278,9 +820,39 @@ mod unit_test {
         assert!(grid.goal_title.is_none());
         for row in grid.titles.iter() {
             for title in row.iter() {
-                assert_eq!(*title, Title::Normal { was_visited: false });
+                assert_eq!(*title, Title::Normal);
             }
         }
+        assert_eq!(25, grid.overlay().count(SearchState::Unvisited));
+    }
+    #[test]
+    fn overlay_is_separated_from_map() {
+        let mut grid = Grid::builder(5, 5)
+            .start(TitleCoords { x: 0, y: 0 })
+            .goal(TitleCoords { x: 4, y: 4 })
+            .obstacle(TitleCoords { x: 2, y: 2 })
+            .build()
+            .unwrap();
+
+        grid.mark_visited(TitleCoords { x: 0, y: 0 });
+        grid.mark_visited(TitleCoords { x: 1, y: 0 });
+        grid.mark_process(TitleCoords { x: 2, y: 0 });
+
+        assert_eq!(Some(&Title::Start), grid.title(TitleCoords { x: 0, y: 0 }));
+        assert_eq!(1, grid.overlay().count(SearchState::Visited));
+
+        let first_run = grid.take_overlay();
+        assert_eq!(0, grid.overlay().count(SearchState::Visited));
+
+        grid.mark_visited(TitleCoords { x: 1, y: 0 });
+        grid.clear_overlay();
+        assert_eq!(25, grid.overlay().count(SearchState::Unvisited));
+        assert_eq!(Some(TitleCoords { x: 0, y: 0 }), grid.start_title);
+        assert_eq!(Some(TitleCoords { x: 4, y: 4 }), grid.goal_title);
+        assert!(grid.is_obstacle(TitleCoords { x: 2, y: 2 }));
+
+        grid.set_overlay(first_run.clone());
+        assert_eq!(&first_run, grid.overlay());
     }
     #[test]
     fn calculate_neighbors() {
@@ -305,4 +877,230 @@ mod unit_test {
             assert_eq!(exp_neighbors[id], *actual);
         }
     }
+    #[test]
+    fn place_titles_by_coords() {
+        let mut grid = Grid::new(0, 0, 60, 30, 10);
+        let start = TitleCoords { x: 5, y: 1 };
+        let goal = TitleCoords { x: 0, y: 2 };
+        let obstacle = TitleCoords { x: 3, y: 0 };
+
+        assert_eq!(6, grid.columns);
+        assert_eq!(3, grid.rows);
+
+        assert!(grid.set_start(TitleCoords { x: 1, y: 1 }).is_ok());
+        assert!(grid.set_start(start).is_ok());
+        assert!(grid.set_goal(goal).is_ok());
+        assert!(grid.set_obstacle(obstacle).is_ok());
+
+        assert_eq!(Some(&Title::Normal), grid.title(TitleCoords { x: 1, y: 1 }));
+        assert_eq!(Some(&Title::Start), grid.title(start));
+        assert_eq!(Some(&Title::End), grid.title(goal));
+        assert_eq!(Some(&Title::Obstacle), grid.title(obstacle));
+        assert_eq!(None, grid.title(TitleCoords { x: 6, y: 0 }));
+
+        assert_eq!(Err(MapError::TitleOccupied), grid.set_goal(start));
+        assert_eq!(Err(MapError::TitleOccupied), grid.set_obstacle(goal));
+        assert_eq!(
+            Err(MapError::OutOfBounds),
+            grid.set_obstacle(TitleCoords { x: 0, y: 3 })
+        );
+
+        assert!(grid.clear_title(start).is_ok());
+        assert!(grid.clear_title(obstacle).is_ok());
+        assert!(grid.start_title.is_none());
+        assert!(!grid.is_obstacle(obstacle));
+        assert_eq!(Some(goal), grid.goal_title);
+    }
+    #[test]
+    fn eight_directional_neighbors() {
+        // [ ][#][ ]
+        // [ ][c][ ]
+        // [ ][ ][ ]
+        let mut grid = Grid::builder(3, 3)
+            .obstacle(TitleCoords { x: 1, y: 0 })
+            .build()
+            .unwrap();
+        let current = TitleCoords { x: 1, y: 1 };
+
+        assert_eq!(3, grid.get_neighbors(current).len());
+
+        grid.set_neighbourhood(Neighbourhood::Eight);
+        assert_eq!(DistanceMetric::Octile, grid.distance_metric());
+        let neighbors = grid.get_neighbors(current);
+        assert_eq!(7, neighbors.len());
+        assert!(neighbors.contains(&TitleCoords { x: 0, y: 0 }));
+
+        grid.set_neighbourhood(Neighbourhood::EightNoCornerCutting);
+        let neighbors = grid.get_neighbors(current);
+        assert_eq!(5, neighbors.len());
+        assert!(!neighbors.contains(&TitleCoords { x: 0, y: 0 }));
+        assert!(!neighbors.contains(&TitleCoords { x: 2, y: 0 }));
+
+        let diagonal = TitleCoords { x: 2, y: 2 };
+        assert_eq!(std::f64::consts::SQRT_2, grid.cost(current, diagonal));
+        assert_eq!(1.0, grid.cost(current, TitleCoords { x: 1, y: 2 }));
+    }
+    #[test]
+    fn distance_metrics() {
+        let from = TitleCoords { x: 1, y: 1 };
+        let to = TitleCoords { x: 4, y: 5 };
+
+        assert_eq!(7.0, DistanceMetric::Manhattan.distance(from, to));
+        assert_eq!(4.0, DistanceMetric::Chebyshev.distance(from, to));
+        let octile = DistanceMetric::Octile.distance(from, to);
+        assert!((octile - (1.0 + 3.0 * std::f64::consts::SQRT_2)).abs() < 1e-9);
+
+        let mut grid = Grid::new(0, 0, 10, 10, 1);
+        grid.set_terrain_cost(Terrain::Road, 1.0);
+        assert_eq!(7.0, grid.heuristic(from, to));
+        grid.set_distance_metric(DistanceMetric::Chebyshev);
+        assert_eq!(4.0, grid.heuristic(from, to));
+
+        // The distance is scaled by the cheapest terrain
+        grid.set_terrain_cost(Terrain::Road, 0.25);
+        assert_eq!(0.25, grid.heuristic_scale());
+        assert_eq!(1.0, grid.heuristic(from, to));
+    }
+    #[test]
+    fn terrain_movement_cost() {
+        let mut grid = Grid::new(0, 0, 50, 50, 10);
+        let current = TitleCoords { x: 1, y: 1 };
+        let mud = TitleCoords { x: 1, y: 2 };
+        let water = TitleCoords { x: 2, y: 1 };
+
+        assert_eq!(1.0, grid.cost(current, mud));
+
+        grid.set_terrain(mud, Terrain::Mud);
+        grid.on_mouse_paint_terrain(&[25.0, 15.0], Terrain::Water);
+
+        assert_eq!(Terrain::Mud, grid.terrain(mud));
+        assert_eq!(Terrain::Water, grid.terrain(water));
+        assert_eq!(Terrain::Mud.default_cost(), grid.cost(current, mud));
+        assert_eq!(Terrain::Water.default_cost(), grid.cost(current, water));
+
+        grid.set_terrain_cost(Terrain::Mud, 3.0);
+        assert_eq!(3.0, grid.cost(current, mud));
+
+        grid.set_terrain_cost(Terrain::Water, -4.0);
+        assert_eq!(Terrain::Water.default_cost(), grid.cost(current, water));
+        grid.set_terrain_cost(Terrain::Water, 0.5);
+        assert_eq!(0.5, grid.cost(current, water));
+
+        // Road is cheaper than plain and has its own color
+        let road = TitleCoords { x: 0, y: 1 };
+        grid.set_terrain(road, Terrain::Road);
+        assert!(grid.cost(current, road) < grid.cost(current, TitleCoords { x: 0, y: 0 }));
+        assert_eq!(ROAD_COLOR, grid.title_color(road));
+
+        grid.reset();
+        assert_eq!(Terrain::Plain, grid.terrain(mud));
+    }
+
+    #[test]
+    fn line_of_sight() {
+        // [S][ ][ ][ ]
+        // [ ][#][ ][ ]
+        // [ ][ ][ ][G]
+        let mut grid = Grid::new(0, 0, 4, 3, 1);
+        let start = TitleCoords { x: 0, y: 0 };
+        let goal = TitleCoords { x: 3, y: 2 };
+        assert_eq!(
+            vec![
+                start,
+                TitleCoords { x: 1, y: 0 },
+                TitleCoords { x: 1, y: 1 },
+                TitleCoords { x: 2, y: 1 },
+                TitleCoords { x: 2, y: 2 },
+                goal
+            ],
+            line_titles(start, goal)
+        );
+        assert_eq!(
+            vec![
+                start,
+                TitleCoords { x: 1, y: 1 },
+                TitleCoords { x: 2, y: 2 }
+            ],
+            line_titles(start, TitleCoords { x: 2, y: 2 })
+        );
+        assert!(grid.has_line_of_sight(start, goal));
+
+        grid.set_obstacle(TitleCoords { x: 1, y: 1 }).unwrap();
+        assert!(!grid.has_line_of_sight(start, goal));
+        assert!(!grid.has_line_of_sight(goal, start));
+        assert!(grid.has_line_of_sight(start, TitleCoords { x: 3, y: 0 }));
+        // The corner of the obstacle
+        assert!(!grid.has_line_of_sight(TitleCoords { x: 0, y: 1 }, TitleCoords { x: 1, y: 0 }));
+        grid.set_neighbourhood(Neighbourhood::Eight);
+        assert!(grid.has_line_of_sight(TitleCoords { x: 0, y: 1 }, TitleCoords { x: 1, y: 0 }));
+    }
+
+    #[test]
+    fn hex_grid() {
+        let mut grid = Grid::new(0, 0, 100, 100, 10);
+        grid.set_neighbourhood(Neighbourhood::Hex);
+        assert_eq!(DistanceMetric::Hex, grid.distance_metric());
+
+        // Odd rows are shifted right, so the neighbours above and below differ by the row
+        let even = TitleCoords { x: 3, y: 2 };
+        let odd = TitleCoords { x: 3, y: 3 };
+        assert_eq!(6, grid.get_neighbors(even).len());
+        assert!(grid
+            .get_neighbors(even)
+            .contains(&TitleCoords { x: 2, y: 1 }));
+        assert!(grid
+            .get_neighbors(odd)
+            .contains(&TitleCoords { x: 4, y: 2 }));
+        assert!(!grid
+            .get_neighbors(odd)
+            .contains(&TitleCoords { x: 2, y: 2 }));
+        assert_eq!(2, grid.get_neighbors(TitleCoords { x: 0, y: 0 }).len());
+        assert_eq!(1.0, grid.cost(odd, TitleCoords { x: 4, y: 2 }));
+
+        // The title size fits the shifted rows
+        grid.fit_into(100, 100);
+        assert_eq!(9, grid.title_size);
+        // Centre of the first title and the shifted title of the odd row
+        grid.on_mouse_clicked(&[4.5, 5.2], Title::Start);
+        grid.on_mouse_clicked(&[9.0, 13.0], Title::End);
+        assert_eq!(Some(TitleCoords { x: 0, y: 0 }), grid.start_title);
+        assert_eq!(Some(TitleCoords { x: 0, y: 1 }), grid.goal_title);
+        grid.on_mouse_clicked(&[1.0, 13.0], Title::Obstacle);
+        assert!(!grid.is_obstacle(TitleCoords { x: 0, y: 1 }));
+        assert_eq!(None, grid.title(TitleCoords { x: 0, y: 10 }));
+
+        let (from, to) = (TitleCoords { x: 0, y: 4 }, TitleCoords { x: 6, y: 4 });
+        assert!(grid.has_line_of_sight(from, to));
+        assert_eq!(6.0, grid.centre_distance(from, to));
+        grid.set_obstacle(TitleCoords { x: 3, y: 4 }).unwrap();
+        assert!(!grid.has_line_of_sight(from, to));
+        assert!(grid.has_line_of_sight(from, TitleCoords { x: 2, y: 0 }));
+    }
+
+    #[test]
+    fn track_changed_titles() {
+        let mut grid = Grid::new(0, 0, 10, 10, 1);
+        let obstacle = TitleCoords { x: 1, y: 1 };
+        let mud = TitleCoords { x: 2, y: 2 };
+
+        grid.set_obstacle(obstacle).unwrap();
+        grid.set_terrain(mud, Terrain::Mud);
+        grid.set_terrain(mud, Terrain::Mud);
+        let mut changed_titles = grid.take_changed_titles();
+        changed_titles.sort_by_key(|title| title.x);
+        assert_eq!(vec![obstacle, mud], changed_titles);
+        assert!(grid.take_changed_titles().is_empty());
+
+        grid.set_start(mud).unwrap();
+        grid.clear_title(mud).unwrap();
+        assert!(grid.take_changed_titles().is_empty());
+
+        grid.on_mouse_erase_obstacle(&[1.5, 1.5]);
+        assert!(!grid.is_obstacle(obstacle));
+        assert_eq!(vec![obstacle], grid.take_changed_titles());
+
+        grid.set_obstacle(obstacle).unwrap();
+        grid.reset();
+        assert!(grid.take_changed_titles().is_empty());
+    }
 }
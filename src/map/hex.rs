@@ -0,0 +1,203 @@
+//! # Hex
+//! Geometry of the hexagonal grid.
+//!
+//! The titles are pointy-top hexagons. [`TitleCoords`] keep the "odd-r" offset coordinates:
+//! the column and the row, where the odd rows are shifted right by half a title.
+//! Distances and lines are computed in the axial coordinates (q, r),
+//! see [redblobgames](https://www.redblobgames.com/grids/hexagons/).
+use super::TitleCoords;
+
+/// Neighbours of the title in an even row: Right, Left, Up-Right, Up-Left, Down-Right, Down-Left
+pub const EVEN_ROW_DIRECTIONS: [(isize, isize); 6] =
+    [(1, 0), (-1, 0), (0, -1), (-1, -1), (0, 1), (-1, 1)];
+/// Neighbours of the title in an odd row: Right, Left, Up-Right, Up-Left, Down-Right, Down-Left
+pub const ODD_ROW_DIRECTIONS: [(isize, isize); 6] =
+    [(1, 0), (-1, 0), (1, -1), (0, -1), (1, 1), (0, 1)];
+
+/// Vertical distance between the centres of neighbouring rows (√3/2) - the centres
+/// of neighbouring titles are 1 apart
+pub const ROW_HEIGHT: f64 = 0.866_025_403_784_438_6;
+/// Distance from the centre of the title to its corner (1/√3)
+pub const RADIUS: f64 = 0.577_350_269_189_625_8;
+
+/// # AxialCoords
+/// Axial coordinates of the hexagon. The third cube coordinate is s = -q - r
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct AxialCoords {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl AxialCoords {
+    /// # distance
+    /// Number of moves between the hexagons
+    pub fn distance(&self, other: AxialCoords) -> u64 {
+        let dq = self.q - other.q;
+        let dr = self.r - other.r;
+        (dq.unsigned_abs() + dr.unsigned_abs() + (dq + dr).unsigned_abs()) / 2
+    }
+
+    /// # centre
+    /// Centre of the hexagon measured in titles
+    pub fn centre(&self) -> [f64; 2] {
+        [
+            self.q as f64 + self.r as f64 / 2.0,
+            self.r as f64 * ROW_HEIGHT,
+        ]
+    }
+
+    /// # from_point
+    /// Hexagon containing the point measured in titles, see [`AxialCoords::centre`]
+    pub fn from_point(point: [f64; 2]) -> Self {
+        let r = point[1] / ROW_HEIGHT;
+        round(point[0] - r / 2.0, r)
+    }
+
+    /// # to_title_coords
+    /// Offset coordinates of the hexagon. None if it is left of or above the grid
+    pub fn to_title_coords(&self) -> Option<TitleCoords> {
+        let x = self.q + (self.r - (self.r & 1)) / 2;
+        Some(TitleCoords {
+            x: usize::try_from(x).ok()?,
+            y: usize::try_from(self.r).ok()?,
+        })
+    }
+}
+
+impl From<TitleCoords> for AxialCoords {
+    fn from(title_coords: TitleCoords) -> Self {
+        let (x, y) = (title_coords.x as i64, title_coords.y as i64);
+        Self {
+            q: x - (y - (y & 1)) / 2,
+            r: y,
+        }
+    }
+}
+
+/// # directions
+/// Offsets of the six neighbours, they depend on the row of the title
+pub fn directions(title_coords: TitleCoords) -> [(isize, isize); 6] {
+    if title_coords.y.is_multiple_of(2) {
+        EVEN_ROW_DIRECTIONS
+    } else {
+        ODD_ROW_DIRECTIONS
+    }
+}
+
+/// # distance
+/// Number of moves between the titles
+pub fn distance(from: TitleCoords, to: TitleCoords) -> f64 {
+    AxialCoords::from(from).distance(AxialCoords::from(to)) as f64
+}
+
+/// # centre_distance
+/// Straight line distance between the centres of the titles
+pub fn centre_distance(from: TitleCoords, to: TitleCoords) -> f64 {
+    let (from, to) = (
+        AxialCoords::from(from).centre(),
+        AxialCoords::from(to).centre(),
+    );
+    (to[0] - from[0]).hypot(to[1] - from[1])
+}
+
+/// # line_titles
+/// Titles crossed by the straight line between the centres of two titles, both included.
+///
+/// The line is nudged aside, so it never runs exactly along the side of two titles
+pub fn line_titles(from: TitleCoords, to: TitleCoords) -> Vec<TitleCoords> {
+    let (start, end) = (AxialCoords::from(from), AxialCoords::from(to));
+    let steps = start.distance(end);
+    let (start_q, start_r) = (start.q as f64 + 1e-6, start.r as f64 + 1e-6);
+    let (end_q, end_r) = (end.q as f64 + 1e-6, end.r as f64 + 1e-6);
+
+    (0..=steps)
+        .filter_map(|step| {
+            let t = if steps == 0 {
+                0.0
+            } else {
+                step as f64 / steps as f64
+            };
+            round(
+                start_q + (end_q - start_q) * t,
+                start_r + (end_r - start_r) * t,
+            )
+            .to_title_coords()
+        })
+        .collect()
+}
+
+/// Hexagon containing the fractional axial coordinates
+fn round(q: f64, r: f64) -> AxialCoords {
+    let s = -q - r;
+    let (mut rounded_q, mut rounded_r, rounded_s) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = (
+        (rounded_q - q).abs(),
+        (rounded_r - r).abs(),
+        (rounded_s - s).abs(),
+    );
+    // The coordinate with the largest rounding error is derived from the other two
+    if dq > dr && dq > ds {
+        rounded_q = -rounded_r - rounded_s;
+    } else if dr > ds {
+        rounded_r = -rounded_q - rounded_s;
+    }
+    AxialCoords {
+        q: rounded_q as i64,
+        r: rounded_r as i64,
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn hex_coordinates() {
+        let title = TitleCoords { x: 3, y: 3 };
+        let axial = AxialCoords::from(title);
+        assert_eq!(AxialCoords { q: 2, r: 3 }, axial);
+        assert_eq!(Some(title), axial.to_title_coords());
+        assert_eq!(None, AxialCoords { q: 0, r: -1 }.to_title_coords());
+
+        // Every neighbour is one move and one title away
+        for (dx, dy) in directions(title) {
+            let neighbor = TitleCoords {
+                x: title.x.checked_add_signed(dx).unwrap(),
+                y: title.y.checked_add_signed(dy).unwrap(),
+            };
+            assert_eq!(1.0, distance(title, neighbor));
+            assert!((centre_distance(title, neighbor) - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(
+            3.0,
+            distance(TitleCoords { x: 0, y: 0 }, TitleCoords { x: 1, y: 3 })
+        );
+        assert_eq!(axial, AxialCoords::from_point(axial.centre()));
+        assert_eq!(
+            axial,
+            AxialCoords::from_point([axial.centre()[0] + 0.4, axial.centre()[1] - 0.2])
+        );
+    }
+
+    #[test]
+    fn hex_line() {
+        let from = TitleCoords { x: 0, y: 0 };
+        let to = TitleCoords { x: 3, y: 0 };
+        assert_eq!(
+            vec![
+                from,
+                TitleCoords { x: 1, y: 0 },
+                TitleCoords { x: 2, y: 0 },
+                to
+            ],
+            line_titles(from, to)
+        );
+
+        let line = line_titles(from, TitleCoords { x: 2, y: 4 });
+        assert_eq!(5, line.len());
+        for step in line.windows(2) {
+            assert_eq!(1.0, distance(step[0], step[1]));
+        }
+        assert_eq!(vec![from], line_titles(from, from));
+    }
+}
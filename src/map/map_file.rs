@@ -0,0 +1,383 @@
+//! # Map files
+//! Import and export of the grid layout.
+//!
+//! Two text formats are supported:
+//! - [MovingAI](https://movingai.com/benchmarks/formats.html) benchmark `.map` format.
+//!   `.`/`G` passable, `@`/`O`/`T` obstacle, `S` swamp (mud), `W` water.
+//!   The format has no start and goal, and grass and road are exported as passable `.`.
+//!   `type octile` maps are loaded with 8-way movement without corner cutting,
+//!   `type hex` maps with the hexagonal titles. The other movements are kept
+//!   in the non-standard `movement four` or `movement eight` header line
+//! - Simple ASCII format: `#` wall, `S` start, `G` goal, `.` free.
+//!   Terrain is stored with lower case letters: `g` grass, `m` mud, `w` water, `r` road
+use super::{grid::Grid, MapError, Neighbourhood, Terrain, Title, TitleCoords};
+use std::{fs, io, path::Path};
+
+/// # MapFormat
+/// Supported map file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    MovingAi,
+    Ascii,
+}
+
+impl MapFormat {
+    /// # from_path
+    /// `.map` files are MovingAI maps, everything else is ASCII
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("map") => MapFormat::MovingAi,
+            _ => MapFormat::Ascii,
+        }
+    }
+}
+
+/// # MapFileError
+/// Errors reported when the map file is loaded
+#[derive(Debug)]
+pub enum MapFileError {
+    Io(io::Error),
+    /// The MovingAI header is missing or malformed
+    InvalidHeader(String),
+    /// Unknown title character in line `y` at column `x`
+    InvalidTitle {
+        x: usize,
+        y: usize,
+        character: char,
+    },
+    /// Lines have different lengths or do not match the header
+    InvalidSize,
+    /// More than one start or goal
+    InvalidMap(MapError),
+}
+
+impl From<io::Error> for MapFileError {
+    fn from(error: io::Error) -> Self {
+        MapFileError::Io(error)
+    }
+}
+
+impl From<MapError> for MapFileError {
+    fn from(error: MapError) -> Self {
+        MapFileError::InvalidMap(error)
+    }
+}
+
+impl Grid {
+    /// # load
+    /// Load the grid from the file. The format is chosen by the file extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Grid, MapFileError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        match MapFormat::from_path(path) {
+            MapFormat::MovingAi => Grid::from_movingai(&content),
+            MapFormat::Ascii => Grid::from_ascii(&content),
+        }
+    }
+
+    /// # save
+    /// Save the grid layout to the file. The format is chosen by the file extension
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), MapFileError> {
+        let path = path.as_ref();
+        let content = match MapFormat::from_path(path) {
+            MapFormat::MovingAi => self.to_movingai(),
+            MapFormat::Ascii => self.to_ascii(),
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// # from_movingai
+    /// Parse the grid from MovingAI `.map` content
+    pub fn from_movingai(content: &str) -> Result<Grid, MapFileError> {
+        let mut lines = content.lines().map(|line| line.trim_end());
+        let mut width = None;
+        let mut height = None;
+        let mut neighbourhood = Neighbourhood::Four;
+        let mut movement = None;
+
+        loop {
+            let line = lines
+                .next()
+                .ok_or_else(|| MapFileError::InvalidHeader("missing 'map' line".to_string()))?;
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("map"), None) => break,
+                (Some("type"), Some("octile")) => {
+                    neighbourhood = Neighbourhood::EightNoCornerCutting
+                }
+                (Some("type"), Some("hex")) => neighbourhood = Neighbourhood::Hex,
+                (Some("type"), Some(_)) => {}
+                (Some("movement"), Some("four")) => movement = Some(Neighbourhood::Four),
+                (Some("movement"), Some("eight")) => movement = Some(Neighbourhood::Eight),
+                (Some("height"), Some(value)) => height = Some(parse_dimension(value)?),
+                (Some("width"), Some(value)) => width = Some(parse_dimension(value)?),
+                (None, _) => {}
+                _ => return Err(MapFileError::InvalidHeader(line.to_string())),
+            }
+        }
+
+        let (width, height) = match (width, height) {
+            (Some(width), Some(height)) => (width, height),
+            _ => {
+                return Err(MapFileError::InvalidHeader(
+                    "missing width or height".to_string(),
+                ))
+            }
+        };
+
+        let rows: Vec<&str> = lines.take(height).collect();
+        let mut grid = parse_titles(&rows, |character| match character {
+            '.' | 'G' => Some(MapTitle::Free(Terrain::Plain)),
+            '@' | 'O' | 'T' => Some(MapTitle::Obstacle),
+            'S' => Some(MapTitle::Free(Terrain::Mud)),
+            'W' => Some(MapTitle::Free(Terrain::Water)),
+            _ => None,
+        })?;
+
+        if grid.columns as usize != width || grid.rows as usize != height {
+            return Err(MapFileError::InvalidSize);
+        }
+        grid.set_neighbourhood(movement.unwrap_or(neighbourhood));
+        Ok(grid)
+    }
+
+    /// # to_movingai
+    /// Export the grid layout to MovingAI `.map` content. The hex grid is exported as `type hex`.
+    ///
+    /// `type octile` stands for 8-way movement without corner cutting, 4-way and 8-way grids
+    /// get the `movement` header line, so they are loaded with the same movement
+    pub fn to_movingai(&self) -> String {
+        let map_type = match self.neighbourhood() {
+            Neighbourhood::Hex => "hex",
+            _ => "octile",
+        };
+        let mut content = format!(
+            "type {}\nheight {}\nwidth {}\n",
+            map_type, self.rows, self.columns
+        );
+        match self.neighbourhood() {
+            Neighbourhood::Four => content.push_str("movement four\n"),
+            Neighbourhood::Eight => content.push_str("movement eight\n"),
+            Neighbourhood::EightNoCornerCutting | Neighbourhood::Hex => {}
+        }
+        content.push_str("map\n");
+        content.push_str(
+            &self.export_titles(|title, terrain| match (title, terrain) {
+                (Title::Obstacle, _) => '@',
+                (_, Terrain::Mud) => 'S',
+                (_, Terrain::Water) => 'W',
+                _ => '.',
+            }),
+        );
+        content
+    }
+
+    /// # from_ascii
+    /// Parse the grid from ASCII content
+    pub fn from_ascii(content: &str) -> Result<Grid, MapFileError> {
+        let rows: Vec<&str> = content
+            .lines()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.is_empty())
+            .collect();
+        parse_titles(&rows, |character| match character {
+            '.' => Some(MapTitle::Free(Terrain::Plain)),
+            'g' => Some(MapTitle::Free(Terrain::Grass)),
+            'm' => Some(MapTitle::Free(Terrain::Mud)),
+            'w' => Some(MapTitle::Free(Terrain::Water)),
+            'r' => Some(MapTitle::Free(Terrain::Road)),
+            '#' => Some(MapTitle::Obstacle),
+            'S' => Some(MapTitle::Start),
+            'G' => Some(MapTitle::Goal),
+            _ => None,
+        })
+    }
+
+    /// # to_ascii
+    /// Export the grid layout to ASCII content
+    pub fn to_ascii(&self) -> String {
+        self.export_titles(|title, terrain| match (title, terrain) {
+            (Title::Obstacle, _) => '#',
+            (Title::Start, _) => 'S',
+            (Title::End, _) => 'G',
+            (_, Terrain::Plain) => '.',
+            (_, Terrain::Grass) => 'g',
+            (_, Terrain::Mud) => 'm',
+            (_, Terrain::Water) => 'w',
+            (_, Terrain::Road) => 'r',
+        })
+    }
+
+    fn export_titles(&self, to_character: impl Fn(&Title, Terrain) -> char) -> String {
+        let mut content = String::new();
+        for y in 0..self.rows as usize {
+            for x in 0..self.columns as usize {
+                let title_coords = TitleCoords { x, y };
+                let title = self.title(title_coords).unwrap_or(&Title::Normal);
+                content.push(to_character(title, self.terrain(title_coords)));
+            }
+            content.push('\n');
+        }
+        content
+    }
+}
+
+/// Title read from the map file
+enum MapTitle {
+    Free(Terrain),
+    Obstacle,
+    Start,
+    Goal,
+}
+
+fn parse_dimension(value: &str) -> Result<usize, MapFileError> {
+    value
+        .parse()
+        .map_err(|_| MapFileError::InvalidHeader(format!("invalid dimension '{}'", value)))
+}
+
+fn parse_titles(
+    rows: &[&str],
+    to_title: impl Fn(char) -> Option<MapTitle>,
+) -> Result<Grid, MapFileError> {
+    let columns = rows.first().map_or(0, |row| row.chars().count());
+    if rows.iter().any(|row| row.chars().count() != columns) {
+        return Err(MapFileError::InvalidSize);
+    }
+
+    let mut grid = Grid::builder(columns as u32, rows.len() as u32).build()?;
+    for (y, row) in rows.iter().enumerate() {
+        for (x, character) in row.chars().enumerate() {
+            let title_coords = TitleCoords { x, y };
+            match to_title(character) {
+                Some(MapTitle::Free(terrain)) => grid.set_terrain(title_coords, terrain),
+                Some(MapTitle::Obstacle) => grid.set_obstacle(title_coords)?,
+                Some(MapTitle::Start) if grid.start_title.is_none() => {
+                    grid.set_start(title_coords)?
+                }
+                Some(MapTitle::Goal) if grid.goal_title.is_none() => grid.set_goal(title_coords)?,
+                Some(_) => return Err(MapError::TitleOccupied.into()),
+                None => return Err(MapFileError::InvalidTitle { x, y, character }),
+            }
+        }
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn ascii_round_trip() {
+        let ascii = "S..#\n.mw#\nrrgG\n";
+        let grid = Grid::from_ascii(ascii).unwrap();
+
+        assert_eq!(4, grid.columns);
+        assert_eq!(3, grid.rows);
+        assert_eq!(Some(TitleCoords { x: 0, y: 0 }), grid.start_title);
+        assert_eq!(Some(TitleCoords { x: 3, y: 2 }), grid.goal_title);
+        assert!(grid.is_obstacle(TitleCoords { x: 3, y: 1 }));
+        assert_eq!(Terrain::Water, grid.terrain(TitleCoords { x: 2, y: 1 }));
+        assert_eq!(Terrain::Road, grid.terrain(TitleCoords { x: 1, y: 2 }));
+        assert_eq!(ascii, grid.to_ascii());
+
+        assert!(matches!(
+            Grid::from_ascii("S.\n..x\n"),
+            Err(MapFileError::InvalidSize)
+        ));
+        assert!(matches!(
+            Grid::from_ascii("S.\n.x\n"),
+            Err(MapFileError::InvalidTitle {
+                x: 1,
+                y: 1,
+                character: 'x'
+            })
+        ));
+        assert!(matches!(
+            Grid::from_ascii("SS\n..\n"),
+            Err(MapFileError::InvalidMap(MapError::TitleOccupied))
+        ));
+    }
+
+    #[test]
+    fn movingai_round_trip() {
+        let movingai = "type octile\nheight 2\nwidth 5\nmap\n.@TSW\nG..O.\n";
+        let grid = Grid::from_movingai(movingai).unwrap();
+
+        assert_eq!(5, grid.columns);
+        assert_eq!(2, grid.rows);
+        assert_eq!(Neighbourhood::EightNoCornerCutting, grid.neighbourhood());
+        assert!(grid.is_obstacle(TitleCoords { x: 1, y: 0 }));
+        assert!(grid.is_obstacle(TitleCoords { x: 2, y: 0 }));
+        assert!(grid.is_obstacle(TitleCoords { x: 3, y: 1 }));
+        assert_eq!(Terrain::Mud, grid.terrain(TitleCoords { x: 3, y: 0 }));
+        assert_eq!(Terrain::Water, grid.terrain(TitleCoords { x: 4, y: 0 }));
+        assert_eq!(
+            "type octile\nheight 2\nwidth 5\nmap\n.@@SW\n...@.\n",
+            grid.to_movingai()
+        );
+
+        let hex = "type hex\nheight 1\nwidth 2\nmap\n.@\n";
+        let hex_grid = Grid::from_movingai(hex).unwrap();
+        assert_eq!(Neighbourhood::Hex, hex_grid.neighbourhood());
+        assert_eq!(hex, hex_grid.to_movingai());
+
+        // Every movement is loaded back
+        for neighbourhood in Neighbourhood::ALL {
+            let mut grid = grid.clone();
+            grid.set_neighbourhood(neighbourhood);
+            let loaded = Grid::from_movingai(&grid.to_movingai()).unwrap();
+            assert_eq!(neighbourhood, loaded.neighbourhood());
+            assert_eq!(grid.to_ascii(), loaded.to_ascii());
+        }
+        let mut four_way = grid.clone();
+        four_way.set_neighbourhood(Neighbourhood::Four);
+        assert!(four_way
+            .to_movingai()
+            .starts_with("type octile\nheight 2\nwidth 5\nmovement four\nmap\n"));
+        assert!(matches!(
+            Grid::from_movingai("type octile\nmovement six\nheight 1\nwidth 1\nmap\n.\n"),
+            Err(MapFileError::InvalidHeader(_))
+        ));
+
+        assert!(matches!(
+            Grid::from_movingai("type octile\nheight 3\nwidth 5\nmap\n.....\n"),
+            Err(MapFileError::InvalidSize)
+        ));
+        assert!(matches!(
+            Grid::from_movingai(".....\n"),
+            Err(MapFileError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn save_and_load_file() {
+        let grid = Grid::from_ascii("S.#\n.mG\n").unwrap();
+        let ascii_path = std::env::temp_dir().join("r_path_finder_map_file_test.txt");
+        let movingai_path = std::env::temp_dir().join("r_path_finder_map_file_test.map");
+
+        grid.save(&ascii_path).unwrap();
+        grid.save(&movingai_path).unwrap();
+
+        let ascii_grid = Grid::load(&ascii_path).unwrap();
+        let movingai_grid = Grid::load(&movingai_path).unwrap();
+
+        assert_eq!(grid.to_ascii(), ascii_grid.to_ascii());
+        assert_eq!("..#\n.m.\n", movingai_grid.to_ascii());
+        assert!(movingai_grid.start_title.is_none());
+        assert!(movingai_grid.is_obstacle(TitleCoords { x: 2, y: 0 }));
+        assert_eq!(
+            Terrain::Mud,
+            movingai_grid.terrain(TitleCoords { x: 1, y: 1 })
+        );
+
+        fs::remove_file(ascii_path).unwrap();
+        fs::remove_file(movingai_path).unwrap();
+        assert!(matches!(
+            Grid::load("missing.map"),
+            Err(MapFileError::Io(_))
+        ));
+    }
+}
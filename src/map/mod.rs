@@ -1,21 +1,231 @@
+//! # Map
+//! Grid of titles the path-finding algorithms work on.
+//!
+//! The grid can be created by the number of columns and rows with [`grid::Grid::builder`]
+//! and edited by [`TitleCoords`], independently of the screen coordinates.
+pub mod builder;
 pub mod grid;
+pub mod hex;
+pub mod map_file;
+pub mod overlay;
+use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 /// # TitleCoords
 /// Helper structure to store the location of title in the grid space (x,y)
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct TitleCoords {
     pub x: usize,
     pub y: usize,
 }
 
+/// # MapError
+/// Errors reported when the grid is edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapError {
+    /// The title coordinates are outside the grid
+    OutOfBounds,
+    /// The title is already taken by the start, goal or obstacle
+    TitleOccupied,
+}
+
 /// # Title
-/// Title type
-#[derive(PartialEq, Debug)]
+/// Title type - static content of the map
+#[derive(Clone, PartialEq, Debug)]
 pub enum Title {
-    Normal { was_visited: bool },
+    Normal,
     Start,
     End,
     Obstacle,
-    Path,
+}
+
+/// # SearchState
+/// Per-search visualization state of the title kept in the [`overlay::SearchOverlay`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SearchState {
+    Unvisited,
+    Visited,
     Process,
+    Path,
+    /// Title found by the jump of Jump Point Search
+    JumpPoint,
+    /// Title explored by the backward frontier of the bidirectional search
+    BackwardVisited,
+    /// Title waiting in the backward frontier of the bidirectional search
+    BackwardProcess,
+}
+
+/// # Terrain
+/// Terrain type of the title. Every terrain has its own movement cost
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Terrain {
+    Plain = 0,
+    Grass = 1,
+    Mud = 2,
+    Water = 3,
+    Road = 4,
+}
+
+impl Terrain {
+    pub const ALL: [Terrain; 5] = [
+        Terrain::Plain,
+        Terrain::Grass,
+        Terrain::Mud,
+        Terrain::Water,
+        Terrain::Road,
+    ];
+
+    /// # default_cost
+    /// The movement cost to enter the title with this terrain
+    pub fn default_cost(&self) -> f64 {
+        match self {
+            Terrain::Road => 0.5,
+            Terrain::Plain => 1.0,
+            Terrain::Grass => 2.0,
+            Terrain::Mud => 5.0,
+            Terrain::Water => 10.0,
+        }
+    }
+
+    /// # name
+    /// Terrain name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Terrain::Plain => "Plain",
+            Terrain::Grass => "Grass",
+            Terrain::Mud => "Mud",
+            Terrain::Water => "Water",
+            Terrain::Road => "Road",
+        }
+    }
+}
+
+/// # Neighbourhood
+/// Titles reachable in one move
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Neighbourhood {
+    /// Up, down, left and right
+    Four,
+    /// Four straight and four diagonal moves
+    Eight,
+    /// Diagonal moves are not allowed to cut the corner of an obstacle
+    EightNoCornerCutting,
+    /// Six neighbours of the hexagonal title, see [`hex`]
+    Hex,
+}
+
+impl Neighbourhood {
+    pub const ALL: [Neighbourhood; 4] = [
+        Neighbourhood::Four,
+        Neighbourhood::Eight,
+        Neighbourhood::EightNoCornerCutting,
+        Neighbourhood::Hex,
+    ];
+
+    /// # matching_metric
+    /// Distance metric which keeps A* admissible for the neighbourhood
+    pub fn matching_metric(&self) -> DistanceMetric {
+        match self {
+            Neighbourhood::Four => DistanceMetric::Manhattan,
+            Neighbourhood::Eight | Neighbourhood::EightNoCornerCutting => DistanceMetric::Octile,
+            Neighbourhood::Hex => DistanceMetric::Hex,
+        }
+    }
+
+    /// # name
+    /// Neighbourhood name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Neighbourhood::Four => "4-way",
+            Neighbourhood::Eight => "8-way",
+            Neighbourhood::EightNoCornerCutting => "8-way, no corner cutting",
+            Neighbourhood::Hex => "hex",
+        }
+    }
+}
+
+/// # DistanceMetric
+/// Distance between two titles used as the grid heuristic
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum DistanceMetric {
+    /// Sum of the straight moves - exact for 4-way movement
+    Manhattan,
+    /// Straight and √2 diagonal moves - exact for 8-way movement
+    Octile,
+    /// Number of king moves - diagonal moves are as cheap as straight ones
+    Chebyshev,
+    /// Straight line distance - admissible for every movement
+    Euclidean,
+    /// Number of moves between hexagonal titles - exact for the hex grid
+    Hex,
+    /// No estimation - A* behaves like Dijkstra
+    Zero,
+}
+
+impl DistanceMetric {
+    pub const ALL: [DistanceMetric; 6] = [
+        DistanceMetric::Manhattan,
+        DistanceMetric::Euclidean,
+        DistanceMetric::Octile,
+        DistanceMetric::Chebyshev,
+        DistanceMetric::Hex,
+        DistanceMetric::Zero,
+    ];
+
+    /// # distance
+    /// Distance from one title to the other
+    pub fn distance(&self, from: TitleCoords, to: TitleCoords) -> f64 {
+        let dx = from.x.abs_diff(to.x) as f64;
+        let dy = from.y.abs_diff(to.y) as f64;
+        match self {
+            DistanceMetric::Manhattan => dx + dy,
+            DistanceMetric::Octile => dx.max(dy) + (std::f64::consts::SQRT_2 - 1.0) * dx.min(dy),
+            DistanceMetric::Chebyshev => dx.max(dy),
+            DistanceMetric::Euclidean => dx.hypot(dy),
+            DistanceMetric::Hex => hex::distance(from, to),
+            DistanceMetric::Zero => 0.0,
+        }
+    }
+
+    /// # name
+    /// Metric name
+    pub fn name(&self) -> &'static str {
+        match self {
+            DistanceMetric::Manhattan => "Manhattan",
+            DistanceMetric::Octile => "Octile",
+            DistanceMetric::Chebyshev => "Chebyshev",
+            DistanceMetric::Euclidean => "Euclidean",
+            DistanceMetric::Hex => "Hex",
+            DistanceMetric::Zero => "Zero",
+        }
+    }
+}
+
+/// # line_titles
+/// Titles crossed by the straight line between the centres of two titles, both included.
+///
+/// When the line passes exactly through the corner of titles, it steps diagonally
+pub fn line_titles(from: TitleCoords, to: TitleCoords) -> Vec<TitleCoords> {
+    let (dx, dy) = (to.x as i64 - from.x as i64, to.y as i64 - from.y as i64);
+    let (steps_x, steps_y) = (dx.abs(), dy.abs());
+    let (mut x, mut y) = (from.x as i64, from.y as i64);
+    let (mut ix, mut iy) = (0, 0);
+    let mut titles = vec![from];
+
+    while ix < steps_x || iy < steps_y {
+        // Compare where the line leaves the current title: through the side or the corner
+        let decision = (1 + 2 * ix) * steps_y - (1 + 2 * iy) * steps_x;
+        if decision <= 0 {
+            x += dx.signum();
+            ix += 1;
+        }
+        if decision >= 0 {
+            y += dy.signum();
+            iy += 1;
+        }
+        titles.push(TitleCoords {
+            x: x as usize,
+            y: y as usize,
+        });
+    }
+    titles
 }
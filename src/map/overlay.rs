@@ -0,0 +1,131 @@
+use super::{SearchState, TitleCoords};
+use serde::{Deserialize, Serialize};
+
+/// # TitleChange
+/// Search state of the title changed by the algorithm, e.g. to replay the search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TitleChange {
+    pub title: TitleCoords,
+    pub before: SearchState,
+    pub after: SearchState,
+}
+
+/// # SearchOverlay
+/// Per-search visualization layer drawn over the map.
+///
+/// Algorithms mark the overlay only, so the map (start, goal, obstacles and terrain)
+/// stays untouched and can be searched again by a different algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOverlay {
+    columns: u32,
+    rows: u32,
+    states: Vec<Vec<SearchState>>,
+    /// Turning points of the path drawn as straight segments
+    waypoints: Vec<TitleCoords>,
+}
+
+impl SearchOverlay {
+    /// # new
+    /// Create an empty overlay for the grid with given number of columns and rows
+    pub fn new(columns: u32, rows: u32) -> Self {
+        Self {
+            columns,
+            rows,
+            states: vec![vec![SearchState::Unvisited; rows as usize]; columns as usize],
+            waypoints: Vec::new(),
+        }
+    }
+
+    /// # columns
+    /// Number of columns of the overlay
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// # rows
+    /// Number of rows of the overlay
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// # get
+    /// Search state of the title. Titles outside the overlay are unvisited
+    pub fn get(&self, title_coords: TitleCoords) -> SearchState {
+        self.states
+            .get(title_coords.x)
+            .and_then(|column| column.get(title_coords.y))
+            .copied()
+            .unwrap_or(SearchState::Unvisited)
+    }
+
+    /// # set
+    /// Set search state of the title
+    pub fn set(&mut self, title_coords: TitleCoords, state: SearchState) {
+        if let Some(title_state) = self
+            .states
+            .get_mut(title_coords.x)
+            .and_then(|column| column.get_mut(title_coords.y))
+        {
+            *title_state = state;
+        }
+    }
+
+    /// # waypoints
+    /// Turning points of the path drawn as straight segments. Empty for paths drawn by titles
+    pub fn waypoints(&self) -> &[TitleCoords] {
+        &self.waypoints
+    }
+
+    /// # set_waypoints
+    /// Set turning points of the path drawn as straight segments
+    pub fn set_waypoints(&mut self, waypoints: Vec<TitleCoords>) {
+        self.waypoints = waypoints;
+    }
+
+    /// # count
+    /// Number of titles in given search state
+    pub fn count(&self, state: SearchState) -> usize {
+        self.states
+            .iter()
+            .flatten()
+            .filter(|title_state| **title_state == state)
+            .count()
+    }
+
+    /// # clear
+    /// Mark every title as unvisited
+    pub fn clear(&mut self) {
+        for column in self.states.iter_mut() {
+            for title_state in column.iter_mut() {
+                *title_state = SearchState::Unvisited;
+            }
+        }
+        self.waypoints.clear();
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn overlay_states() {
+        let mut overlay = SearchOverlay::new(4, 2);
+        let visited = TitleCoords { x: 3, y: 1 };
+        let out_of_bounds = TitleCoords { x: 1, y: 2 };
+
+        overlay.set(visited, SearchState::Visited);
+        overlay.set(TitleCoords { x: 0, y: 0 }, SearchState::Path);
+        overlay.set(out_of_bounds, SearchState::Visited);
+        overlay.set_waypoints(vec![visited, TitleCoords { x: 0, y: 0 }]);
+
+        assert_eq!(SearchState::Visited, overlay.get(visited));
+        assert_eq!(SearchState::Unvisited, overlay.get(out_of_bounds));
+        assert_eq!(1, overlay.count(SearchState::Visited));
+        assert_eq!(6, overlay.count(SearchState::Unvisited));
+        assert_eq!(2, overlay.waypoints().len());
+
+        overlay.clear();
+        assert_eq!(SearchOverlay::new(4, 2), overlay);
+    }
+}